@@ -1,10 +1,12 @@
 mod resolution;
 
-use std::fmt;
+use std::{collections::BTreeSet, fmt};
 
-pub(crate) use resolution::{address_to_location, location_to_address};
+pub(crate) use resolution::{
+    address_to_location, file_lines, location_to_address, location_to_addresses,
+};
 
-use crate::file::DebugFile;
+use crate::{file::DebugFile, SymbolName};
 
 #[derive(Clone, PartialEq, Eq, Hash)]
 pub struct FunctionAddressInfo {
@@ -47,6 +49,40 @@ impl fmt::Debug for FunctionAddressInfo {
 
 impl FunctionAddressInfo {}
 
+/// One of (potentially many) addresses that a source location resolves to
+#[derive(Debug, Clone, PartialEq, Eq, Hash, salsa::Update)]
+pub struct LocationAddress {
+    /// Absolute address in the binary
+    pub address: u64,
+    /// Symbol of the concrete function containing the address
+    pub function: SymbolName,
+    /// Name of the containing function, including any generic arguments
+    /// e.g. `my_crate::helper<u32>`
+    pub function_name: String,
+    /// Whether the address is part of an inlined copy of the source location
+    pub inlined: bool,
+}
+
+/// The lines of a source file that have code associated with them,
+/// along with the functions declared in the file
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, salsa::Update)]
+pub struct FileLines {
+    /// Lines that start a statement, i.e. lines where a breakpoint can be set
+    pub lines: BTreeSet<u64>,
+    /// Functions declared in the file, sorted by start line
+    pub functions: Vec<FunctionLines>,
+}
+
+/// The span of lines covered by a function
+#[derive(Debug, Clone, PartialEq, Eq, Hash, salsa::Update)]
+pub struct FunctionLines {
+    pub function: SymbolName,
+    /// The line the function is declared on
+    pub start_line: u64,
+    /// The last line in the function that has code associated with it
+    pub end_line: u64,
+}
+
 // Define the Node for the Interval Tree
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 struct Node {
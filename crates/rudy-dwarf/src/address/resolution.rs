@@ -1,12 +1,18 @@
 //! Address to location and location to address resolution
 
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::Path,
+};
+
 use itertools::Itertools;
 
+use super::{FileLines, FunctionAddressInfo, FunctionLines, LocationAddress};
 use crate::{
-    die::{file_entry_to_path, navigation::parse_roots},
+    die::{file_entry_to_path, navigation::parse_roots, position, UnitRef},
     file::{DebugFile, SourceFile, SourceLocation},
     function::{FunctionData, FunctionIndex},
-    DwarfDb,
+    Die, DwarfDb, SymbolName,
 };
 
 /// Convert an address to source location within a compilation unit
@@ -111,29 +117,7 @@ pub fn location_to_address(
 
         let header = line_program.header();
 
-        let Some(target_file_idx) =
-            header
-                .clone()
-                .file_names()
-                .iter()
-                .enumerate()
-                .find_map(|(idx, f)| {
-                    let Some(file_path) = file_entry_to_path(db, f, &unit_ref) else {
-                        tracing::debug!("failed to convert file entry to path");
-                        return None;
-                    };
-                    tracing::trace!(
-                        "checking file `{}` against target `{}`",
-                        file_path.display(),
-                        file.display()
-                    );
-                    if &file_path == file {
-                        Some(idx as u64 + 1) // +1 because file indices are 1-based
-                    } else {
-                        None
-                    }
-                })
-        else {
+        let Some(target_file_idx) = find_file_index(db, header, &unit_ref, file) else {
             tracing::trace!(
                 "could not find target file `{}` in line program for {:#x} in file {}",
                 file.display(),
@@ -219,3 +203,297 @@ pub fn location_to_address(
     }
     closest_address.map(|addr| (addr, min_line_distance))
 }
+
+/// Find the (1-based) index of `file` in the line program header
+fn find_file_index(
+    db: &dyn DwarfDb,
+    header: &gimli::LineProgramHeader<crate::file::DwarfReader>,
+    unit_ref: &UnitRef<'_>,
+    file: &Path,
+) -> Option<u64> {
+    header.file_names().iter().enumerate().find_map(|(idx, f)| {
+        let Some(file_path) = file_entry_to_path(db, f, unit_ref) else {
+            tracing::debug!("failed to convert file entry to path");
+            return None;
+        };
+        tracing::trace!(
+            "checking file `{}` against target `{}`",
+            file_path.display(),
+            file.display()
+        );
+        if file_path == file {
+            Some(idx as u64 + 1) // +1 because file indices are 1-based
+        } else {
+            None
+        }
+    })
+}
+
+/// Convert a source location to _every_ address it resolves to within a debug file
+///
+/// Generic functions have one copy of their code per monomorphization, and
+/// `#[inline]` functions may have been copied into many callers. Whereas
+/// [`location_to_address`] only returns the closest single match, this returns
+/// one address per containing function and per inlined instance.
+///
+/// If no rows match the target line exactly, the closest following line is used.
+///
+/// Returns the matching addresses, along with the line distance from the query.
+pub fn location_to_addresses(
+    db: &dyn DwarfDb,
+    debug_file: DebugFile,
+    function_index: &FunctionIndex<'_>,
+    query: &SourceLocation,
+) -> Option<(Vec<LocationAddress>, u64)> {
+    let file = &query.file.path;
+    let target_line = query.line;
+
+    let mut min_line_distance = u64::MAX;
+    // (relative address, containing function) for rows at `min_line_distance`
+    let mut candidates: Vec<(u64, &FunctionAddressInfo)> = vec![];
+
+    tracing::info!(
+        "searching for all addresses of `{}:{target_line}` in `{}`",
+        file.display(),
+        debug_file.name(db)
+    );
+
+    for root in parse_roots(db, debug_file) {
+        if !root.files(db).contains(&query.file) {
+            continue;
+        }
+
+        let cu = root.cu(db);
+        let Some(unit_ref) = cu.unit_ref(db) else {
+            continue;
+        };
+
+        let Some(line_program) = unit_ref.line_program.clone() else {
+            continue;
+        };
+
+        let Some(target_file_idx) = find_file_index(db, line_program.header(), &unit_ref, file)
+        else {
+            continue;
+        };
+
+        let mut rows = line_program.clone().rows();
+
+        loop {
+            let row = match rows.next_row() {
+                Ok(Some((_, row))) => row,
+                Ok(None) => break,
+                Err(e) => {
+                    tracing::error!("Failed to parse line program: {e}");
+                    break;
+                }
+            };
+
+            if row.end_sequence() || !row.is_stmt() || row.file_index() != target_file_idx {
+                continue;
+            }
+
+            let Some(line_diff) = row
+                .line()
+                .and_then(|line| line.get().checked_sub(target_line))
+            else {
+                // no line info, or the line is _before_ the target line
+                continue;
+            };
+
+            if line_diff > min_line_distance {
+                continue;
+            }
+            if line_diff < min_line_distance {
+                min_line_distance = line_diff;
+                candidates.clear();
+            }
+
+            let address = row.address();
+            for function in function_index.by_address(db).query_address(address, false) {
+                candidates.push((address, function));
+            }
+        }
+    }
+
+    if candidates.is_empty() {
+        return None;
+    }
+
+    // group the candidates by containing function and inlined instance,
+    // keeping the lowest address of each
+    let mut matches: BTreeMap<(u64, Option<Die>), LocationAddress> = BTreeMap::new();
+
+    for (relative_address, function) in candidates {
+        let absolute_address = function.absolute_start + relative_address - function.relative_start;
+
+        let declaration_die = function_index
+            .by_symbol_name(db)
+            .get(&function.name)
+            .map(|entry| entry.data(db).declaration_die);
+
+        let inlined_instance =
+            declaration_die.and_then(|die| find_inlined_instance(db, die, relative_address));
+
+        let function_name = declaration_die
+            .and_then(|die| die.name(db).ok())
+            .map(|name| {
+                if function.name.module_path.is_empty() {
+                    name
+                } else {
+                    format!("{}::{name}", function.name.module_path.join("::"))
+                }
+            })
+            .unwrap_or_else(|| function.name.to_string());
+
+        let location_address = LocationAddress {
+            address: absolute_address,
+            function: function.name.clone(),
+            function_name,
+            inlined: inlined_instance.is_some(),
+        };
+
+        matches
+            .entry((function.absolute_start, inlined_instance))
+            .and_modify(|existing| {
+                if absolute_address < existing.address {
+                    *existing = location_address.clone();
+                }
+            })
+            .or_insert(location_address);
+    }
+
+    let addresses = matches.into_values().sorted_by_key(|m| m.address).collect();
+
+    Some((addresses, min_line_distance))
+}
+
+/// Collects the lines of `file` that have code, and the functions declared in it
+///
+/// Only rows that start a statement are considered, since those are
+/// the locations where a breakpoint can be set.
+pub fn file_lines(
+    db: &dyn DwarfDb,
+    debug_file: DebugFile,
+    function_index: &FunctionIndex<'_>,
+    file: &SourceFile,
+) -> FileLines {
+    let mut lines = BTreeSet::new();
+    // (first, last) line of the rows in `file`, per containing function
+    let mut function_rows: BTreeMap<&SymbolName, (u64, u64)> = BTreeMap::new();
+
+    for root in parse_roots(db, debug_file) {
+        if !root.files(db).contains(file) {
+            continue;
+        }
+
+        let Some(unit_ref) = root.cu(db).unit_ref(db) else {
+            continue;
+        };
+
+        let Some(line_program) = unit_ref.line_program.clone() else {
+            continue;
+        };
+
+        let Some(target_file_idx) =
+            find_file_index(db, line_program.header(), &unit_ref, &file.path)
+        else {
+            continue;
+        };
+
+        let mut rows = line_program.clone().rows();
+
+        loop {
+            let row = match rows.next_row() {
+                Ok(Some((_, row))) => row,
+                Ok(None) => break,
+                Err(e) => {
+                    tracing::error!("Failed to parse line program: {e}");
+                    break;
+                }
+            };
+
+            if row.end_sequence() || !row.is_stmt() || row.file_index() != target_file_idx {
+                continue;
+            }
+
+            let Some(line) = row.line().map(|l| l.get()) else {
+                continue;
+            };
+            lines.insert(line);
+
+            for function in function_index
+                .by_address(db)
+                .query_address(row.address(), false)
+            {
+                function_rows
+                    .entry(&function.name)
+                    .and_modify(|(first, last)| {
+                        *first = (*first).min(line);
+                        *last = (*last).max(line);
+                    })
+                    .or_insert((line, line));
+            }
+        }
+    }
+
+    let functions = function_rows
+        .into_iter()
+        .filter_map(|(name, (first_line, last_line))| {
+            let declaration_die = function_index
+                .by_symbol_name(db)
+                .get(name)?
+                .data(db)
+                .declaration_die;
+            let start_line = match position(db, declaration_die) {
+                Ok(Some(location)) if location.file == *file => location.line.min(first_line),
+                // the function is declared in another file, so these
+                // rows are code that was inlined from this file
+                Ok(Some(_)) => return None,
+                _ => first_line,
+            };
+            Some(FunctionLines {
+                function: name.clone(),
+                start_line,
+                end_line: last_line,
+            })
+        })
+        .sorted_by(|a, b| (a.start_line, &a.function).cmp(&(b.start_line, &b.function)))
+        .collect();
+
+    FileLines { lines, functions }
+}
+
+/// Find the innermost `DW_TAG_inlined_subroutine` beneath `die` that
+/// contains the (relative) address, if any
+fn find_inlined_instance(db: &dyn DwarfDb, die: Die, relative_address: u64) -> Option<Die> {
+    for child in die.children(db).ok()? {
+        let tag = child.tag(db);
+        if !matches!(
+            tag,
+            gimli::DW_TAG_inlined_subroutine | gimli::DW_TAG_lexical_block
+        ) {
+            continue;
+        }
+
+        let ranges = child.address_ranges(db).unwrap_or_default();
+        let contains_address = ranges
+            .iter()
+            .any(|(start, end)| (*start..*end).contains(&relative_address));
+
+        // lexical blocks without ranges may still contain inlined subroutines
+        if !ranges.is_empty() && !contains_address {
+            continue;
+        }
+
+        if let Some(inner) = find_inlined_instance(db, child, relative_address) {
+            return Some(inner);
+        }
+
+        if contains_address && tag == gimli::DW_TAG_inlined_subroutine {
+            return Some(child);
+        }
+    }
+
+    None
+}
@@ -5,7 +5,7 @@ pub(crate) mod navigation;
 pub(crate) mod unit;
 pub(crate) mod utils;
 
-use std::fmt;
+use std::{collections::BTreeMap, fmt};
 
 use anyhow::Context;
 pub(crate) use cu::CompilationUnitId;
@@ -194,6 +194,12 @@ impl Die {
         self.string_attr(db, gimli::DW_AT_name)
     }
 
+    /// The size of an address in this entry's compilation unit, i.e. the
+    /// pointer size of the target it was compiled for
+    pub fn address_size(&self, db: &dyn DwarfDb) -> Result<usize> {
+        Ok(self.unit_ref(db)?.encoding().address_size as usize)
+    }
+
     // GROUP 3: Attribute Access (Keep - building blocks for other operations)
 
     pub(crate) fn get_member(&self, db: &dyn DwarfDb, name: &str) -> Result<Die> {
@@ -262,6 +268,20 @@ impl Die {
             .into_die_result(db, self)
     }
 
+    /// Get the address ranges covered by this DIE, relative to the debug file
+    pub(crate) fn address_ranges(&self, db: &dyn DwarfDb) -> Result<Vec<(u64, u64)>> {
+        self.with_entry_and_unit(db, |entry, unit_ref| {
+            let mut ranges = vec![];
+            let mut iter = unit_ref.die_ranges(entry)?;
+            while let Some(range) = iter.next()? {
+                ranges.push((range.begin, range.end));
+            }
+            Ok::<_, gimli::Error>(ranges)
+        })?
+        .context("Failed to parse DIE address ranges")
+        .into_die_result(db, self)
+    }
+
     pub(crate) fn print(&self, db: &dyn DwarfDb) -> String {
         self.with_entry_and_unit(db, |entry, unit_ref| {
             self.format_with_location(db, pretty_print_die_entry(entry, unit_ref))
@@ -345,3 +365,63 @@ pub(crate) fn position(db: &dyn DwarfDb, entry: Die) -> Result<Option<SourceLoca
         None,
     )))
 }
+
+/// Get the column at which the code of a function starts on the given line
+///
+/// Subprograms only record their declaration line, so we take the leftmost
+/// column of the line table rows for that line within the function. For a
+/// closure, this is the start of its body.
+pub(crate) fn first_column_on_line(db: &dyn DwarfDb, entry: Die, line: u64) -> Result<Option<u64>> {
+    /// Indexes the line program of a compilation unit by line, listing the
+    /// address and column of each row that has one
+    ///
+    /// Tracked so that the line program is only read once per unit, rather
+    /// than once per function we look up.
+    #[salsa::tracked(returns(ref))]
+    fn line_columns(
+        db: &dyn DwarfDb,
+        file: DebugFile,
+        cu_offset: UnitSectionOffset<usize>,
+    ) -> BTreeMap<u64, Vec<(u64, u64)>> {
+        let mut columns: BTreeMap<u64, Vec<(u64, u64)>> = BTreeMap::new();
+        let Some(line_program) = unit::get_unit_ref(db, file, cu_offset)
+            .and_then(|unit_ref| unit_ref.line_program.clone())
+        else {
+            return columns;
+        };
+
+        let mut rows = line_program.rows();
+        loop {
+            let row = match rows.next_row() {
+                Ok(Some((_, row))) => row,
+                Ok(None) => break,
+                Err(e) => {
+                    tracing::warn!("Failed to read line program row: {e}");
+                    break;
+                }
+            };
+            if row.end_sequence() {
+                continue;
+            }
+            if let (Some(line), gimli::ColumnType::Column(column)) = (row.line(), row.column()) {
+                columns
+                    .entry(line.get())
+                    .or_default()
+                    .push((row.address(), column.get()));
+            }
+        }
+        columns
+    }
+
+    let Some(&(start, end)) = entry.address_ranges(db)?.first() else {
+        return Ok(None);
+    };
+
+    Ok(line_columns(db, entry.file, entry.cu_offset)
+        .get(&line)
+        .into_iter()
+        .flatten()
+        .filter(|(address, _)| (start..end).contains(address))
+        .map(|&(_, column)| column)
+        .min())
+}
@@ -1,12 +1,20 @@
-use std::{collections::BTreeSet, path::PathBuf};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
 
 use gimli::Reader as _;
 
 use crate::{
     die::{
-        cu::is_rust_cu, file_entry_to_path, navigation::get_roots, utils::pretty_print_die_entry,
+        cu::is_rust_cu,
+        file_entry_to_path,
+        navigation::get_roots,
+        utils::{get_string_attr, pretty_print_die_entry},
+        UnitRef,
     },
-    DebugFile, DwarfDb, SourceFile,
+    file::{RawDie, SourceOrigin},
+    DebugFile, Die, DwarfDb, SourceFile,
 };
 
 /// Build an index of all types + functions (using fully qualified names
@@ -65,3 +73,76 @@ pub fn index_debug_file_sources(
 
     (compile_dirs, sources)
 }
+
+/// Finds the local crates compiled into a debug file, keyed by the
+/// directory of their root source file (e.g. `/home/me/app/src` for
+/// `src/main.rs`)
+///
+/// rustc names compilation units after the crate root, relative to the
+/// compilation directory, followed by `/@/` and the codegen unit name. The
+/// crate name is that of the top-level namespace holding a function declared
+/// under the root's directory.
+#[salsa::tracked(returns(ref))]
+pub fn index_local_crates(db: &dyn DwarfDb, debug_file: DebugFile) -> BTreeMap<PathBuf, String> {
+    let mut crates = BTreeMap::new();
+
+    let roots = get_roots(db, debug_file);
+    for (unit_offset, unit_ref) in &roots {
+        let mut entries = unit_ref.entries();
+        let Some((_, root)) = entries.next_dfs().ok().flatten() else {
+            continue;
+        };
+        if !is_rust_cu(root, unit_ref) {
+            continue;
+        }
+        let Some(root_dir) = crate_root_dir(db, root, unit_ref) else {
+            continue;
+        };
+        // a crate is split into many codegen units
+        if crates.contains_key(&root_dir)
+            || SourceFile::new(root_dir.clone()).origin().0 != SourceOrigin::Local
+        {
+            continue;
+        }
+
+        let unit = Die::new(debug_file, *unit_offset, root.offset());
+        let namespaces = unit.children(db).unwrap_or_default();
+        let crate_name = namespaces
+            .into_iter()
+            .filter(|child| child.tag(db) == gimli::DW_TAG_namespace)
+            .find(|namespace| declares_function_in(db, *namespace, &root_dir))
+            .and_then(|namespace| namespace.name(db).ok());
+        if let Some(crate_name) = crate_name {
+            crates.insert(root_dir, crate_name);
+        }
+    }
+
+    crates
+}
+
+/// The directory of the root source file a compilation unit was built from
+fn crate_root_dir(db: &dyn DwarfDb, root: &RawDie<'_>, unit_ref: &UnitRef<'_>) -> Option<PathBuf> {
+    let name = get_string_attr(root, gimli::DW_AT_name, unit_ref).ok()??;
+    let root_file = name
+        .split_once("/@/")
+        .map_or(name.as_str(), |(file, _)| file);
+    let comp_dir = unit_ref.comp_dir.as_ref()?.to_string().ok()?;
+    let path = db.remap_path(&Path::new(comp_dir.as_ref()).join(root_file));
+    path.parent().map(Path::to_path_buf)
+}
+
+/// Whether a function declared under `dir` can be found within `entry`
+fn declares_function_in(db: &dyn DwarfDb, entry: Die, dir: &Path) -> bool {
+    entry
+        .children(db)
+        .unwrap_or_default()
+        .into_iter()
+        .any(|child| match child.tag(db) {
+            gimli::DW_TAG_subprogram => crate::die::position(db, child)
+                .ok()
+                .flatten()
+                .is_some_and(|location| location.file.path.starts_with(dir)),
+            gimli::DW_TAG_namespace => declares_function_in(db, child, dir),
+            _ => false,
+        })
+}
@@ -1,6 +1,6 @@
 use std::{
     fmt::{self, Debug},
-    path::PathBuf,
+    path::{Component, Path, PathBuf},
     sync::Arc,
 };
 
@@ -10,7 +10,7 @@ use object::{read::archive::ArchiveFile, Object};
 pub(crate) mod index;
 pub(crate) mod loader;
 
-pub use index::index_debug_file_sources;
+pub use index::{index_debug_file_sources, index_local_crates};
 pub(crate) use loader::{Dwarf, DwarfReader, RawDie};
 
 use crate::DwarfDb;
@@ -56,6 +56,90 @@ pub fn detect_cargo_root() -> Option<PathBuf> {
     cargo_detect_workspace().or_else(cwd_detect_workspace)
 }
 
+/// The standard library sources of a toolchain's `rust-src` component
+///
+/// The standard library's debug info refers to its sources as
+/// `/rustc/<commit>/...` paths, which are remapped into `dir` when `<commit>`
+/// matches the toolchain's, so that they can be matched against real files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RustSrc {
+    /// The `lib/rustlib/src/rust` directory of the toolchain
+    pub dir: PathBuf,
+    /// The commit hash of the toolchain's `rustc`, as printed by `rustc -vV`
+    pub commit_hash: String,
+}
+
+impl RustSrc {
+    /// Finds the `rust-src` component of the toolchain that runs as `$RUSTC`
+    /// (or `rustc`), returning `None` if it isn't installed
+    pub fn detect() -> Option<Self> {
+        let rustc = std::env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+        let run = |args: &[&str]| -> Option<String> {
+            let output = std::process::Command::new(&rustc)
+                .args(args)
+                .output()
+                .ok()?;
+            if !output.status.success() {
+                return None;
+            }
+            String::from_utf8(output.stdout).ok()
+        };
+
+        let sysroot = PathBuf::from(run(&["--print", "sysroot"])?.trim());
+        let commit_hash = run(&["-vV"])?
+            .lines()
+            .find_map(|line| line.strip_prefix("commit-hash: "))?
+            .trim()
+            .to_string();
+        Self::from_sysroot(&sysroot, commit_hash)
+    }
+
+    /// The `rust-src` component of the toolchain at `sysroot`, whose `rustc`
+    /// was built from `commit_hash`, returning `None` if it isn't installed
+    pub fn from_sysroot(sysroot: &Path, commit_hash: impl Into<String>) -> Option<Self> {
+        let dir = sysroot.join("lib").join("rustlib").join("src").join("rust");
+        if !dir.join("library").is_dir() {
+            tracing::debug!(
+                "rust-src component not found in sysroot {}",
+                sysroot.display()
+            );
+            return None;
+        }
+        tracing::debug!("using rust-src component at {}", dir.display());
+        Some(Self {
+            dir,
+            commit_hash: commit_hash.into(),
+        })
+    }
+
+    /// Remaps a `/rustc/<commit>/...` path to the same path within `dir`
+    ///
+    /// Returns `None` if the path is not a `/rustc/<commit>/` path, or was
+    /// built by a different `rustc`, whose sources may not match ours.
+    pub fn remap(&self, path: &Path) -> Option<PathBuf> {
+        let mut components = path.components();
+        if components.next()? != Component::RootDir {
+            return None;
+        }
+        if components.next()?.as_os_str() != "rustc" {
+            return None;
+        }
+        let commit = components.next()?.as_os_str().to_str()?;
+        if !commit.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        if commit != self.commit_hash {
+            tracing::trace!(
+                "not remapping {}: built by rustc {commit}, but rust-src is from {}",
+                path.display(),
+                self.commit_hash
+            );
+            return None;
+        }
+        Some(self.dir.join(components.as_path()))
+    }
+}
+
 #[salsa::input(debug)]
 pub struct File {
     #[returns(ref)]
@@ -201,6 +285,18 @@ pub fn load<'db>(db: &'db dyn DwarfDb, file: File) -> Result<LoadedFile, Error>
     })
 }
 
+/// Where a source file comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub enum SourceOrigin {
+    /// Any file that is not a dependency or part of the standard library,
+    /// usually part of the current workspace
+    Local,
+    /// A dependency downloaded by cargo, from a registry or git
+    Registry,
+    /// The standard library
+    Std,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd, salsa::Update)]
 pub struct SourceFile {
     pub path: PathBuf,
@@ -223,6 +319,105 @@ impl SourceFile {
             .to_string();
         !self.path.starts_with(&current_dir)
     }
+
+    /// Classifies where this file comes from, along with the name of the
+    /// crate it belongs to if that can be derived from the path
+    ///
+    /// For the standard library this is the directory under `library/`, and
+    /// for dependencies it is the package directory without its version
+    /// (or git commit hash). The crate name of local files is not known.
+    pub fn origin(&self) -> (SourceOrigin, Option<String>) {
+        let components = self
+            .path
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>();
+        let component = |idx: usize| components.get(idx).map(|c| c.as_ref());
+
+        for (idx, c) in components.iter().enumerate() {
+            match c.as_ref() {
+                // `<sysroot>/lib/rustlib/src/rust/library/<crate>`
+                // or `/rustc/<commit>/library/<crate>`
+                "library"
+                    if idx >= 2
+                        && (component(idx - 1) == Some("rust")
+                            && component(idx - 2) == Some("src")
+                            || component(idx - 2) == Some("rustc")) =>
+                {
+                    return (
+                        SourceOrigin::Std,
+                        component(idx + 1).map(ToString::to_string),
+                    );
+                }
+                // `$CARGO_HOME/registry/src/<index>/<crate>-<version>`
+                "registry" if component(idx + 1) == Some("src") => {
+                    let name = component(idx + 3).map(|package| {
+                        package
+                            .match_indices('-')
+                            .find(|(i, _)| {
+                                package[i + 1..].starts_with(|c: char| c.is_ascii_digit())
+                            })
+                            .map_or(package, |(i, _)| &package[..i])
+                            .to_string()
+                    });
+                    return (SourceOrigin::Registry, name);
+                }
+                // `$CARGO_HOME/git/checkouts/<repo>-<hash>/<commit>`
+                "git" if component(idx + 1) == Some("checkouts") => {
+                    let name = component(idx + 2).map(|repo| {
+                        repo.rsplit_once('-')
+                            .filter(|(_, hash)| hash.chars().all(|c| c.is_ascii_hexdigit()))
+                            .map_or(repo, |(name, _)| name)
+                            .to_string()
+                    });
+                    return (SourceOrigin::Registry, name);
+                }
+                _ => {}
+            }
+        }
+
+        (SourceOrigin::Local, None)
+    }
+
+    /// Checks if this file's path ends with the provided (relative) path
+    ///
+    /// Matching is done by whole path components, so `main.rs` matches
+    /// `/project/src/main.rs`, but not `/project/src/domain.rs`.
+    pub fn matches_suffix(&self, query: &Path) -> bool {
+        let query = normalize_query(query);
+        !query.as_os_str().is_empty() && self.path.ends_with(query)
+    }
+
+    /// Fuzzy match of this file's path against the provided path
+    ///
+    /// The file names must match exactly, and the directories in `query` must
+    /// all appear in this file's path, in order. e.g. `my_crate/server.rs`
+    /// matches `/project/my_crate/src/server.rs`.
+    pub fn matches_fuzzy(&self, query: &Path) -> bool {
+        let query = normalize_query(query);
+        let (Some(file_name), Some(query_file_name)) = (self.path.file_name(), query.file_name())
+        else {
+            return false;
+        };
+        if file_name != query_file_name {
+            return false;
+        }
+
+        let mut parents = self.path.parent().into_iter().flat_map(Path::components);
+        query
+            .parent()
+            .into_iter()
+            .flat_map(Path::components)
+            .all(|query_component| parents.any(|c| c == query_component))
+    }
+}
+
+/// Strips any leading `./` from a query path
+fn normalize_query(query: &Path) -> PathBuf {
+    query
+        .components()
+        .filter(|c| !matches!(c, Component::CurDir))
+        .collect()
 }
 
 #[derive(Debug, Clone)]
@@ -280,3 +475,78 @@ impl From<gimli::Error> for Error {
         Error::Gimli(err)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::path::{Path, PathBuf};
+
+    use super::{RustSrc, SourceFile, SourceOrigin};
+
+    #[test]
+    fn test_remap_rustc_path() {
+        let rust_src = RustSrc {
+            dir: PathBuf::from("/home/user/.rustup/toolchains/stable/lib/rustlib/src/rust"),
+            commit_hash: "17067e9ac6d7e98f18d4d3b2f0e4b1d5b6f8a2c1".to_string(),
+        };
+        assert_eq!(
+            rust_src.remap(Path::new(
+                "/rustc/17067e9ac6d7e98f18d4d3b2f0e4b1d5b6f8a2c1/library/core/src/option.rs"
+            )),
+            Some(rust_src.dir.join("library/core/src/option.rs"))
+        );
+        // built by a different toolchain
+        assert_eq!(
+            rust_src.remap(Path::new(
+                "/rustc/29483883eed69d5fb4db01964cdf2af4d86e9cb2/library/core/src/option.rs"
+            )),
+            None
+        );
+        assert_eq!(
+            rust_src.remap(Path::new("/rustc/not-a-commit/library/lib.rs")),
+            None
+        );
+        assert_eq!(rust_src.remap(Path::new("/project/src/main.rs")), None);
+    }
+
+    #[test]
+    fn test_source_file_matching() {
+        let file = SourceFile::new(PathBuf::from("/project/my_crate/src/server.rs"));
+
+        assert!(file.matches_suffix(Path::new("server.rs")));
+        assert!(file.matches_suffix(Path::new("src/server.rs")));
+        assert!(file.matches_suffix(Path::new("./src/server.rs")));
+        assert!(!file.matches_suffix(Path::new("er.rs")));
+        assert!(!file.matches_suffix(Path::new("my_crate/server.rs")));
+
+        assert!(file.matches_fuzzy(Path::new("my_crate/server.rs")));
+        assert!(file.matches_fuzzy(Path::new("project/src/server.rs")));
+        assert!(!file.matches_fuzzy(Path::new("src/my_crate/server.rs")));
+        assert!(!file.matches_fuzzy(Path::new("my_crate/client.rs")));
+    }
+
+    #[test]
+    fn test_source_file_origin() {
+        let origin = |path: &str| SourceFile::new(PathBuf::from(path)).origin();
+
+        assert_eq!(
+            origin("/home/user/.rustup/toolchains/stable/lib/rustlib/src/rust/library/alloc/src/vec/mod.rs"),
+            (SourceOrigin::Std, Some("alloc".to_string()))
+        );
+        assert_eq!(
+            origin("/rustc/17067e9ac6d7e98f18d4d3b2f0e4b1d5b6f8a2c1/library/core/src/option.rs"),
+            (SourceOrigin::Std, Some("core".to_string()))
+        );
+        assert_eq!(
+            origin("/home/user/.cargo/registry/src/index.crates.io-6f17d22bba15001f/serde-json-1.0.140-rc.1/src/lib.rs"),
+            (SourceOrigin::Registry, Some("serde-json".to_string()))
+        );
+        assert_eq!(
+            origin("/home/user/.cargo/git/checkouts/salsa-e0b1ac1d4b3e8bca/a1b2c3d/src/lib.rs"),
+            (SourceOrigin::Registry, Some("salsa".to_string()))
+        );
+        assert_eq!(
+            origin("/project/my_crate/src/library/mod.rs"),
+            (SourceOrigin::Local, None)
+        );
+    }
+}
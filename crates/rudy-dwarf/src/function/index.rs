@@ -5,9 +5,12 @@ use std::collections::BTreeMap;
 use itertools::Itertools;
 
 use crate::{
-    address::{address_to_location, location_to_address, AddressTree, FunctionAddressInfo},
+    address::{
+        address_to_location, file_lines, location_to_address, location_to_addresses, AddressTree,
+        FileLines, FunctionAddressInfo, LocationAddress,
+    },
     die::utils::{get_string_attr, pretty_print_die_entry, to_range},
-    file::SourceLocation,
+    file::{SourceFile, SourceLocation},
     symbols::{RawSymbol, Symbol},
     visitor::{walk_file, DieVisitor, DieWalker, VisitorNode},
     DebugFile, Die, DwarfDb, SymbolName,
@@ -73,6 +76,28 @@ impl<'db> FunctionIndex<'db> {
     ) -> Option<(u64, u64)> {
         location_to_address(db, debug_file, self, location)
     }
+
+    /// Like [`Self::location_to_address`], but returns every address the location
+    /// resolves to (one per monomorphization and inlined copy), along with
+    /// the line distance from the requested location.
+    pub fn location_to_addresses(
+        &self,
+        db: &dyn DwarfDb,
+        debug_file: DebugFile,
+        location: &SourceLocation,
+    ) -> Option<(Vec<LocationAddress>, u64)> {
+        location_to_addresses(db, debug_file, self, location)
+    }
+
+    /// Gets the lines of `file` that have code, and the functions declared in it
+    pub fn file_lines(
+        &self,
+        db: &dyn DwarfDb,
+        debug_file: DebugFile,
+        file: &SourceFile,
+    ) -> FileLines {
+        file_lines(db, debug_file, self, file)
+    }
 }
 
 /// Visitor for building function index efficiently
@@ -13,6 +13,7 @@ pub mod function;
 pub mod modules;
 pub mod parser;
 pub mod symbols;
+pub mod trait_impls;
 pub mod types;
 pub mod visitor;
 
@@ -35,6 +36,9 @@ pub use types::{find_type_by_name, get_die_typename};
 #[salsa::db]
 pub trait DwarfDb: salsa::Database {
     /// Get source path remapping
+    ///
+    /// Applies the user-provided source map, and then remaps any remaining
+    /// `/rustc/<commit>/` paths to the configured `rust-src` component.
     fn remap_path(&self, path: &Path) -> PathBuf {
         let mut path = path.to_path_buf();
         for (source, target) in self.get_source_map() {
@@ -51,9 +55,21 @@ pub trait DwarfDb: salsa::Database {
                 path = target.join(stripped);
             }
         }
+        if let Some(remapped) = self
+            .get_rust_src()
+            .and_then(|rust_src| rust_src.remap(&path))
+        {
+            tracing::trace!("Remapping {} to {}", path.display(), remapped.display());
+            path = remapped;
+        }
         path
     }
 
     /// Get the source map for path remapping
     fn get_source_map(&self) -> &[(PathBuf, PathBuf)];
+
+    /// Get the `rust-src` component that standard library paths are remapped to, if any
+    fn get_rust_src(&self) -> Option<&file::RustSrc> {
+        None
+    }
 }
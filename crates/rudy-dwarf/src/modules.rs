@@ -1,4 +1,7 @@
-use std::{collections::BTreeMap, fmt};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
 
 use crate::{
     die::{cu::is_rust_cu, utils::get_string_attr},
@@ -12,6 +15,96 @@ pub struct Module {
     pub entries: Vec<Die>,
 }
 
+impl Module {
+    /// Lists the items declared directly within this module
+    ///
+    /// Compiler-generated items, such as `{impl#0}` blocks
+    /// and `{closure_env#0}` types, are skipped.
+    pub fn items(&self, db: &dyn DwarfDb) -> ModuleItems {
+        let mut items = ModuleItems {
+            modules: self
+                .modules
+                .keys()
+                .filter(|name| !name.starts_with('{'))
+                .cloned()
+                .collect(),
+            ..Default::default()
+        };
+
+        for entry in self
+            .entries
+            .iter()
+            .flat_map(|die| die.children(db).unwrap_or_default())
+        {
+            let tag = entry.tag(db);
+            if !matches!(
+                tag,
+                gimli::DW_TAG_structure_type
+                    | gimli::DW_TAG_enumeration_type
+                    | gimli::DW_TAG_union_type
+                    | gimli::DW_TAG_subprogram
+                    | gimli::DW_TAG_variable
+            ) {
+                continue;
+            }
+            let Ok(name) = entry.name(db) else {
+                continue;
+            };
+            if name.starts_with('{') {
+                continue;
+            }
+
+            let base_name = name.split_once('<').map_or(name.as_str(), |(base, _)| base);
+            match tag {
+                gimli::DW_TAG_subprogram => items
+                    .functions
+                    .entry(base_name.to_string())
+                    .or_default()
+                    .insert(name),
+                gimli::DW_TAG_variable => items.statics.insert(name),
+                _ => items
+                    .types
+                    .entry(base_name.to_string())
+                    .or_default()
+                    .insert(name),
+            };
+        }
+
+        items
+    }
+}
+
+/// The items declared directly within a module
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash, salsa::Update)]
+pub struct ModuleItems {
+    /// Names of the child modules
+    pub modules: BTreeSet<String>,
+    /// Types, keyed by their name without generic arguments,
+    /// with the full name of each instantiation
+    pub types: BTreeMap<String, BTreeSet<String>>,
+    /// Functions, keyed by their name without generic arguments,
+    /// with the full name of each instantiation
+    pub functions: BTreeMap<String, BTreeSet<String>>,
+    pub statics: BTreeSet<String>,
+}
+
+impl ModuleItems {
+    /// Merges the items of the same module from another debug file
+    pub fn merge(&mut self, other: ModuleItems) {
+        self.modules.extend(other.modules);
+        for (name, instantiations) in other.types {
+            self.types.entry(name).or_default().extend(instantiations);
+        }
+        for (name, instantiations) in other.functions {
+            self.functions
+                .entry(name)
+                .or_default()
+                .extend(instantiations);
+        }
+        self.statics.extend(other.statics);
+    }
+}
+
 #[salsa::tracked(debug)]
 pub struct ModuleIndex<'db> {
     #[returns(ref)]
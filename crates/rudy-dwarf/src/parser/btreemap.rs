@@ -35,7 +35,7 @@ use crate::{
         children::parse_children,
         option::parse_option_entry,
         pointers::nonnull,
-        primitives::{data_offset, is_member, member, resolved_generic},
+        primitives::{attr, data_offset, is_member, member, resolved_generic},
     },
     Die, DwarfDb,
 };
@@ -112,12 +112,16 @@ impl Parser<MapLayout<Die>> for BTreeMapParser {
             .then(data_offset())
             .parse(db, internal_node_type)?;
 
+        // Edges are node pointers, like the root
+        let edge_size = attr::<usize>(gimli::DW_AT_byte_size).parse(db, node_ptr_type)?;
+
         // Create the node layout
         let node_layout = BTreeNodeLayout {
             keys_offset,
             vals_offset,
             len_offset,
             edges_offset,
+            edge_size,
         };
 
         Ok(MapLayout {
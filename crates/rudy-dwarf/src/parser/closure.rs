@@ -0,0 +1,281 @@
+//! Closure environment parser implementation using combinators
+
+use std::collections::BTreeSet;
+
+use anyhow::Result;
+use rudy_types::{
+    CaptureMode, ClosureCapture, ClosureLayout, Layout, PrimitiveLayout, ReferenceLayout,
+    SourcePosition, TypeDefinition,
+};
+
+use crate::{
+    modules::module_index,
+    parser::{
+        children::try_for_each_child,
+        combinators::all,
+        primitives::{attr, data_offset, entry_type, is_member_tag, resolve_type_shallow},
+        Parser,
+    },
+    Die, DwarfDb,
+};
+
+/// Prefix rustc gives to the members of by-ref captures
+const BY_REF_PREFIX: &str = "_ref__";
+
+/// Whether a `DW_TAG_structure_type` name is that of a closure environment
+pub fn is_closure_env_name(name: &str) -> bool {
+    name.starts_with("{closure_env#")
+}
+
+/// Parser for a single captured variable
+///
+/// rustc names the members after the captured place, joining
+/// field accesses with `__` and prefixing by-ref captures with `_ref__`.
+/// The name is left as rustc wrote it (minus the prefix): see
+/// [`capture_place`] for turning it back into a path.
+///
+///   DW_TAG_member
+///     DW_AT_name    ("_ref__name")
+///     DW_AT_type    (0x00002064 "alloc::string::String *")
+///     DW_AT_alignment       (8)
+///     DW_AT_data_member_location    (0x00)
+pub fn closure_capture() -> impl Parser<ClosureCapture<Die>> {
+    all((
+        attr::<String>(gimli::DW_AT_name),
+        data_offset(),
+        entry_type().then(resolve_type_shallow()),
+    ))
+    .map(|(member_name, offset, ty)| {
+        let (mode, place) = match member_name.strip_prefix(BY_REF_PREFIX) {
+            Some(place) => (CaptureMode::ByRef, place),
+            None => (CaptureMode::ByMove, member_name.as_str()),
+        };
+
+        // the environment holds a raw pointer to by-ref captures,
+        // but it behaves like (and is shown as) a reference
+        let ty = match (mode, ty.layout.as_ref()) {
+            (CaptureMode::ByRef, Layout::Primitive(PrimitiveLayout::Pointer(pointer))) => {
+                TypeDefinition::new(
+                    ty.location,
+                    Layout::Primitive(PrimitiveLayout::Reference(ReferenceLayout {
+                        mutable: pointer.mutable,
+                        pointed_type: pointer.pointed_type.clone(),
+                    })),
+                )
+            }
+            _ => ty,
+        };
+
+        ClosureCapture {
+            name: place.to_string(),
+            mode,
+            offset,
+            ty,
+        }
+    })
+}
+
+/// Turns the name rustc gives a captured place back into a path
+///
+/// rustc appends `__<field>` to the variable name for each field
+/// access, so `point__0` is `point.0` if `point` is the captured
+/// variable. Since variable names may contain `__` themselves, the
+/// variable is the longest of the enclosing function's `locals` that
+/// the name starts with, and the name is kept as is if there is none.
+fn capture_place(place: &str, locals: &BTreeSet<String>) -> String {
+    let variable = locals
+        .iter()
+        .filter(|local| {
+            place
+                .strip_prefix(local.as_str())
+                .is_some_and(|fields| fields.is_empty() || fields.starts_with("__"))
+        })
+        .max_by_key(|local| local.len());
+
+    match variable {
+        Some(variable) => {
+            let fields = &place[variable.len()..];
+            format!("{variable}{}", fields.replace("__", "."))
+        }
+        None => place.to_string(),
+    }
+}
+
+/// Collects the names of the parameters and variables of the function
+/// a closure is defined in, which is named after the last segment of
+/// the closure's module path
+fn enclosing_locals(
+    db: &dyn DwarfDb,
+    namespaces: &[Die],
+    function_name: &str,
+) -> Result<BTreeSet<String>> {
+    fn collect(db: &dyn DwarfDb, entry: Die, locals: &mut BTreeSet<String>) -> Result<()> {
+        for child in entry.children(db)? {
+            match child.tag(db) {
+                gimli::DW_TAG_variable | gimli::DW_TAG_formal_parameter => {
+                    if let Ok(name) = child.name(db) {
+                        locals.insert(name);
+                    }
+                }
+                gimli::DW_TAG_lexical_block => collect(db, child, locals)?,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    let mut locals = BTreeSet::new();
+    for namespace in namespaces {
+        for child in namespace.children(db)? {
+            let is_function = child.tag(db) == gimli::DW_TAG_subprogram
+                && child.name(db).is_ok_and(|name| {
+                    name.strip_prefix(function_name)
+                        .is_some_and(|generics| generics.is_empty() || generics.starts_with('<'))
+                });
+            if is_function {
+                collect(db, child, &mut locals)?;
+            }
+        }
+    }
+    Ok(locals)
+}
+
+/// Finds where a closure is defined from its `{closure#N}` function
+///
+/// rustc emits the closure function within the namespace of the
+/// enclosing function, though not necessarily in the same compilation
+/// unit as the environment type:
+///
+///   DW_TAG_namespace
+///     DW_AT_name    ("main")
+///     DW_TAG_subprogram
+///       DW_AT_name  ("{closure#0}")
+///       DW_AT_decl_file     ("/tmp/clo.rs")
+///       DW_AT_decl_line     (8)
+///     DW_TAG_structure_type
+///       DW_AT_name  ("{closure_env#0}")
+fn closure_position(
+    db: &dyn DwarfDb,
+    namespaces: &[Die],
+    env_name: &str,
+) -> Result<Option<SourcePosition>> {
+    let Some(index) = env_name
+        .strip_prefix("{closure_env#")
+        .and_then(|rest| rest.split_once('}'))
+        .map(|(index, _)| index)
+    else {
+        return Ok(None);
+    };
+    let function_name = format!("{{closure#{index}}}");
+
+    let is_closure_fn = |child: &Die| {
+        child.tag(db) == gimli::DW_TAG_subprogram
+            && child.name(db).is_ok_and(|name| {
+                name == function_name
+                    || name
+                        .strip_prefix(&function_name)
+                        .is_some_and(|generics| generics.starts_with('<'))
+            })
+    };
+    let Some(function) = namespaces
+        .iter()
+        .flat_map(|namespace| namespace.children(db).unwrap_or_default())
+        .find(is_closure_fn)
+    else {
+        tracing::debug!("no {function_name} found for {env_name}");
+        return Ok(None);
+    };
+
+    let Some(location) = crate::die::position(db, function)? else {
+        return Ok(None);
+    };
+    let column = crate::die::first_column_on_line(db, function, location.line)?;
+
+    Ok(Some(SourcePosition {
+        file: location.file.path_str().into_owned(),
+        line: location.line,
+        column,
+    }))
+}
+
+/// Parser for closure environment types
+///
+/// Should be called on a `DW_TAG_structure_type` DIE whose name
+/// passes [`is_closure_env_name`].
+pub fn closure_def() -> impl Parser<ClosureLayout<Die>> {
+    struct ClosureParser;
+
+    impl Parser<ClosureLayout<Die>> for ClosureParser {
+        fn parse(&self, db: &dyn DwarfDb, entry: Die) -> Result<ClosureLayout<Die>> {
+            tracing::debug!("resolving closure type: {}", entry.print(db));
+
+            let (env_name, size, mut captures) = all((
+                attr::<String>(gimli::DW_AT_name),
+                attr::<usize>(gimli::DW_AT_byte_size),
+                try_for_each_child(
+                    is_member_tag(gimli::DW_TAG_member)
+                        .filter()
+                        .then(closure_capture()),
+                ),
+            ))
+            .parse(db, entry)?;
+
+            // the closure lives in the namespace of its enclosing function
+            let module_index = module_index(db, entry.file);
+            let module_path = module_index
+                .find_by_offset(db, entry.offset())
+                .map(|range| range.module_path.clone())
+                .unwrap_or_default();
+            let enclosing_function = module_path.join("::");
+            let location = match module_index.find_by_path(db, &module_path) {
+                Some(module) => closure_position(db, &module.entries, &env_name)?,
+                None => None,
+            };
+
+            // only look for the captured variables if a place needs splitting
+            if captures.iter().any(|capture| capture.name.contains("__")) {
+                let locals = match module_path.split_last() {
+                    Some((function_name, parent_path)) => {
+                        match module_index.find_by_path(db, parent_path) {
+                            Some(parent) => enclosing_locals(db, &parent.entries, function_name)?,
+                            None => BTreeSet::new(),
+                        }
+                    }
+                    None => BTreeSet::new(),
+                };
+                for capture in &mut captures {
+                    capture.name = capture_place(&capture.name, &locals);
+                }
+            }
+
+            let name = match &location {
+                Some(location) => {
+                    let file = location
+                        .file
+                        .rsplit(['/', '\\'])
+                        .next()
+                        .unwrap_or(&location.file);
+                    let column = location
+                        .column
+                        .map(|column| format!(":{column}"))
+                        .unwrap_or_default();
+                    format!(
+                        "{enclosing_function}::{{closure@{file}:{}{column}}}",
+                        location.line
+                    )
+                }
+                None => format!("{enclosing_function}::{env_name}"),
+            };
+
+            Ok(ClosureLayout {
+                name,
+                enclosing_function,
+                location,
+                captures,
+                size,
+            })
+        }
+    }
+
+    ClosureParser
+}
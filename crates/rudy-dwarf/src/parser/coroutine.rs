@@ -0,0 +1,132 @@
+//! Coroutine (e.g. `async fn` future) parser implementation using combinators
+
+use anyhow::Result;
+use rudy_types::{
+    CoroutineLayout, CoroutineState, CoroutineStateLayout, DiscriminantType, SourcePosition,
+};
+
+use crate::{
+    parser::{
+        children::for_each_child,
+        combinators::all,
+        enums::enum_discriminant,
+        primitives::{attr, entry_type, is_member_tag, member_by_tag, optional_attr},
+        Parser,
+    },
+    types::shallow_resolve_type,
+    Die, DwarfDb,
+};
+
+/// Whether a `DW_TAG_variant_part` is the state of a coroutine
+///
+/// rustc describes coroutines as enums whose discriminant is an
+/// artificial `__state` member.
+pub fn is_coroutine_variant_part(db: &dyn DwarfDb, variant_part: Die) -> bool {
+    variant_part
+        .get_referenced_entry(db, gimli::DW_AT_discr)
+        .and_then(|discr| discr.name(db))
+        .is_ok_and(|name| name == "__state")
+}
+
+/// Parser for a single coroutine state
+///
+/// The member of each variant carries the source location of the
+/// corresponding await (or yield) point:
+///
+///   DW_TAG_variant
+///     DW_AT_discr_value       (0x03)
+///     DW_TAG_member
+///       DW_AT_name    ("3")
+///       DW_AT_type    (0x00002f9c "main::work::{async_fn_env#0}::Suspend0")
+///       DW_AT_decl_file       ("/tmp/coro/main.rs")
+///       DW_AT_decl_line       (15)
+///       DW_AT_data_member_location    (0x00)
+pub fn coroutine_state() -> impl Parser<CoroutineStateLayout<Die>> {
+    struct CoroutineStateParser;
+
+    impl Parser<CoroutineStateLayout<Die>> for CoroutineStateParser {
+        fn parse(&self, db: &dyn DwarfDb, entry: Die) -> Result<CoroutineStateLayout<Die>> {
+            let (discriminant, member) = is_member_tag(gimli::DW_TAG_variant)
+                .then(
+                    optional_attr::<i128>(gimli::DW_AT_discr_value)
+                        .and(member_by_tag(gimli::DW_TAG_member)),
+                )
+                .parse(db, entry)?;
+
+            let state_entry = entry_type().parse(db, member)?;
+            let variant_name = attr::<String>(gimli::DW_AT_name).parse(db, state_entry)?;
+            let state = CoroutineState::from_variant_name(&variant_name).ok_or_else(|| {
+                anyhow::anyhow!("unexpected coroutine state variant: {variant_name}")
+            })?;
+
+            // only suspension points have a meaningful location
+            let location = match state {
+                CoroutineState::Suspended(_) => {
+                    crate::die::position(db, member)?.map(|location| SourcePosition {
+                        file: location.file.path_str().into_owned(),
+                        line: location.line,
+                        column: None,
+                    })
+                }
+                _ => None,
+            };
+
+            Ok(CoroutineStateLayout {
+                state,
+                discriminant,
+                location,
+                layout: shallow_resolve_type(db, state_entry)?,
+            })
+        }
+    }
+
+    CoroutineStateParser
+}
+
+/// Parser for coroutine types
+///
+/// Should be called on a `DW_TAG_structure_type` DIE whose variant part
+/// passes [`is_coroutine_variant_part`].
+pub fn coroutine_def() -> impl Parser<CoroutineLayout<Die>> {
+    struct CoroutineParser;
+
+    impl Parser<CoroutineLayout<Die>> for CoroutineParser {
+        fn parse(&self, db: &dyn DwarfDb, entry: Die) -> Result<CoroutineLayout<Die>> {
+            tracing::debug!("resolving coroutine type: {}", entry.print(db));
+
+            let (name, size, variants_entry) = all((
+                attr::<String>(gimli::DW_AT_name),
+                attr::<usize>(gimli::DW_AT_byte_size),
+                member_by_tag(gimli::DW_TAG_variant_part),
+            ))
+            .parse(db, entry)?;
+
+            let mut discriminant = enum_discriminant().parse(db, variants_entry)?;
+            if matches!(discriminant.ty, DiscriminantType::Implicit) {
+                anyhow::bail!("coroutine {name} has no __state discriminant");
+            }
+            // unlike enums, the offset of the `__state` is on the member itself,
+            // as it follows the locals saved across await points
+            let has_offset = optional_attr::<usize>(gimli::DW_AT_data_member_location)
+                .parse(db, variants_entry)?
+                .is_some();
+            if !has_offset {
+                let state = variants_entry.get_referenced_entry(db, gimli::DW_AT_discr)?;
+                discriminant.offset = optional_attr::<usize>(gimli::DW_AT_data_member_location)
+                    .parse(db, state)?
+                    .unwrap_or(0);
+            }
+
+            let states = for_each_child(coroutine_state()).parse(db, variants_entry)?;
+
+            Ok(CoroutineLayout {
+                name,
+                discriminant,
+                states,
+                size,
+            })
+        }
+    }
+
+    CoroutineParser
+}
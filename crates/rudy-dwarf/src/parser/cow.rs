@@ -0,0 +1,60 @@
+//! Cow parser implementation using combinators
+
+use anyhow::Result;
+use rudy_types::CowLayout;
+
+use super::Parser;
+use crate::{
+    parser::{
+        children::parse_children,
+        combinators::all,
+        enums::{enum_discriminant, enum_named_tuple_variant},
+        primitives::{attr, member_by_tag, resolve_type_shallow},
+    },
+    Die, DwarfDb,
+};
+
+pub struct CowDefParser;
+
+/// Parser for `Cow` types
+///
+/// Like `Result`, we parse it as a generic enum and extract the expected
+/// "Borrowed" and "Owned" variants
+pub fn cow_def() -> CowDefParser {
+    CowDefParser
+}
+
+impl Parser<CowLayout<Die>> for CowDefParser {
+    fn parse(&self, db: &dyn DwarfDb, entry: Die) -> Result<CowLayout<Die>> {
+        tracing::debug!("resolving cow type: {}", entry.print(db));
+
+        let (name, size, (discriminant, (borrowed, owned))) = all((
+            attr::<String>(gimli::DW_AT_name),
+            attr::<usize>(gimli::DW_AT_byte_size),
+            member_by_tag(gimli::DW_TAG_variant_part).then(
+                enum_discriminant().and(parse_children((
+                    enum_named_tuple_variant("Borrowed", (resolve_type_shallow(),))
+                        .map(|(discriminant, ((offset, ty),))| (discriminant, (offset, ty))),
+                    enum_named_tuple_variant("Owned", (resolve_type_shallow(),))
+                        .map(|(discriminant, ((offset, ty),))| (discriminant, (offset, ty))),
+                ))),
+            ),
+        ))
+        .parse(db, entry)?;
+
+        let (borrowed_discriminant, (borrowed_offset, borrowed_type)) = borrowed;
+        let (owned_discriminant, (owned_offset, owned_type)) = owned;
+
+        Ok(CowLayout {
+            name,
+            discriminant,
+            borrowed_discriminant,
+            borrowed_type,
+            borrowed_offset,
+            owned_discriminant,
+            owned_type,
+            owned_offset,
+            size,
+        })
+    }
+}
@@ -125,7 +125,7 @@ pub fn enum_named_tuple_variant<T>(variant_name: &str, parser: T) -> EnumNamedTu
 }
 
 pub(super) struct PartiallyParsedEnumVariant {
-    pub discriminant: Option<usize>,
+    pub discriminant: Option<i128>,
     pub offset: usize,
     pub layout: Die,
 }
@@ -152,7 +152,7 @@ pub(super) struct PartiallyParsedEnumVariant {
 ///         DW_AT_data_member_location    (0x00)
 pub(super) fn named_enum_variant(variant_name: &str) -> impl Parser<PartiallyParsedEnumVariant> {
     is_member_tag(gimli::DW_TAG_variant).then(
-        optional_attr::<usize>(gimli::DW_AT_discr)
+        optional_attr::<i128>(gimli::DW_AT_discr_value)
             .and(member(variant_name).then(all((data_offset(), entry_type()))))
             .map(
                 |(discriminant, (offset, layout))| PartiallyParsedEnumVariant {
@@ -168,11 +168,11 @@ macro_rules! impl_parse_enum_named_tuple_variant_for_tuples {
     (
         $($P:ident, $T:ident, $idx:tt),*
     ) => {
-        impl<'db, $($T, $P,)*> Parser< (Option<usize>, ($((usize, $T),)*))> for EnumNamedTupleVariant<($($P,)*)>
+        impl<'db, $($T, $P,)*> Parser< (Option<i128>, ($((usize, $T),)*))> for EnumNamedTupleVariant<($($P,)*)>
         where
             $($P: Parser< $T>),*
         {
-            fn parse(&self, db: &dyn DwarfDb, entry: Die) -> anyhow::Result<(Option<usize>, ($((usize, $T),)*))> {
+            fn parse(&self, db: &dyn DwarfDb, entry: Die) -> anyhow::Result<(Option<i128>, ($((usize, $T),)*))> {
                 // --> DW_TAG_variant
                 //       DW_AT_discr_value       (0x00)
                 //       DW_TAG_member
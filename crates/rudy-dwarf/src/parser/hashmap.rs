@@ -1,6 +1,7 @@
 //! HashMap parser implementation using combinators
 
-use rudy_types::MapVariant;
+use object::Object;
+use rudy_types::{MapVariant, RandomStateLayout};
 
 use super::{
     children::parse_children,
@@ -55,9 +56,46 @@ pub fn hashbrown_map() -> impl Parser<MapVariant> {
                 pair_size,
                 key_offset,
                 value_offset,
+                group_width: group_width(db, entry),
+                random_state: random_state(db, entry),
             })
         }
     }
 
     HashBrownMapParser
 }
+
+/// The number of control bytes hashbrown probes at a time, which depends on
+/// the SIMD support of the target: SSE2 groups on x86, 8-byte groups elsewhere
+pub(super) fn group_width(db: &dyn DwarfDb, entry: Die) -> usize {
+    let architecture = crate::file::load(db, entry.file.file(db))
+        .as_ref()
+        .map(|loaded| loaded.object.architecture());
+    match architecture {
+        Ok(
+            object::Architecture::X86_64
+            | object::Architecture::X86_64_X32
+            | object::Architecture::I386,
+        ) => 16,
+        _ => 8,
+    }
+}
+
+/// The location of the SipHash keys in the `hash_builder`, if the map uses
+/// std's `RandomState`
+pub(super) fn random_state(db: &dyn DwarfDb, entry: Die) -> Option<RandomStateLayout> {
+    let (hash_builder_offset, hash_builder_type) = member("hash_builder")
+        .then(data_offset().and(entry_type()))
+        .parse(db, entry)
+        .ok()?;
+    if hash_builder_type.name(db).ok()? != "RandomState" {
+        return None;
+    }
+    let (k0_offset, k1_offset) = parse_children((is_member_offset("k0"), is_member_offset("k1")))
+        .parse(db, hash_builder_type)
+        .ok()?;
+    Some(RandomStateLayout {
+        k0_offset: hash_builder_offset + k0_offset,
+        k1_offset: hash_builder_offset + k1_offset,
+    })
+}
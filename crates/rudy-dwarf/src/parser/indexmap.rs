@@ -0,0 +1,81 @@
+//! IndexMap parser implementation using combinators
+//!
+//! An `indexmap::IndexMap<K, V, S>` stores its entries densely, in insertion
+//! order, in a `Vec<Bucket<K, V>>` and keeps a separate hashbrown table of
+//! indices into that Vec for lookups:
+//!
+//! ```text
+//! IndexMap { core: Core { indices: HashTable<usize>, entries: Vec<Bucket<K, V>> }, hash_builder: S }
+//! Bucket { hash: HashValue, key: K, value: V }
+//! ```
+
+use anyhow::Result;
+use rudy_types::MapVariant;
+
+use super::{
+    children::parse_children,
+    hashmap::{group_width, random_state},
+    primitives::{attr, data_offset, entry_type, is_member, is_member_offset, member},
+    vec::vec,
+    Parser,
+};
+use crate::{Die, DwarfDb};
+
+/// Parser for the indexmap IndexMap layout
+pub fn index_map() -> impl Parser<MapVariant> {
+    struct IndexMapParser;
+
+    impl Parser<MapVariant> for IndexMapParser {
+        fn parse(&self, db: &dyn DwarfDb, entry: Die) -> Result<MapVariant> {
+            let (size, (core_offset, core_type)) = attr::<usize>(gimli::DW_AT_byte_size)
+                .and(member("core").then(data_offset().and(entry_type())))
+                .parse(db, entry)?;
+
+            let ((indices_offset, indices_type), (entries_offset, entries)) = parse_children((
+                is_member("indices").then(data_offset().and(entry_type())),
+                is_member("entries").then(data_offset().and(entry_type().then(vec()))),
+            ))
+            .parse(db, core_type)?;
+
+            // Bucket<K, V> holds the cached hash alongside the key and value
+            let (bucket_size, (key_offset, value_offset)) = attr::<usize>(gimli::DW_AT_byte_size)
+                .and(parse_children((
+                    is_member_offset("key"),
+                    is_member_offset("value"),
+                )))
+                .parse(db, entries.inner_type.location)?;
+
+            // indices: HashTable<usize> -> raw: RawTable<usize> -> table: RawTableInner
+            let (raw_offset, raw_table_type) = member("raw")
+                .then(data_offset().and(entry_type()))
+                .parse(db, indices_type)?;
+            let (table_offset, raw_table_inner_type) = member("table")
+                .then(data_offset().and(entry_type()))
+                .parse(db, raw_table_type)?;
+            let (bucket_mask_offset, ctrl_offset, items_offset) = parse_children((
+                is_member_offset("bucket_mask"),
+                is_member_offset("ctrl"),
+                is_member_offset("items"),
+            ))
+            .parse(db, raw_table_inner_type)?;
+            let table_offset = core_offset + indices_offset + raw_offset + table_offset;
+
+            let entries_offset = core_offset + entries_offset;
+            Ok(MapVariant::IndexMap {
+                size,
+                entries_ptr_offset: entries_offset + entries.data_ptr_offset,
+                entries_len_offset: entries_offset + entries.length_offset,
+                bucket_size,
+                key_offset,
+                value_offset,
+                indices_bucket_mask_offset: table_offset + bucket_mask_offset,
+                indices_ctrl_offset: table_offset + ctrl_offset,
+                indices_items_offset: table_offset + items_offset,
+                group_width: group_width(db, entry),
+                random_state: random_state(db, entry),
+            })
+        }
+    }
+
+    IndexMapParser
+}
@@ -0,0 +1,51 @@
+//! LinkedList parser implementation using combinators
+
+use anyhow::Result;
+use rudy_types::LinkedListLayout;
+
+use super::{
+    children::parse_children,
+    option::parse_option_entry,
+    pointers::nonnull,
+    primitives::{data_offset, entry_type, is_member, is_member_offset, resolved_generic},
+    Parser,
+};
+use crate::{Die, DwarfDb};
+
+/// Parser for LinkedList<T> type layout
+pub fn linked_list() -> LinkedListParser {
+    LinkedListParser
+}
+
+pub struct LinkedListParser;
+
+impl Parser<LinkedListLayout<Die>> for LinkedListParser {
+    fn parse(&self, db: &dyn DwarfDb, entry: Die) -> Result<LinkedListLayout<Die>> {
+        tracing::debug!("resolving linked list type: {}", entry.print(db));
+
+        let (inner_type, (head_offset, head_option_type), length_offset) = parse_children((
+            resolved_generic("T"),
+            is_member("head").then(data_offset().and(entry_type())),
+            is_member_offset("len"),
+        ))
+        .parse(db, entry)?;
+
+        // `head` is an Option<NonNull<Node<T>>>, which uses the null pointer niche
+        let (_, _, _, some_variant) = parse_option_entry().parse(db, head_option_type)?;
+        let node_type = nonnull().parse(db, some_variant.layout)?;
+
+        tracing::debug!("resolving linked list node type: {}", node_type.print(db));
+
+        let (next_offset, element_offset) =
+            parse_children((is_member_offset("next"), is_member_offset("element")))
+                .parse(db, node_type)?;
+
+        Ok(LinkedListLayout {
+            head_offset: head_offset + some_variant.offset,
+            length_offset,
+            next_offset,
+            element_offset,
+            inner_type,
+        })
+    }
+}
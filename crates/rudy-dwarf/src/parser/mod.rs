@@ -12,10 +12,15 @@ use crate::{
 // Module structure
 pub mod btreemap;
 pub mod children;
+pub mod closure;
 pub mod combinators;
+pub mod coroutine;
+pub mod cow;
 pub mod enums;
 pub mod functions;
 pub mod hashmap;
+pub mod indexmap;
+pub mod linked_list;
 pub mod option;
 pub mod pointers;
 pub mod primitives;
@@ -1,4 +1,4 @@
-//! Option parser implementation using combinators
+//! Result parser implementation using combinators
 
 use anyhow::Result;
 use rudy_types::ResultLayout;
@@ -48,13 +48,15 @@ impl Parser<ResultLayout<Die>> for ResultDefParser {
         ))
         .parse(db, entry)?;
 
-        let (_, (ok_offset, ok_type)) = ok;
-        let (_, (err_offset, err_type)) = err;
+        let (ok_discriminant, (ok_offset, ok_type)) = ok;
+        let (err_discriminant, (err_offset, err_type)) = err;
 
         Ok(ResultLayout {
             name,
+            ok_discriminant,
             ok_type,
             ok_offset,
+            err_discriminant,
             err_type,
             err_offset,
             size,
@@ -1,12 +1,16 @@
 //! Vec parser implementation using combinators
+//!
+//! Also covers the collections backed by a `RawVec`: `VecDeque` and `BinaryHeap`.
 
-use rudy_types::VecLayout;
+use rudy_types::{BinaryHeapLayout, VecDequeLayout, VecLayout};
 
 use crate::Die;
 
 use super::{
     children::parse_children,
-    primitives::{field_path_offset, is_member_offset, resolved_generic},
+    primitives::{
+        data_offset, entry_type, field_path_offset, is_member_offset, member, resolved_generic,
+    },
     Parser,
 };
 
@@ -28,3 +32,43 @@ pub fn vec() -> impl Parser<VecLayout<Die>> {
     )
     .context("Failed to parse Vec layout")
 }
+
+/// Parser for VecDeque<T> type layout
+pub fn vec_deque() -> impl Parser<VecDequeLayout<Die>> {
+    parse_children((
+        is_member_offset("head"),
+        is_member_offset("len"),
+        field_path_offset(vec!["buf", "inner", "ptr"]),
+        field_path_offset(vec!["buf", "inner", "cap", "__0"]),
+        resolved_generic("T"),
+    ))
+    .map(
+        |(head_offset, length_offset, data_ptr_offset, capacity_offset, inner_type)| {
+            VecDequeLayout {
+                head_offset,
+                length_offset,
+                data_ptr_offset,
+                capacity_offset,
+                inner_type,
+            }
+        },
+    )
+    .context("Failed to parse VecDeque layout")
+}
+
+/// Parser for BinaryHeap<T> type layout
+///
+/// The heap is a thin wrapper around a `Vec<T>` stored in the `data` field.
+pub fn binary_heap() -> impl Parser<BinaryHeapLayout<Die>> {
+    member("data")
+        .then(data_offset().and(entry_type().then(vec())))
+        .map(|(data_offset, vec)| {
+            BinaryHeapLayout(VecLayout {
+                length_offset: data_offset + vec.length_offset,
+                data_ptr_offset: data_offset + vec.data_ptr_offset,
+                capacity_offset: data_offset + vec.capacity_offset,
+                inner_type: vec.inner_type,
+            })
+        })
+        .context("Failed to parse BinaryHeap layout")
+}
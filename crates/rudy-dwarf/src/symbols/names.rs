@@ -60,7 +60,13 @@ impl std::hash::Hash for TypeName {
 impl TypeName {
     pub fn parse(module_path: &[String], name: &str) -> anyhow::Result<Self> {
         fn known_bad_case(path: &str) -> bool {
-            path.contains("{closure_env#") || path.contains("{impl#") || path.contains("{extern#")
+            path.contains("{closure_env#")
+                || path.contains("{impl#")
+                || path.contains("{extern#")
+                // coroutines are detected from their layout instead
+                || path.contains("{async_fn_env#")
+                || path.contains("{async_block_env#")
+                || path.contains("{coroutine_env#")
         }
 
         // If we have a module path, prepend it to the name for parsing
@@ -196,6 +202,12 @@ impl SymbolName {
     pub fn matches_name_and_module(&self, name: &str, module: &[String]) -> bool {
         self.lookup_name == name && self.module_path.ends_with(module)
     }
+
+    /// If this symbol is defined directly in a trait impl, e.g. `<Foo as Trait>::method`,
+    /// returns the implementing type and the trait
+    pub fn trait_impl(&self) -> Option<(String, String)> {
+        parser::parse_trait_impl(self.module_path.last()?)
+    }
 }
 
 impl PartialOrd for SymbolName {
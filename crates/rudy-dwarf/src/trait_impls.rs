@@ -0,0 +1,92 @@
+//! Trait implementations recorded in the debug info
+//!
+//! Symbol tables only name trait impls that have a method with code in the
+//! binary. The debug info also records:
+//!
+//! - vtables of the impls used as trait objects, as variables named like
+//!   `<alloc::string::String as core::fmt::Debug>::{vtable}`, including
+//!   impls of marker traits that have no methods at all
+//! - trait methods that were only ever inlined, as subprograms whose
+//!   `DW_AT_specification` points to the method's declaration
+
+use std::collections::BTreeSet;
+
+use crate::{
+    die::utils::{get_string_attr, get_unit_ref_attr},
+    symbols::RawSymbol,
+    visitor::{walk_file, DieVisitor, DieWalker, VisitorNode},
+    DebugFile, DwarfDb,
+};
+
+/// Collects the (type name, trait name) of the impls found in a debug file
+#[derive(Default)]
+struct TraitImplCollector {
+    impls: BTreeSet<(String, String)>,
+}
+
+impl<'db> DieVisitor<'db> for TraitImplCollector {
+    fn visit_die<'a>(
+        walker: &mut DieWalker<'a, 'db, Self>,
+        node: VisitorNode<'a>,
+    ) -> anyhow::Result<()> {
+        match node.die.tag() {
+            gimli::DW_TAG_namespace => walker.walk_namespace()?,
+            gimli::DW_TAG_variable => Self::visit_variable(walker, node)?,
+            gimli::DW_TAG_subprogram => Self::visit_function(walker, node)?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn visit_variable<'a>(
+        walker: &mut DieWalker<'a, 'db, Self>,
+        node: VisitorNode<'a>,
+    ) -> anyhow::Result<()> {
+        let Ok(Some(name)) = node.name() else {
+            return Ok(());
+        };
+        if let Some(trait_impl) = name
+            .strip_suffix("::{vtable}")
+            .and_then(rudy_parser::parse_trait_impl)
+        {
+            walker.visitor.impls.insert(trait_impl);
+        }
+        Ok(())
+    }
+
+    fn visit_function<'a>(
+        walker: &mut DieWalker<'a, 'db, Self>,
+        node: VisitorNode<'a>,
+    ) -> anyhow::Result<()> {
+        let Ok(offset) = get_unit_ref_attr(&node.die, gimli::DW_AT_specification) else {
+            return Ok(());
+        };
+        let Ok(declaration) = node.unit_ref.entry(offset) else {
+            return Ok(());
+        };
+        let Ok(Some(linkage_name)) =
+            get_string_attr(&declaration, gimli::DW_AT_linkage_name, &node.unit_ref)
+        else {
+            return Ok(());
+        };
+        let symbol = RawSymbol::new(linkage_name.into_bytes());
+        if let Some(trait_impl) = symbol.demangle().ok().and_then(|name| name.trait_impl()) {
+            walker.visitor.impls.insert(trait_impl);
+        }
+        Ok(())
+    }
+}
+
+/// Finds the trait impls recorded in the debug info of a file, as
+/// (type name, trait name) pairs
+///
+/// Type and trait names are as written in the debug info, so unlike names
+/// from demangled symbols they include any generic arguments.
+#[salsa::tracked(returns(ref))]
+pub fn trait_impls(db: &dyn DwarfDb, debug_file: DebugFile) -> BTreeSet<(String, String)> {
+    let mut collector = TraitImplCollector::default();
+    if let Err(e) = walk_file(db, debug_file, &mut collector) {
+        tracing::error!("Failed to walk debug file: {e}");
+    }
+    collector.impls
+}
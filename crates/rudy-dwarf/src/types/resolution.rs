@@ -6,13 +6,18 @@ use rudy_types::*;
 use crate::{
     parser::{
         btreemap::btree_map,
-        children::parse_children,
+        children::{child, parse_children},
+        closure::{closure_def, is_closure_env_name},
+        coroutine::{coroutine_def, is_coroutine_variant_part},
+        cow::cow_def,
         enums::{c_enum_def, enum_def},
         hashmap::hashbrown_map,
+        indexmap::index_map,
+        linked_list::linked_list,
         option::option_def,
-        primitives::{entry_type, member, resolved_generic},
+        primitives::{data_offset, entry_type, member, resolved_generic},
         result::result_def,
-        vec::vec,
+        vec::{binary_heap, vec, vec_deque},
         Parser,
     },
     types::{get_die_typename, DieTypeDefinition, TypeIndexEntry},
@@ -66,58 +71,121 @@ fn resolve_map_type(db: &dyn DwarfDb, entry: Die, variant: MapVariant) -> Result
         MapVariant::BTreeMap { .. } => Ok(btree_map()
             .parse(db, entry)
             .context("failed to parse btree map layout")?),
-        _ => {
-            unimplemented!(
-                "Map variant `{variant:?}` not yet implemented: {}",
-                entry.location(db)
-            )
+        MapVariant::IndexMap { .. } => {
+            let variant = index_map()
+                .parse(db, entry)
+                .context("failed to parse indexmap layout")?;
+
+            Ok(MapLayout {
+                key_type,
+                value_type,
+                variant,
+            })
         }
     }
 }
 
+/// Resolve Set type layout from DWARF
+///
+/// Sets are thin wrappers around a map with zero-sized values, so we locate
+/// the inner map and reuse the map resolution.
+fn resolve_set_type(db: &dyn DwarfDb, entry: Die, variant: MapVariant) -> Result<SetLayout<Die>> {
+    let element_type = child(resolved_generic("T")).parse(db, entry)?;
+
+    // std's HashSet wraps a hashbrown HashSet in a "base" member
+    let (base_offset, base_entry) = if let Ok(base) = member("base")
+        .then(data_offset().and(entry_type()))
+        .parse(db, entry)
+    {
+        base
+    } else {
+        (0, entry)
+    };
+
+    let (map_offset, map_entry) = member("map")
+        .then(data_offset().and(entry_type()))
+        .parse(db, base_entry)
+        .context("failed to find the map inside the set")?;
+
+    let mut map = resolve_map_type(db, map_entry, variant)?;
+    // The map values are zero-sized markers (e.g. BTreeSet's `SetValZST`), which
+    // stay aliases after shallow resolution. Resolve them fully so the reader
+    // knows their size when walking the map storage.
+    map.value_type = resolve_type_offset(db, map.value_type.location)?;
+
+    Ok(SetLayout {
+        element_type,
+        map_offset: base_offset + map_offset,
+        map,
+    })
+}
+
 /// Resolve smart pointer type layout from DWARF
+///
+/// `parsed` is the layout inferred from the type name, which tells us whether
+/// unsized data is a `str` (DWARF only describes it as `u8` elements).
 fn resolve_smart_ptr_type(
     db: &dyn DwarfDb,
     entry: Die,
-    variant: SmartPtrVariant,
+    parsed: &SmartPtrLayout,
 ) -> Result<SmartPtrLayout<Die>> {
+    let variant = parsed.variant;
+
+    // the pointee of a wide data pointer, which describes the unsized data
+    let mut wide_data = None;
+    let mut metadata = PointerMetadata::None;
+    let mut box_ptr_offset = 0;
+
     let inner_type = match variant {
         // `Box` is output as a pointer_type and has the type in the entry itself
-        SmartPtrVariant::Box => resolve_entry_type_shallow(db, entry)
-            .context("Failed to resolve inner type for smart pointer")?,
-        // `Rc` and `Arc` are output as struct types, with generic type parameters
+        SmartPtrVariant::Box if entry.tag(db) == gimli::DW_TAG_pointer_type => {
+            resolve_entry_type_shallow(db, entry)
+                .context("Failed to resolve inner type for smart pointer")?
+        }
+        // ...unless it points to unsized data, in which case it is a wide pointer struct
+        SmartPtrVariant::Box => {
+            let (data_ptr_offset, data_ptr, box_metadata) = resolve_wide_pointer(db, entry)?;
+            box_ptr_offset = data_ptr_offset;
+            metadata = box_metadata;
+            let data = data_ptr.get_referenced_entry(db, gimli::DW_AT_type)?;
+            wide_data = Some(data);
+            shallow_resolve_type(db, data).context("failed to resolve the inner type")?
+        }
+        // everything else is output as struct types, with generic type parameters
         SmartPtrVariant::Arc
         | SmartPtrVariant::Rc
+        | SmartPtrVariant::Weak
         | SmartPtrVariant::Mutex
+        | SmartPtrVariant::RwLock
         | SmartPtrVariant::Cell
         | SmartPtrVariant::RefCell
-        | SmartPtrVariant::UnsafeCell => {
+        | SmartPtrVariant::UnsafeCell
+        | SmartPtrVariant::ManuallyDrop
+        | SmartPtrVariant::MaybeUninit => {
             let type_entry = entry
                 .get_generic_type_entry(db, "T")
                 .context("could not find inner type")?;
 
             shallow_resolve_type(db, type_entry).context("failed to resolve the inner type")?
         }
-        _ => {
-            unimplemented!(
-                "{}",
-                entry.format_with_location(
-                    db,
-                    format!("Smart pointer variant `{variant:?}` not yet implemented")
-                )
-            )
-        }
     };
 
+    // the shared allocation of `Rc`/`Arc`/`Weak`, which holds the reference counts
+    let mut shared_inner = None;
+
     let (inner_ptr_offset, data_ptr_offset) = match variant {
-        SmartPtrVariant::Box => (0, 0), // Box has no offset, it's just a pointer
+        // Box is just a (possibly wide) pointer
+        SmartPtrVariant::Box => (box_ptr_offset, 0),
 
-        SmartPtrVariant::Mutex | SmartPtrVariant::RefCell | SmartPtrVariant::Cell => {
+        SmartPtrVariant::Mutex
+        | SmartPtrVariant::RwLock
+        | SmartPtrVariant::RefCell
+        | SmartPtrVariant::Cell => {
             // Mutex.data -> UnsafeCell<T>
             let mut inner_offset = 0;
 
             let inner_name = match variant {
-                SmartPtrVariant::Mutex => "data",
+                SmartPtrVariant::Mutex | SmartPtrVariant::RwLock => "data",
                 SmartPtrVariant::RefCell | SmartPtrVariant::Cell => "value",
                 _ => unreachable!(),
             };
@@ -144,7 +212,28 @@ fn resolve_smart_ptr_type(
 
             (inner_offset, 0)
         }
-        SmartPtrVariant::Rc | SmartPtrVariant::Arc => {
+        SmartPtrVariant::ManuallyDrop => {
+            // ManuallyDrop.value -> T
+            let inner_offset = entry
+                .get_udata_member_attribute(db, "value", gimli::DW_AT_data_member_location)
+                .context("ManuallyDrop value offset is not a valid udata")?;
+
+            (inner_offset, 0)
+        }
+        SmartPtrVariant::MaybeUninit => {
+            // MaybeUninit is a union of `uninit: ()` and `value: ManuallyDrop<T>`
+            let value = entry.get_member(db, "value")?;
+            let mut inner_offset = value.udata_attr(db, gimli::DW_AT_data_member_location)?;
+
+            // ManuallyDrop.value -> T
+            let manually_drop_entry = value.get_referenced_entry(db, gimli::DW_AT_type)?;
+            inner_offset += manually_drop_entry
+                .get_udata_member_attribute(db, "value", gimli::DW_AT_data_member_location)
+                .context("ManuallyDrop value offset is not a valid udata")?;
+
+            (inner_offset, 0)
+        }
+        SmartPtrVariant::Rc | SmartPtrVariant::Arc | SmartPtrVariant::Weak => {
             // Arc.ptr -> NonNull<ArcInner<T>>
 
             let mut inner_offset = 0;
@@ -165,7 +254,16 @@ fn resolve_smart_ptr_type(
                 .udata_attr(db, gimli::DW_AT_data_member_location)
                 .context("could not find pointer offset")?;
 
-            let arcinner_pointer = pointer.get_referenced_entry(db, gimli::DW_AT_type)?;
+            let mut arcinner_pointer = pointer.get_referenced_entry(db, gimli::DW_AT_type)?;
+
+            // pointers to unsized data (e.g. `Arc<str>`) are wide pointer structs
+            if arcinner_pointer.tag(db) == gimli::DW_TAG_structure_type {
+                let (data_ptr_offset, data_ptr, wide_metadata) =
+                    resolve_wide_pointer(db, arcinner_pointer)?;
+                metadata = wide_metadata.offset_by(inner_offset);
+                inner_offset += data_ptr_offset;
+                arcinner_pointer = data_ptr;
+            }
 
             // pointer type that needs to be dereferenced to get the inner type
             // then we have _another_ offset to get the data
@@ -176,23 +274,77 @@ fn resolve_smart_ptr_type(
             let name = match variant {
                 SmartPtrVariant::Arc => "data",
                 SmartPtrVariant::Rc => "value",
-                _ => unreachable!(),
+                // `Weak` points to either of them
+                _ if arc_inner.get_member(db, "data").is_ok() => "data",
+                _ => "value",
             };
-            let data_ptr_offset = arc_inner
-                .get_udata_member_attribute(db, name, gimli::DW_AT_data_member_location)
+            let data = arc_inner.get_member(db, name)?;
+            let data_ptr_offset = data
+                .udata_attr(db, gimli::DW_AT_data_member_location)
                 .context("data offset is not a valid udata")?;
 
+            if metadata.is_wide() {
+                wide_data = Some(data.get_referenced_entry(db, gimli::DW_AT_type)?);
+            }
+            shared_inner = Some(arc_inner);
+
             (inner_offset, data_ptr_offset)
         }
-        _ => {
-            unimplemented!(
-                "{}",
-                entry.format_with_location(
-                    db,
-                    format!("Smart pointer variant `{variant:?}` not yet implemented")
-                )
-            )
+    };
+
+    let state = match variant {
+        SmartPtrVariant::Box
+        | SmartPtrVariant::Cell
+        | SmartPtrVariant::UnsafeCell
+        | SmartPtrVariant::ManuallyDrop
+        | SmartPtrVariant::MaybeUninit => SmartPtrState::None,
+        SmartPtrVariant::Rc | SmartPtrVariant::Arc | SmartPtrVariant::Weak => {
+            // {Arc,Rc}Inner.strong -> Cell<usize> / AtomicUsize
+            let shared_inner = shared_inner.context("missing shared allocation type")?;
+            SmartPtrState::RefCounts {
+                strong_offset: scalar_member_offset(db, shared_inner.get_member(db, "strong")?)
+                    .context("could not find strong count")?,
+                weak_offset: scalar_member_offset(db, shared_inner.get_member(db, "weak")?)
+                    .context("could not find weak count")?,
+            }
         }
+        SmartPtrVariant::RefCell => {
+            // RefCell.borrow -> Cell<isize>
+            SmartPtrState::BorrowFlag {
+                offset: scalar_member_offset(db, entry.get_member(db, "borrow")?)
+                    .context("could not find borrow flag")?,
+            }
+        }
+        SmartPtrVariant::Mutex | SmartPtrVariant::RwLock => {
+            // {Mutex,RwLock}.inner -> sys::{Mutex,RwLock} -> Futex (AtomicU32)
+            let state_offset = scalar_member_offset(db, entry.get_member(db, "inner")?)
+                .inspect_err(|e| tracing::debug!("lock state is not a futex: {e:?}"))
+                .ok();
+            // {Mutex,RwLock}.poison -> poison::Flag -> AtomicBool
+            SmartPtrState::Lock {
+                state_offset,
+                poison_offset: scalar_member_offset(db, entry.get_member(db, "poison")?)
+                    .context("could not find poison flag")?,
+            }
+        }
+    };
+
+    let inner_type = match (metadata, wide_data) {
+        (PointerMetadata::Length { .. }, _)
+            if matches!(
+                parsed.inner_type.layout.as_ref(),
+                Layout::Primitive(PrimitiveLayout::Str(_))
+            ) =>
+        {
+            DieTypeDefinition::new(entry, Layout::Primitive(PrimitiveLayout::Str(())))
+        }
+        // the generic parameter of `Rc<[T]>` and friends isn't reliable, so
+        // we use the type of the data itself: the element type for slices,
+        // or the trait object type
+        (PointerMetadata::Length { .. } | PointerMetadata::VTable { .. }, Some(data)) => {
+            shallow_resolve_type(db, data).context("failed to resolve the unsized data type")?
+        }
+        _ => inner_type,
     };
 
     Ok(SmartPtrLayout {
@@ -200,9 +352,120 @@ fn resolve_smart_ptr_type(
         inner_type,
         inner_ptr_offset,
         data_ptr_offset,
+        state,
+        metadata,
     })
 }
 
+/// Resolves a wide pointer, which DWARF describes as a struct holding the
+/// data pointer and its metadata
+///
+/// This is `data_ptr` + `length` for `str` and slices, and `pointer` +
+/// `vtable` for trait objects. Returns the offset of the data pointer, its
+/// pointer type, and the metadata.
+fn resolve_wide_pointer(db: &dyn DwarfDb, entry: Die) -> Result<(usize, Die, PointerMetadata)> {
+    let (data_ptr_name, metadata) = if entry.get_member(db, "data_ptr").is_ok() {
+        let length_offset = entry
+            .get_udata_member_attribute(db, "length", gimli::DW_AT_data_member_location)
+            .context("could not find length of wide pointer")?;
+        ("data_ptr", PointerMetadata::Length { length_offset })
+    } else {
+        let vtable_offset = entry
+            .get_udata_member_attribute(db, "vtable", gimli::DW_AT_data_member_location)
+            .context("could not find vtable of wide pointer")?;
+        ("pointer", PointerMetadata::VTable { vtable_offset })
+    };
+
+    let data_ptr = entry.get_member(db, data_ptr_name)?;
+    let offset = data_ptr
+        .udata_attr(db, gimli::DW_AT_data_member_location)
+        .context("could not find data pointer offset")?;
+    let pointer_type = data_ptr.get_referenced_entry(db, gimli::DW_AT_type)?;
+
+    Ok((offset, pointer_type, metadata))
+}
+
+/// Follows a member through single-field wrappers such as `Cell<usize>`,
+/// `AtomicU32` or `UnsafeCell<isize>` down to the primitive they contain
+///
+/// Returns the offset of that primitive relative to the struct holding `member`.
+fn scalar_member_offset(db: &dyn DwarfDb, member: Die) -> anyhow::Result<usize> {
+    let mut offset = member.udata_attr(db, gimli::DW_AT_data_member_location)?;
+    let mut ty = member.get_referenced_entry(db, gimli::DW_AT_type)?;
+    loop {
+        match ty.tag(db) {
+            gimli::DW_TAG_base_type => return Ok(offset),
+            gimli::DW_TAG_structure_type => {
+                let field = ty.get_member_by_tag(db, gimli::DW_TAG_member)?;
+                offset += field.udata_attr(db, gimli::DW_AT_data_member_location)?;
+                ty = field.get_referenced_entry(db, gimli::DW_AT_type)?;
+            }
+            tag => anyhow::bail!(
+                "{}",
+                ty.format_with_location(db, format!("expected a primitive, found {tag}"))
+            ),
+        }
+    }
+}
+
+/// Resolve `PathBuf`, `OsString` and `CString`, and their borrowed forms
+///
+/// References are wide pointers holding the bytes directly. The owned types
+/// are wrappers around wrappers (e.g. `PathBuf { inner: OsString { inner: Buf
+/// { inner: Vec<u8> } } }`), so we follow the `inner` members down to the
+/// `Vec<u8>` or, for `CString`, the `Box<[u8]>` holding the bytes.
+fn resolve_os_string_type(
+    db: &dyn DwarfDb,
+    entry: Die,
+    variant: OsStringVariant,
+) -> Result<OsStringLayout> {
+    let mut offset = 0;
+    let mut ty = entry;
+    loop {
+        if ty.get_member(db, "data_ptr").is_ok() {
+            let data_ptr_offset = ty
+                .get_udata_member_attribute(db, "data_ptr", gimli::DW_AT_data_member_location)
+                .context("could not find data_ptr")?;
+            let length_offset = ty
+                .get_udata_member_attribute(db, "length", gimli::DW_AT_data_member_location)
+                .context("could not find length")?;
+            return Ok(OsStringLayout {
+                variant,
+                data_ptr_offset: offset + data_ptr_offset,
+                length_offset: offset + length_offset,
+                path_style: path_style(db, entry),
+            });
+        }
+
+        if ty.name(db).is_ok_and(|name| name.starts_with("Vec<")) {
+            let vec = vec().parse(db, ty)?;
+            return Ok(OsStringLayout {
+                variant,
+                data_ptr_offset: offset + vec.data_ptr_offset,
+                length_offset: offset + vec.length_offset,
+                path_style: path_style(db, entry),
+            });
+        }
+
+        let inner = ty.get_member(db, "inner").with_context(|| {
+            ty.format_with_location(db, format!("could not find bytes of {}", variant.name()))
+        })?;
+        offset += inner.udata_attr(db, gimli::DW_AT_data_member_location)?;
+        ty = inner.get_referenced_entry(db, gimli::DW_AT_type)?;
+    }
+}
+
+/// The path syntax of the target, which only uses Windows paths in PE files
+fn path_style(db: &dyn DwarfDb, entry: Die) -> PathStyle {
+    let format = crate::file::load(db, entry.file.file(db))
+        .as_ref()
+        .map(|loaded| loaded.object.format());
+    match format {
+        Ok(object::BinaryFormat::Pe | object::BinaryFormat::Coff) => PathStyle::Windows,
+        _ => PathStyle::Unix,
+    }
+}
+
 fn resolve_tuple_type(db: &dyn DwarfDb, entry: Die) -> Result<TupleLayout<Die>> {
     let mut elements = Vec::new();
     let size = entry
@@ -238,6 +501,24 @@ fn resolve_primitive_type<L: Location + Clone>(
         PrimitiveLayout::Unit(_) => PrimitiveLayout::Unit(UnitLayout),
 
         // these types need to be resolved further
+        // wide pointers to `str` (`&mut str`, `*const str`, ...) are
+        // laid out just like `&str`
+        PrimitiveLayout::Pointer(PointerLayout { pointed_type, .. })
+        | PrimitiveLayout::Reference(ReferenceLayout { pointed_type, .. })
+            if matches!(
+                pointed_type.layout.as_ref(),
+                Layout::Primitive(PrimitiveLayout::Str(_))
+            ) =>
+        {
+            return resolve_primitive_type(
+                db,
+                entry,
+                &PrimitiveLayout::<L>::StrSlice(StrSliceLayout {
+                    data_ptr_offset: 0,
+                    length_offset: 0,
+                }),
+            );
+        }
         PrimitiveLayout::StrSlice(_) => {
             let data_ptr_offset = entry
                 .get_udata_member_attribute(db, "data_ptr", gimli::DW_AT_data_member_location)
@@ -380,15 +661,40 @@ fn resolve_as_builtin_type(db: &dyn DwarfDb, entry: Die) -> Result<Option<DieTyp
                         .parse(db, entry)
                         .map(|v| Some(Layout::Std(StdLayout::Vec(v))))?)
                 }
+                StdLayout::VecDeque(_) => Ok(vec_deque()
+                    .parse(db, entry)
+                    .map(|v| Some(Layout::Std(StdLayout::VecDeque(v))))?),
+                StdLayout::LinkedList(_) => Ok(linked_list()
+                    .parse(db, entry)
+                    .map(|l| Some(Layout::Std(StdLayout::LinkedList(l))))?),
+                StdLayout::BinaryHeap(_) => Ok(binary_heap()
+                    .parse(db, entry)
+                    .map(|h| Some(Layout::Std(StdLayout::BinaryHeap(h))))?),
                 StdLayout::String(_) => {
                     // String has a known layout similar to Vec
                     resolve_string_type(db, entry).map(|s| Some(Layout::Std(StdLayout::String(s))))
                 }
+                StdLayout::OsString(os_string) => {
+                    // PathBuf/OsString/CString wrap the bytes in a Vec or boxed slice
+                    resolve_os_string_type(db, entry, os_string.variant)
+                        .map(|s| Some(Layout::Std(StdLayout::OsString(s))))
+                }
                 StdLayout::Map(map) => {
                     // HashMap/BTreeMap need layout resolution
                     resolve_map_type(db, entry, map.variant.clone())
                         .map(|m| Some(Layout::Std(StdLayout::Map(m))))
                 }
+                StdLayout::Set(set) => {
+                    // HashSet/BTreeSet resolve through their inner map
+                    resolve_set_type(db, entry, set.map.variant.clone())
+                        .map(|s| Some(Layout::Std(StdLayout::Set(s))))
+                }
+                StdLayout::Cow(_) => {
+                    // Cow is an enum over the borrowed and owned data
+                    Ok(Some(Layout::Std(StdLayout::Cow(
+                        cow_def().parse(db, entry)?,
+                    ))))
+                }
                 StdLayout::Result(_) => {
                     // Result types need layout resolution
                     Ok(Some(Layout::Std(StdLayout::Result(resolve_result_type(
@@ -397,7 +703,7 @@ fn resolve_as_builtin_type(db: &dyn DwarfDb, entry: Die) -> Result<Option<DieTyp
                 }
                 StdLayout::SmartPtr(s) => {
                     // Smart pointers like Box, Rc, Arc need layout resolution
-                    resolve_smart_ptr_type(db, entry, s.variant)
+                    resolve_smart_ptr_type(db, entry, s)
                         .map(|s| Some(Layout::Std(StdLayout::SmartPtr(s))))
                 }
             }
@@ -407,10 +713,22 @@ fn resolve_as_builtin_type(db: &dyn DwarfDb, entry: Die) -> Result<Option<DieTyp
             // They'll be resolved through the normal struct resolution path
             Ok(None)
         }
+        Layout::Union(_) => {
+            // Unions are resolved from their DWARF union type
+            Ok(None)
+        }
         Layout::Enum(_) => {
             // For custom enums, we don't handle them as builtins
             Ok(None)
         }
+        Layout::Coroutine(_) => {
+            // Coroutines are detected from their DWARF structure, not their name
+            Ok(None)
+        }
+        Layout::Closure(_) => {
+            // Closures are resolved from their environment struct
+            Ok(None)
+        }
         Layout::CEnum(_) => {
             // C enums are handled as custom enums, not builtins
             Ok(None)
@@ -458,6 +776,22 @@ fn resolve_result_type(db: &dyn DwarfDb, entry: Die) -> Result<ResultLayout<Die>
     Ok(result_def().parse(db, entry)?)
 }
 
+fn resolve_union_type(db: &dyn DwarfDb, entry: Die) -> Result<UnionLayout<Die>> {
+    // unions are described just like structs, but all fields live at offset 0
+    let StructLayout {
+        name,
+        size,
+        alignment,
+        fields,
+    } = resolve_struct_type(db, entry)?;
+    Ok(UnionLayout {
+        name,
+        size,
+        alignment,
+        fields,
+    })
+}
+
 fn resolve_struct_type(db: &dyn DwarfDb, entry: Die) -> Result<StructLayout<Die>> {
     // let index = crate::index::build_index(db);
     // let type_name = index.data(db).die_to_type_name.get(&entry).copied();
@@ -531,18 +865,24 @@ fn resolve_type_offset_tracked<'db>(
         gimli::DW_TAG_structure_type => {
             // rustc uses `structure_type` for a bunch of things.
             // we need to do a little investigation to figure out what it actually is
-            let is_enum = entry
+            let variant_part = entry
                 .children(db)?
-                .iter()
-                .any(|c| c.tag(db) == gimli::DW_TAG_variant_part);
+                .into_iter()
+                .find(|c| c.tag(db) == gimli::DW_TAG_variant_part);
 
-            if is_enum {
+            if variant_part.is_some_and(|v| is_coroutine_variant_part(db, v)) {
+                // async fns and blocks are enums over their suspension points
+                Layout::Coroutine(coroutine_def().parse(db, entry)?)
+            } else if variant_part.is_some() {
                 Layout::Enum(resolve_enum_type(db, entry)?)
+            } else if entry.name(db).is_ok_and(|name| is_closure_env_name(&name)) {
+                Layout::Closure(closure_def().parse(db, entry)?)
             } else {
                 // we have a struct type and it's _not_ a builtin -- we'll handle it now
                 Layout::Struct(resolve_struct_type(db, entry)?)
             }
         }
+        gimli::DW_TAG_union_type => Layout::Union(resolve_union_type(db, entry)?),
         gimli::DW_TAG_subroutine_type => {
             let return_type = entry
                 .get_referenced_entry(db, gimli::DW_AT_type)
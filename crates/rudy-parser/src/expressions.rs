@@ -63,6 +63,12 @@ pub enum Expression {
         function: String,
         args: Vec<Expression>,
     },
+
+    /// Half-open range, with optional bounds (e.g., `1..10`, `start..`, `..`)
+    Range {
+        start: Option<Box<Expression>>,
+        end: Option<Box<Expression>>,
+    },
 }
 
 impl fmt::Display for Expression {
@@ -90,6 +96,16 @@ impl fmt::Display for Expression {
             Expression::FunctionCall { function, args } => {
                 write!(f, "{function}({})", args.iter().join(", "))
             }
+            Expression::Range { start, end } => {
+                if let Some(start) = start {
+                    write!(f, "{start}")?;
+                }
+                write!(f, "..")?;
+                if let Some(end) = end {
+                    write!(f, "{end}")?;
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -101,6 +117,8 @@ enum Token {
     Number(u64),
     String(String),
     Dot,
+    /// '..'
+    DotDot,
     Star,
     Ampersand,
     /// '['
@@ -230,7 +248,12 @@ impl<'a> Tokenizer<'a> {
             None => Ok(Token::Eof),
             Some('.') => {
                 self.advance();
-                Ok(Token::Dot)
+                if self.current_char() == Some('.') {
+                    self.advance();
+                    Ok(Token::DotDot)
+                } else {
+                    Ok(Token::Dot)
+                }
             }
             Some('*') => {
                 self.advance();
@@ -352,7 +375,27 @@ impl Parser {
     }
 
     fn parse_expression(&mut self) -> Result<Expression> {
-        self.parse_unary()
+        self.parse_range()
+    }
+
+    /// Ranges bind the loosest, so `a.b..c[0]` is `(a.b)..(c[0])`
+    fn parse_range(&mut self) -> Result<Expression> {
+        let start = if matches!(self.current_token(), Token::DotDot) {
+            None
+        } else {
+            let start = self.parse_unary()?;
+            if !matches!(self.current_token(), Token::DotDot) {
+                return Ok(start);
+            }
+            Some(Box::new(start))
+        };
+        self.advance(); // consume '..'
+
+        let end = match self.current_token() {
+            Token::RightParen | Token::RightBracket | Token::Comma | Token::Eof => None,
+            _ => Some(Box::new(self.parse_unary()?)),
+        };
+        Ok(Expression::Range { start, end })
     }
 
     fn parse_unary(&mut self) -> Result<Expression> {
@@ -405,6 +448,14 @@ impl Parser {
                                 field,
                             };
                         }
+                    } else if let Token::Number(index) = self.current_token() {
+                        // tuple element access, e.g. `pair.0`
+                        let field = index.to_string();
+                        self.advance();
+                        expr = Expression::FieldAccess {
+                            base: Box::new(expr),
+                            field,
+                        };
                     } else {
                         return Err(anyhow!("Expected field name after '.'"));
                     }
@@ -645,6 +696,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_tuple_field_access() {
+        let expr = parse("pair.0.1");
+        assert_eq!(
+            expr,
+            Expression::FieldAccess {
+                base: Box::new(Expression::FieldAccess {
+                    base: Box::new(Expression::Variable("pair".to_string())),
+                    field: "0".to_string(),
+                }),
+                field: "1".to_string(),
+            }
+        );
+    }
+
     #[test]
     fn test_index_access() {
         let expr = parse("arr[0]");
@@ -894,6 +960,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_range_argument() {
+        let expr = parse("index.range(10..0x20)");
+        assert_eq!(
+            expr,
+            Expression::MethodCall {
+                base: Box::new(Expression::Variable("index".to_string())),
+                method: "range".to_string(),
+                args: vec![Expression::Range {
+                    start: Some(Box::new(Expression::NumberLiteral(10))),
+                    end: Some(Box::new(Expression::NumberLiteral(0x20))),
+                }],
+            }
+        );
+
+        // Open ranges, with bounds that are themselves expressions
+        let expr = parse("m.range(..limits.max)");
+        assert_eq!(
+            expr,
+            Expression::MethodCall {
+                base: Box::new(Expression::Variable("m".to_string())),
+                method: "range".to_string(),
+                args: vec![Expression::Range {
+                    start: None,
+                    end: Some(Box::new(Expression::FieldAccess {
+                        base: Box::new(Expression::Variable("limits".to_string())),
+                        field: "max".to_string(),
+                    })),
+                }],
+            }
+        );
+        assert_eq!(parse(r#"m.range("a"..)"#).to_string(), r#"m.range("a"..)"#);
+        assert_eq!(parse("pair.0..pair.1").to_string(), "pair.0..pair.1");
+    }
+
     #[test]
     fn test_method_call_display() {
         assert_eq!(parse("vec.len()").to_string(), "vec.len()");
@@ -8,4 +8,4 @@ pub mod expressions;
 pub mod types;
 
 pub use expressions::{Expression, parse_expression};
-pub use types::{ParsedSymbol, Path, Type, parse_symbol, parse_type};
+pub use types::{ParsedSymbol, Path, Type, parse_symbol, parse_trait_impl, parse_type};
@@ -447,6 +447,48 @@ pub fn parse_symbol(s: &str) -> anyhow::Result<ParsedSymbol> {
     Ok((module_path, function_name, hash))
 }
 
+/// Splits a trait implementation segment of a symbol path into
+/// the implementing type and the trait
+///
+/// Demangled symbols use one of two forms:
+///
+/// - `<alloc::string::String as core::fmt::Debug>`
+/// - `<impl core::fmt::Debug for alloc::string::String>`
+///
+/// Returns `None` for any other segment, including inherent impls like `<impl Foo>`.
+pub fn parse_trait_impl(segment: &str) -> Option<(String, String)> {
+    /// Splits `s` at the first occurrence of `separator` outside of any brackets
+    fn split_top_level<'a>(s: &'a str, separator: &str) -> Option<(&'a str, &'a str)> {
+        let mut depth = 0usize;
+        let mut prev = None;
+        for (idx, ch) in s.char_indices() {
+            match ch {
+                '<' | '(' | '[' => depth += 1,
+                // `->` in function pointer types is not a closing bracket
+                '>' if prev == Some('-') => {}
+                '>' | ')' | ']' => depth = depth.saturating_sub(1),
+                _ if depth == 0 && s[idx..].starts_with(separator) => {
+                    return Some((&s[..idx], &s[idx + separator.len()..]));
+                }
+                _ => {}
+            }
+            prev = Some(ch);
+        }
+        None
+    }
+
+    let inner = segment.strip_prefix('<')?.strip_suffix('>')?;
+    let (type_name, trait_name) = match inner.strip_prefix("impl ") {
+        Some(rest) => {
+            let (trait_name, type_name) = split_top_level(rest, " for ")?;
+            (type_name, trait_name)
+        }
+        None => split_top_level(inner, " as ")?,
+    };
+
+    Some((type_name.trim().to_string(), trait_name.trim().to_string()))
+}
+
 pub fn parse_type(s: &str) -> unsynn::Result<Type> {
     let mut iter = s.to_token_iter();
     let ty = Cons::<Type, EndOfStream>::parse(&mut iter)?;
@@ -476,6 +518,17 @@ impl Type {
                 }))
             }
             Type::Ref(ref_type) => {
+                if let Type::Path(path) = ref_type.inner.as_ref()
+                    && let Some(variant) = path.as_borrowed_os_str()
+                {
+                    // `&Path`, `&OsStr` and `&CStr` are wide pointers to the bytes
+                    return Layout::Std(StdLayout::OsString(OsStringLayout {
+                        variant,
+                        data_ptr_offset: 0,
+                        length_offset: 0,
+                        path_style: PathStyle::Unix,
+                    }));
+                }
                 let inner = ref_type.inner.as_layout();
                 Layout::Primitive(PrimitiveLayout::Reference(ReferenceLayout {
                     mutable: ref_type.is_mutable(),
@@ -548,6 +601,20 @@ impl Type {
 }
 
 impl Path {
+    /// Detects the unsized std string types that are only ever seen behind a reference
+    fn as_borrowed_os_str(&self) -> Option<OsStringVariant> {
+        let segments = self.segments();
+        if !matches!(segments.first().map(String::as_str), Some("std" | "core")) {
+            return None;
+        }
+        match segments.last()?.as_str() {
+            "Path" => Some(OsStringVariant::Path),
+            "OsStr" => Some(OsStringVariant::OsStr),
+            "CStr" => Some(OsStringVariant::CStr),
+            _ => None,
+        }
+    }
+
     fn as_typedef(&self) -> Layout {
         // First, let's extract the segments
         let segments = self.segments();
@@ -608,10 +675,11 @@ impl Path {
         // Check if this is a standard library type by examining the path
         let is_std = segments[0] == "std" || segments[0] == "core" || segments[0] == "alloc";
         let is_hashbrown = segments[0] == "hashbrown";
+        let is_indexmap = segments[0] == "indexmap";
 
         tracing::trace!("Parser segments: {:?}, is_std: {}", segments, is_std);
 
-        if is_std || is_hashbrown || segments.len() == 1 {
+        if is_std || is_hashbrown || is_indexmap || segments.len() == 1 {
             // Parse the last segment for generic types
             // (we're guaranteed to have at least one segment here)
             if let Some(path_segment) = self.segments.0.last()
@@ -651,6 +719,19 @@ impl Path {
                             ),
                         })));
                     }
+                    "PathBuf" | "OsString" | "CString" => {
+                        let variant = match type_name.as_str() {
+                            "PathBuf" => OsStringVariant::PathBuf,
+                            "OsString" => OsStringVariant::OsString,
+                            _ => OsStringVariant::CString,
+                        };
+                        return Layout::Std(StdLayout::OsString(OsStringLayout {
+                            variant,
+                            data_ptr_offset: 0,
+                            length_offset: 0,
+                            path_style: PathStyle::Unix,
+                        }));
+                    }
                     "Vec" => {
                         let inner = get_generics()
                             .first()
@@ -670,6 +751,43 @@ impl Path {
                             capacity_offset: 0,
                         }));
                     }
+                    "VecDeque" | "LinkedList" | "BinaryHeap" => {
+                        let inner = get_generics()
+                            .first()
+                            .map(|t| TypeDefinition::new((), t.as_layout()))
+                            .unwrap_or_else(|| {
+                                TypeDefinition::new(
+                                    (),
+                                    Layout::Alias {
+                                        name: "Unknown".to_string(),
+                                    },
+                                )
+                            });
+                        tracing::trace!("Matched collection type: '{type_name}'");
+                        return Layout::Std(match type_name.as_str() {
+                            "VecDeque" => StdLayout::VecDeque(VecDequeLayout {
+                                inner_type: inner,
+                                head_offset: 0,
+                                length_offset: 0,
+                                data_ptr_offset: 0,
+                                capacity_offset: 0,
+                            }),
+                            "LinkedList" => StdLayout::LinkedList(LinkedListLayout {
+                                inner_type: inner,
+                                head_offset: 0,
+                                length_offset: 0,
+                                next_offset: 0,
+                                element_offset: 0,
+                            }),
+                            "BinaryHeap" => StdLayout::BinaryHeap(BinaryHeapLayout(VecLayout {
+                                inner_type: inner,
+                                length_offset: 0,
+                                data_ptr_offset: 0,
+                                capacity_offset: 0,
+                            })),
+                            _ => unreachable!(),
+                        });
+                    }
                     "Option" => {
                         let inner = get_generics()
                             .first()
@@ -723,14 +841,65 @@ impl Path {
                                 offset: 0,
                                 ty: DiscriminantType::Implicit,
                             },
+                            ok_discriminant: None,
                             ok_type,
                             ok_offset: 0,
+                            err_discriminant: None,
                             err_type,
                             err_offset: 0,
                             size: 0,
                         }));
                     }
-                    "HashMap" | "BTreeMap" => {
+                    "Cow" => {
+                        let borrowed = get_generics().into_iter().next();
+                        let name = match &borrowed {
+                            Some(borrowed) => format!("Cow<{borrowed}>"),
+                            None => "Cow".to_string(),
+                        };
+                        let borrowed_type = borrowed
+                            .map(|t| {
+                                TypeDefinition::new(
+                                    (),
+                                    Layout::Primitive(PrimitiveLayout::Reference(
+                                        ReferenceLayout {
+                                            mutable: false,
+                                            pointed_type: TypeDefinition::new((), t.as_layout()),
+                                        },
+                                    )),
+                                )
+                            })
+                            .unwrap_or_else(|| {
+                                TypeDefinition::new(
+                                    (),
+                                    Layout::Alias {
+                                        name: "Unknown".to_string(),
+                                    },
+                                )
+                            });
+                        // the owned type is `<B as ToOwned>::Owned`, which
+                        // we only learn from the DWARF layout
+                        let owned_type = TypeDefinition::new(
+                            (),
+                            Layout::Alias {
+                                name: "Unknown".to_string(),
+                            },
+                        );
+                        return Layout::Std(StdLayout::Cow(CowLayout {
+                            name,
+                            discriminant: Discriminant {
+                                offset: 0,
+                                ty: DiscriminantType::Implicit,
+                            },
+                            borrowed_discriminant: None,
+                            borrowed_type,
+                            borrowed_offset: 0,
+                            owned_discriminant: None,
+                            owned_type,
+                            owned_offset: 0,
+                            size: 0,
+                        }));
+                    }
+                    "HashMap" | "BTreeMap" | "IndexMap" => {
                         let mut generics_iter = get_generics().into_iter();
                         let key_type = generics_iter
                             .next()
@@ -754,31 +923,7 @@ impl Path {
                                     },
                                 )
                             });
-                        let variant = match type_name.as_str() {
-                            "HashMap" => MapVariant::HashMap {
-                                bucket_mask_offset: 0,
-                                ctrl_offset: 0,
-                                items_offset: 0,
-                                pair_size: 0,
-                                key_offset: 0,
-                                value_offset: 0,
-                            },
-                            "BTreeMap" => MapVariant::BTreeMap {
-                                length_offset: 0,
-                                root_offset: 0,
-                                root_layout: BTreeRootLayout {
-                                    node_offset: 0,
-                                    height_offset: 0,
-                                },
-                                node_layout: BTreeNodeLayout {
-                                    keys_offset: 0,
-                                    vals_offset: 0,
-                                    len_offset: 0,
-                                    edges_offset: 0,
-                                },
-                            },
-                            _ => unreachable!(),
-                        };
+                        let variant = placeholder_map_variant(&type_name);
                         tracing::trace!("Matched Map type: '{type_name}'");
                         return Layout::Std(StdLayout::Map(MapLayout {
                             key_type,
@@ -786,8 +931,36 @@ impl Path {
                             variant,
                         }));
                     }
-                    "Box" | "Rc" | "Arc" | "Cell" | "RefCell" | "UnsafeCell" | "Mutex"
-                    | "RwLock" => {
+                    "HashSet" | "BTreeSet" | "IndexSet" => {
+                        let element_type = get_generics()
+                            .into_iter()
+                            .next()
+                            .map(|t| TypeDefinition::new((), t.as_layout()))
+                            .unwrap_or_else(|| {
+                                TypeDefinition::new(
+                                    (),
+                                    Layout::Alias {
+                                        name: "Unknown".to_string(),
+                                    },
+                                )
+                            });
+                        let variant = placeholder_map_variant(&type_name);
+                        tracing::trace!("Matched Set type: '{type_name}'");
+                        return Layout::Std(StdLayout::Set(SetLayout {
+                            map: MapLayout {
+                                key_type: element_type.clone(),
+                                value_type: TypeDefinition::new(
+                                    (),
+                                    Layout::Primitive(PrimitiveLayout::Unit(UnitLayout)),
+                                ),
+                                variant,
+                            },
+                            element_type,
+                            map_offset: 0,
+                        }));
+                    }
+                    "Box" | "Rc" | "Arc" | "Weak" | "Cell" | "RefCell" | "UnsafeCell" | "Mutex"
+                    | "RwLock" | "ManuallyDrop" | "MaybeUninit" => {
                         let inner = get_generics()
                             .into_iter()
                             .next()
@@ -809,6 +982,9 @@ impl Path {
                             "UnsafeCell" => SmartPtrVariant::UnsafeCell,
                             "Mutex" => SmartPtrVariant::Mutex,
                             "RwLock" => SmartPtrVariant::RwLock,
+                            "Weak" => SmartPtrVariant::Weak,
+                            "ManuallyDrop" => SmartPtrVariant::ManuallyDrop,
+                            "MaybeUninit" => SmartPtrVariant::MaybeUninit,
                             _ => unreachable!(),
                         };
                         return Layout::Std(StdLayout::SmartPtr(SmartPtrLayout {
@@ -816,6 +992,8 @@ impl Path {
                             inner_ptr_offset: 0,
                             data_ptr_offset: 0,
                             variant,
+                            state: SmartPtrState::None,
+                            metadata: PointerMetadata::None,
                         }));
                     }
                     _ => {}
@@ -830,6 +1008,52 @@ impl Path {
     }
 }
 
+/// Map variant with zeroed offsets for a std map (or set) type name,
+/// to be filled in once the DWARF layout is resolved
+fn placeholder_map_variant(type_name: &str) -> MapVariant {
+    match type_name {
+        "HashMap" | "HashSet" => MapVariant::HashMap {
+            bucket_mask_offset: 0,
+            ctrl_offset: 0,
+            items_offset: 0,
+            pair_size: 0,
+            key_offset: 0,
+            value_offset: 0,
+            group_width: 0,
+            random_state: None,
+        },
+        "BTreeMap" | "BTreeSet" => MapVariant::BTreeMap {
+            length_offset: 0,
+            root_offset: 0,
+            root_layout: BTreeRootLayout {
+                node_offset: 0,
+                height_offset: 0,
+            },
+            node_layout: BTreeNodeLayout {
+                keys_offset: 0,
+                vals_offset: 0,
+                len_offset: 0,
+                edges_offset: 0,
+                edge_size: 0,
+            },
+        },
+        "IndexMap" | "IndexSet" => MapVariant::IndexMap {
+            size: 0,
+            entries_ptr_offset: 0,
+            entries_len_offset: 0,
+            bucket_size: 0,
+            key_offset: 0,
+            value_offset: 0,
+            indices_bucket_mask_offset: 0,
+            indices_ctrl_offset: 0,
+            indices_items_offset: 0,
+            group_width: 0,
+            random_state: None,
+        },
+        _ => unreachable!("not a map type: {type_name}"),
+    }
+}
+
 #[cfg(test)]
 mod test {
     use itertools::Itertools;
@@ -1058,8 +1282,93 @@ mod test {
                 variant: SmartPtrVariant::Box,
                 inner_ptr_offset: 0,
                 data_ptr_offset: 0,
+                state: SmartPtrState::None,
+                metadata: PointerMetadata::None,
+            },
+        );
+        infer(
+            "alloc::sync::Weak<i32>",
+            SmartPtrLayout {
+                inner_type: TypeDefinition::new((), IntLayout::i32().into()),
+                variant: SmartPtrVariant::Weak,
+                inner_ptr_offset: 0,
+                data_ptr_offset: 0,
+                state: SmartPtrState::None,
+                metadata: PointerMetadata::None,
             },
         );
+        infer(
+            "core::mem::maybe_uninit::MaybeUninit<i32>",
+            SmartPtrLayout {
+                inner_type: TypeDefinition::new((), IntLayout::i32().into()),
+                variant: SmartPtrVariant::MaybeUninit,
+                inner_ptr_offset: 0,
+                data_ptr_offset: 0,
+                state: SmartPtrState::None,
+                metadata: PointerMetadata::None,
+            },
+        );
+        infer(
+            "alloc::borrow::Cow<str>",
+            StdLayout::Cow(CowLayout {
+                name: "Cow<str>".to_string(),
+                discriminant: Discriminant {
+                    offset: 0,
+                    ty: DiscriminantType::Implicit,
+                },
+                borrowed_discriminant: None,
+                borrowed_type: TypeDefinition::new(
+                    (),
+                    Layout::Primitive(PrimitiveLayout::Str(())).as_reference(()),
+                ),
+                borrowed_offset: 0,
+                owned_discriminant: None,
+                owned_type: TypeDefinition::new(
+                    (),
+                    Layout::Alias {
+                        name: "Unknown".to_string(),
+                    },
+                ),
+                owned_offset: 0,
+                size: 0,
+            }),
+        );
+        infer(
+            "std::path::PathBuf",
+            StdLayout::OsString(OsStringLayout {
+                variant: OsStringVariant::PathBuf,
+                data_ptr_offset: 0,
+                length_offset: 0,
+                path_style: PathStyle::Unix,
+            }),
+        );
+        infer(
+            "alloc::ffi::c_str::CString",
+            StdLayout::OsString(OsStringLayout {
+                variant: OsStringVariant::CString,
+                data_ptr_offset: 0,
+                length_offset: 0,
+                path_style: PathStyle::Unix,
+            }),
+        );
+        infer(
+            "&std::ffi::os_str::OsStr",
+            StdLayout::OsString(OsStringLayout {
+                variant: OsStringVariant::OsStr,
+                data_ptr_offset: 0,
+                length_offset: 0,
+                path_style: PathStyle::Unix,
+            }),
+        );
+        infer(
+            "&core::ffi::c_str::CStr",
+            StdLayout::OsString(OsStringLayout {
+                variant: OsStringVariant::CStr,
+                data_ptr_offset: 0,
+                length_offset: 0,
+                path_style: PathStyle::Unix,
+            }),
+        );
         infer(
             "alloc::String::String",
             string_def().layout.as_ref().clone(),
@@ -1076,6 +1385,69 @@ mod test {
                     pair_size: 0,
                     key_offset: 0,
                     value_offset: 0,
+                    group_width: 0,
+                    random_state: None,
+                },
+            },
+        );
+
+        infer(
+            "std::collections::hash::set::HashSet<u32, std::hash::random::RandomState>",
+            SetLayout {
+                element_type: TypeDefinition::new((), UnsignedIntLayout::u32().into()),
+                map_offset: 0,
+                map: MapLayout {
+                    key_type: TypeDefinition::new((), UnsignedIntLayout::u32().into()),
+                    value_type: TypeDefinition::new(
+                        (),
+                        Layout::Primitive(PrimitiveLayout::Unit(UnitLayout)),
+                    ),
+                    variant: MapVariant::HashMap {
+                        bucket_mask_offset: 0,
+                        ctrl_offset: 0,
+                        items_offset: 0,
+                        pair_size: 0,
+                        key_offset: 0,
+                        value_offset: 0,
+                        group_width: 0,
+                        random_state: None,
+                    },
+                },
+            },
+        );
+
+        infer(
+            "alloc::collections::vec_deque::VecDeque<i32, alloc::alloc::Global>",
+            VecDequeLayout {
+                inner_type: TypeDefinition::new((), IntLayout::i32().into()),
+                head_offset: 0,
+                length_offset: 0,
+                data_ptr_offset: 0,
+                capacity_offset: 0,
+            },
+        );
+        infer(
+            "alloc::collections::binary_heap::BinaryHeap<u64>",
+            BinaryHeapLayout(VecLayout::new(UnsignedIntLayout::u64())),
+        );
+
+        infer(
+            "indexmap::map::IndexMap<alloc::string::String, i32, std::hash::random::RandomState>",
+            MapLayout {
+                key_type: string_def(),
+                value_type: TypeDefinition::new((), IntLayout::i32().into()),
+                variant: MapVariant::IndexMap {
+                    size: 0,
+                    entries_ptr_offset: 0,
+                    entries_len_offset: 0,
+                    bucket_size: 0,
+                    key_offset: 0,
+                    value_offset: 0,
+                    indices_bucket_mask_offset: 0,
+                    indices_ctrl_offset: 0,
+                    indices_items_offset: 0,
+                    group_width: 0,
+                    random_state: None,
                 },
             },
         );
@@ -1111,11 +1483,13 @@ mod test {
                             offset: 0,
                             ty: DiscriminantType::Implicit,
                         },
+                        ok_discriminant: None,
                         ok_type: TypeDefinition::new(
                             (),
                             Layout::Primitive(PrimitiveLayout::Unit(UnitLayout)),
                         ),
                         ok_offset: 0,
+                        err_discriminant: None,
                         err_type: TypeDefinition::new(
                             (),
                             Layout::Alias {
@@ -1214,6 +1588,39 @@ mod test {
         assert_eq!(hash, Some("hec874816052de6db".to_string()));
     }
 
+    #[test]
+    fn test_trait_impl_parsing() {
+        assert_eq!(
+            parse_trait_impl("<alloc::string::String as core::fmt::Debug>"),
+            Some((
+                "alloc::string::String".to_string(),
+                "core::fmt::Debug".to_string()
+            ))
+        );
+        assert_eq!(
+            parse_trait_impl(
+                "<impl core::convert::From<&core::ffi::c_str::CStr> for alloc::boxed::Box<core::ffi::c_str::CStr>>"
+            ),
+            Some((
+                "alloc::boxed::Box<core::ffi::c_str::CStr>".to_string(),
+                "core::convert::From<&core::ffi::c_str::CStr>".to_string()
+            ))
+        );
+        assert_eq!(
+            parse_trait_impl("<alloc::vec::Vec<<T as Trait>::Item> as core::ops::drop::Drop>"),
+            Some((
+                "alloc::vec::Vec<<T as Trait>::Item>".to_string(),
+                "core::ops::drop::Drop".to_string()
+            ))
+        );
+        assert_eq!(
+            parse_trait_impl("<fn() -> u32 as core::fmt::Debug>"),
+            Some(("fn() -> u32".to_string(), "core::fmt::Debug".to_string()))
+        );
+        assert_eq!(parse_trait_impl("<impl alloc::string::String>"), None);
+        assert_eq!(parse_trait_impl("NonZero<u8>"), None);
+    }
+
     #[test]
     fn test_symbol_parsing_errors() {
         // Test empty string
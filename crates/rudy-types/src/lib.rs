@@ -50,12 +50,25 @@ impl<L: Location> Layout<L> {
             Layout::Std(std_def) => match std_def {
                 StdLayout::SmartPtr(smart_ptr_def) => {
                     let inner = smart_ptr_def.inner_type.display_name();
+                    // unsized slices keep their element type as the inner type
+                    let inner = match smart_ptr_def.metadata {
+                        PointerMetadata::Length { .. }
+                            if !matches!(
+                                smart_ptr_def.inner_type.layout.as_ref(),
+                                Layout::Primitive(PrimitiveLayout::Str(_))
+                            ) =>
+                        {
+                            format!("[{inner}]")
+                        }
+                        _ => inner,
+                    };
                     match smart_ptr_def.variant {
                         SmartPtrVariant::Box => format!("Box<{inner}>"),
                         _ => format!("{:?}<{}>", smart_ptr_def.variant, inner),
                     }
                 }
                 StdLayout::Map(map_def) => map_def.display_name(),
+                StdLayout::Set(set_def) => set_def.display_name(),
                 StdLayout::Option(option_def) => {
                     let inner_type = option_def.some_type.display_name();
                     format!("Option<{inner_type}>")
@@ -65,14 +78,31 @@ impl<L: Location> Layout<L> {
                     let err_type = result_def.err_type.display_name();
                     format!("Result<{ok_type}, {err_type}>")
                 }
+                StdLayout::Cow(cow_def) => cow_def.name.clone(),
                 StdLayout::String(_) => "String".to_string(),
+                StdLayout::OsString(os_string_def) => os_string_def.variant.name().to_string(),
                 StdLayout::Vec(vec_def) => {
                     let inner_type = vec_def.inner_type.display_name();
                     format!("Vec<{inner_type}>")
                 }
+                StdLayout::VecDeque(vec_deque_def) => {
+                    let inner_type = vec_deque_def.inner_type.display_name();
+                    format!("VecDeque<{inner_type}>")
+                }
+                StdLayout::LinkedList(list_def) => {
+                    let inner_type = list_def.inner_type.display_name();
+                    format!("LinkedList<{inner_type}>")
+                }
+                StdLayout::BinaryHeap(heap_def) => {
+                    let inner_type = heap_def.0.inner_type.display_name();
+                    format!("BinaryHeap<{inner_type}>")
+                }
             },
             Layout::Struct(struct_def) => struct_def.name.clone(),
+            Layout::Union(union_def) => union_def.name.clone(),
             Layout::Enum(enum_def) => enum_def.name.clone(),
+            Layout::Coroutine(coroutine_def) => coroutine_def.name.clone(),
+            Layout::Closure(closure_def) => closure_def.name.clone(),
             Layout::CEnum(c_enum_def) => c_enum_def.name.clone(),
             Layout::Alias { name } => name.to_string(),
         }
@@ -83,7 +113,10 @@ impl<L: Location> Layout<L> {
             Layout::Primitive(primitive_def) => primitive_def.size(),
             Layout::Std(std_def) => std_def.size(),
             Layout::Struct(struct_def) => Some(struct_def.size),
+            Layout::Union(union_def) => Some(union_def.size),
             Layout::Enum(enum_def) => Some(enum_def.size),
+            Layout::Coroutine(coroutine_def) => Some(coroutine_def.size),
+            Layout::Closure(closure_def) => Some(closure_def.size),
             Layout::CEnum(c_enum_def) => Some(c_enum_def.size),
             Layout::Alias { name: _ } => None,
         }
@@ -95,9 +128,12 @@ impl<L: Location> Layout<L> {
             (Layout::Std(s1), Layout::Std(s2)) => s1.matching_type(s2),
             (Layout::Struct(s1), Layout::Struct(s2)) => s1.name == s2.name,
             (Layout::Struct(s1), Layout::Alias { name }) => &s1.name == name,
+            (Layout::Union(u1), Layout::Union(u2)) => u1.name == u2.name,
             (Layout::Alias { name: left }, Layout::Alias { name: right }) => left == right,
             (Layout::Alias { name }, x) | (x, Layout::Alias { name }) => name == &x.display_name(),
             (Layout::Enum(e1), Layout::Enum(e2)) => e1.name == e2.name,
+            (Layout::Coroutine(c1), Layout::Coroutine(c2)) => c1.name == c2.name,
+            (Layout::Closure(c1), Layout::Closure(c2)) => c1.name == c2.name,
             (Layout::CEnum(e1), Layout::CEnum(e2)) => e1.name == e2.name,
             _ => false,
         }
@@ -148,6 +184,12 @@ impl<L: Location> From<StructLayout<L>> for Layout<L> {
     }
 }
 
+impl<L: Location> From<UnionLayout<L>> for Layout<L> {
+    fn from(union_def: UnionLayout<L>) -> Self {
+        Layout::Union(union_def)
+    }
+}
+
 impl<L: Location> From<EnumLayout<L>> for Layout<L> {
     fn from(enum_def: EnumLayout<L>) -> Self {
         Layout::Enum(enum_def)
@@ -234,6 +276,12 @@ impl<L: Location> From<MapLayout<L>> for StdLayout<L> {
     }
 }
 
+impl<L: Location> From<SetLayout<L>> for StdLayout<L> {
+    fn from(set: SetLayout<L>) -> Self {
+        StdLayout::Set(set)
+    }
+}
+
 impl<L: Location> From<StringLayout<L>> for StdLayout<L> {
     fn from(string: StringLayout<L>) -> Self {
         StdLayout::String(string)
@@ -246,6 +294,24 @@ impl<L: Location> From<VecLayout<L>> for StdLayout<L> {
     }
 }
 
+impl<L: Location> From<VecDequeLayout<L>> for StdLayout<L> {
+    fn from(vec_deque: VecDequeLayout<L>) -> Self {
+        StdLayout::VecDeque(vec_deque)
+    }
+}
+
+impl<L: Location> From<LinkedListLayout<L>> for StdLayout<L> {
+    fn from(list: LinkedListLayout<L>) -> Self {
+        StdLayout::LinkedList(list)
+    }
+}
+
+impl<L: Location> From<BinaryHeapLayout<L>> for StdLayout<L> {
+    fn from(heap: BinaryHeapLayout<L>) -> Self {
+        StdLayout::BinaryHeap(heap)
+    }
+}
+
 // Convenience constructors for primitives with unit values
 impl<L: Location> From<()> for PrimitiveLayout<L> {
     fn from(_: ()) -> Self {
@@ -290,12 +356,36 @@ impl<L: Location> From<VecLayout<L>> for Layout<L> {
     }
 }
 
+impl<L: Location> From<VecDequeLayout<L>> for Layout<L> {
+    fn from(vec_deque: VecDequeLayout<L>) -> Self {
+        Layout::Std(StdLayout::VecDeque(vec_deque))
+    }
+}
+
+impl<L: Location> From<LinkedListLayout<L>> for Layout<L> {
+    fn from(list: LinkedListLayout<L>) -> Self {
+        Layout::Std(StdLayout::LinkedList(list))
+    }
+}
+
+impl<L: Location> From<BinaryHeapLayout<L>> for Layout<L> {
+    fn from(heap: BinaryHeapLayout<L>) -> Self {
+        Layout::Std(StdLayout::BinaryHeap(heap))
+    }
+}
+
 impl<L: Location> From<MapLayout<L>> for Layout<L> {
     fn from(map: MapLayout<L>) -> Self {
         Layout::Std(StdLayout::Map(map))
     }
 }
 
+impl<L: Location> From<SetLayout<L>> for Layout<L> {
+    fn from(set: SetLayout<L>) -> Self {
+        Layout::Std(StdLayout::Set(set))
+    }
+}
+
 impl<L: Location> From<SmartPtrLayout<L>> for Layout<L> {
     fn from(smart_ptr: SmartPtrLayout<L>) -> Self {
         Layout::Std(StdLayout::SmartPtr(smart_ptr))
@@ -371,9 +461,18 @@ where
     /// Structs and tuples
     Struct(StructLayout<L>),
 
+    /// Unions, whose fields all overlap
+    Union(UnionLayout<L>),
+
     /// Enums
     Enum(EnumLayout<L>),
 
+    /// Coroutines, such as the futures of `async fn`s and `async` blocks
+    Coroutine(CoroutineLayout<L>),
+
+    /// Closures, i.e. the environment holding their captured variables
+    Closure(ClosureLayout<L>),
+
     /// C-style enumerations (simple named integer constants)
     CEnum(CEnumLayout),
 
@@ -442,7 +541,10 @@ impl<L: Location> PrimitiveLayout<L> {
                 0
             }
             PrimitiveLayout::Slice(_) => size_of::<&[u8]>(),
-            PrimitiveLayout::Str(_) => unimplemented!(),
+            PrimitiveLayout::Str(_) => {
+                // str is unsized, its length lives in the pointer to it
+                return None;
+            }
             PrimitiveLayout::StrSlice(_) => size_of::<&str>(),
             PrimitiveLayout::Tuple(tuple_def) => tuple_def.size,
             PrimitiveLayout::Unit(_) => 0,
@@ -618,15 +720,24 @@ where
 {
     SmartPtr(SmartPtrLayout<L>),
     Map(MapLayout<L>),
+    Set(SetLayout<L>),
     Option(OptionLayout<L>),
     Result(ResultLayout<L>),
+    Cow(CowLayout<L>),
     String(StringLayout<L>),
+    OsString(OsStringLayout),
     Vec(VecLayout<L>),
+    VecDeque(VecDequeLayout<L>),
+    LinkedList(LinkedListLayout<L>),
+    BinaryHeap(BinaryHeapLayout<L>),
 }
 
 impl<L: Location> StdLayout<L> {
     fn size(&self) -> Option<usize> {
         let size = match self {
+            StdLayout::SmartPtr(smart_ptr_def) if smart_ptr_def.metadata.is_wide() => {
+                size_of::<Box<str>>()
+            }
             StdLayout::SmartPtr(smart_ptr_def) => match smart_ptr_def.variant {
                 SmartPtrVariant::Box => size_of::<Box<()>>(),
                 SmartPtrVariant::Rc => size_of::<std::rc::Rc<()>>(),
@@ -636,15 +747,31 @@ impl<L: Location> StdLayout<L> {
                 SmartPtrVariant::RwLock => size_of::<std::sync::RwLock<()>>(),
                 SmartPtrVariant::Cell => size_of::<std::cell::Cell<()>>(),
                 SmartPtrVariant::UnsafeCell => size_of::<std::cell::UnsafeCell<()>>(),
+                SmartPtrVariant::Weak => size_of::<std::rc::Weak<()>>(),
+                // transparent wrappers are the size of what they hold
+                SmartPtrVariant::ManuallyDrop | SmartPtrVariant::MaybeUninit => {
+                    smart_ptr_def.inner_type.size()?
+                }
             },
             StdLayout::Map(map_def) => match map_def.variant {
                 MapVariant::HashMap { .. } => size_of::<std::collections::HashMap<(), ()>>(),
                 MapVariant::BTreeMap { .. } => size_of::<std::collections::BTreeMap<(), ()>>(),
-                MapVariant::IndexMap => unimplemented!(),
+                MapVariant::IndexMap { size, .. } => size,
+            },
+            StdLayout::Set(set_def) => match set_def.map.variant {
+                MapVariant::HashMap { .. } => size_of::<std::collections::HashSet<()>>(),
+                MapVariant::BTreeMap { .. } => size_of::<std::collections::BTreeSet<()>>(),
+                // an IndexSet is a newtype around an IndexMap
+                MapVariant::IndexMap { size, .. } => size,
             },
             StdLayout::Option(def) => def.size,
             StdLayout::Result(def) => def.size,
+            StdLayout::Cow(def) => def.size,
             StdLayout::String(_) | StdLayout::Vec(_) => size_of::<Vec<()>>(),
+            StdLayout::OsString(def) => def.variant.size(),
+            StdLayout::VecDeque(_) => size_of::<std::collections::VecDeque<()>>(),
+            StdLayout::LinkedList(_) => size_of::<std::collections::LinkedList<()>>(),
+            StdLayout::BinaryHeap(_) => size_of::<std::collections::BinaryHeap<()>>(),
         };
 
         Some(size)
@@ -660,12 +787,26 @@ impl<L: Location> StdLayout<L> {
                     && l.key_type.matching_type(&r.key_type)
                     && l.value_type.matching_type(&r.value_type)
             }
+            (StdLayout::Set(l), StdLayout::Set(r)) => {
+                l.map.variant == r.map.variant && l.element_type.matching_type(&r.element_type)
+            }
             (StdLayout::Option(l), StdLayout::Option(r)) => l.some_type.matching_type(&r.some_type),
             (StdLayout::Result(l), StdLayout::Result(r)) => {
                 l.ok_type.matching_type(&r.ok_type) && l.err_type.matching_type(&r.err_type)
             }
+            (StdLayout::Cow(l), StdLayout::Cow(r)) => l.name == r.name,
             (StdLayout::String(_), StdLayout::String(_)) => true,
+            (StdLayout::OsString(l), StdLayout::OsString(r)) => l.variant == r.variant,
             (StdLayout::Vec(l), StdLayout::Vec(r)) => l.inner_type.matching_type(&r.inner_type),
+            (StdLayout::VecDeque(l), StdLayout::VecDeque(r)) => {
+                l.inner_type.matching_type(&r.inner_type)
+            }
+            (StdLayout::LinkedList(l), StdLayout::LinkedList(r)) => {
+                l.inner_type.matching_type(&r.inner_type)
+            }
+            (StdLayout::BinaryHeap(l), StdLayout::BinaryHeap(r)) => {
+                l.0.inner_type.matching_type(&r.0.inner_type)
+            }
             _ => false,
         }
     }
@@ -680,6 +821,65 @@ where
     pub inner_ptr_offset: usize,
     pub data_ptr_offset: usize,
     pub variant: SmartPtrVariant,
+    pub state: SmartPtrState,
+    pub metadata: PointerMetadata,
+}
+
+/// Metadata stored next to the data pointer of a smart pointer to unsized data
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Update)]
+pub enum PointerMetadata {
+    /// A thin pointer to sized data
+    None,
+    /// The length of `str` or `[T]` data
+    ///
+    /// For slices, the inner type of the smart pointer is the element type.
+    Length { length_offset: usize },
+    /// The vtable of a trait object
+    VTable { vtable_offset: usize },
+}
+
+impl PointerMetadata {
+    /// Whether the pointer is a fat (pointer + metadata) pointer
+    pub fn is_wide(&self) -> bool {
+        !matches!(self, PointerMetadata::None)
+    }
+
+    /// Shifts the metadata location by `offset`, for wide pointers nested in another struct
+    pub fn offset_by(self, offset: usize) -> Self {
+        match self {
+            PointerMetadata::None => PointerMetadata::None,
+            PointerMetadata::Length { length_offset } => PointerMetadata::Length {
+                length_offset: length_offset + offset,
+            },
+            PointerMetadata::VTable { vtable_offset } => PointerMetadata::VTable {
+                vtable_offset: vtable_offset + offset,
+            },
+        }
+    }
+}
+
+/// Bookkeeping state stored alongside the data of a smart pointer
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Update)]
+pub enum SmartPtrState {
+    /// No state beyond the data itself (`Box`, `Cell`, `UnsafeCell`, `ManuallyDrop`, `MaybeUninit`)
+    None,
+    /// Strong and weak reference counts (`Rc`, `Arc`, `Weak`)
+    ///
+    /// Offsets are relative to the start of the shared allocation.
+    RefCounts {
+        strong_offset: usize,
+        weak_offset: usize,
+    },
+    /// The `isize` borrow flag of a `RefCell`
+    BorrowFlag { offset: usize },
+    /// The lock state word and poison flag of a `Mutex` or `RwLock`
+    ///
+    /// The state word is only known for futex-based locks; other platforms
+    /// keep their lock state behind a pointer we don't follow.
+    Lock {
+        state_offset: Option<usize>,
+        poison_offset: usize,
+    },
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash, Update, Copy)]
@@ -692,6 +892,10 @@ pub enum SmartPtrVariant {
     RwLock,
     Cell,
     UnsafeCell,
+    Weak,
+    ManuallyDrop,
+    /// `MaybeUninit`, which may or may not hold an initialized value
+    MaybeUninit,
 }
 
 impl SmartPtrVariant {
@@ -705,6 +909,9 @@ impl SmartPtrVariant {
             SmartPtrVariant::RwLock => "RwLock",
             SmartPtrVariant::Cell => "Cell",
             SmartPtrVariant::UnsafeCell => "UnsafeCell",
+            SmartPtrVariant::Weak => "Weak",
+            SmartPtrVariant::ManuallyDrop => "ManuallyDrop",
+            SmartPtrVariant::MaybeUninit => "MaybeUninit",
         }
     }
 }
@@ -730,6 +937,29 @@ impl<L: Location> MapLayout<L> {
     }
 }
 
+/// Layout of a `HashSet` or `BTreeSet`, which wrap a map with zero-sized values
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Update)]
+pub struct SetLayout<L = ()>
+where
+    L: Location,
+{
+    pub element_type: TypeDefinition<L>,
+    /// Offset to the wrapped map within the set
+    pub map_offset: usize,
+    /// Layout of the wrapped map, keyed by the set elements
+    pub map: MapLayout<L>,
+}
+
+impl<L: Location> SetLayout<L> {
+    pub fn display_name(&self) -> String {
+        format!(
+            "{}<{}>",
+            self.map.variant.set_name(),
+            self.element_type.display_name()
+        )
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Hash, Update)]
 pub struct BTreeRootLayout {
     pub node_offset: usize,   // offset to node field within Root
@@ -742,17 +972,27 @@ pub struct BTreeNodeLayout {
     pub vals_offset: usize,  // offset to vals array in LeafNode
     pub len_offset: usize,   // offset to len field in LeafNode
     pub edges_offset: usize, // offset to edges array in InternalNode
+    pub edge_size: usize,    // size of each edge (a node pointer)
+}
+
+/// The SipHash keys of std's `RandomState` hasher
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Update)]
+pub struct RandomStateLayout {
+    pub k0_offset: usize, // offset to the first key within the map
+    pub k1_offset: usize, // offset to the second key within the map
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash, Update)]
 pub enum MapVariant {
     HashMap {
-        bucket_mask_offset: usize, // offset within RawTableInner
-        ctrl_offset: usize,        // offset to ctrl pointer
-        items_offset: usize,       // offset to items count
-        pair_size: usize,          // size of a single key-value pair
-        key_offset: usize,         // offset to key within a pair
-        value_offset: usize,       // offset to value within a pair
+        bucket_mask_offset: usize,               // offset within RawTableInner
+        ctrl_offset: usize,                      // offset to ctrl pointer
+        items_offset: usize,                     // offset to items count
+        pair_size: usize,                        // size of a single key-value pair
+        key_offset: usize,                       // offset to key within a pair
+        value_offset: usize,                     // offset to value within a pair
+        group_width: usize, // number of control bytes probed at a time on the target
+        random_state: Option<RandomStateLayout>, // present when the hasher is std's RandomState
     },
     BTreeMap {
         length_offset: usize,         // offset to length field in BTreeMap
@@ -760,7 +1000,19 @@ pub enum MapVariant {
         root_layout: BTreeRootLayout, // layout of the root structure
         node_layout: BTreeNodeLayout, // layout of the node structures
     },
-    IndexMap,
+    IndexMap {
+        size: usize,                       // size of the IndexMap, which depends on the hasher
+        entries_ptr_offset: usize,         // offset to the data pointer of the entries Vec
+        entries_len_offset: usize,         // offset to the length of the entries Vec
+        bucket_size: usize,                // size of a single Bucket<K, V> entry
+        key_offset: usize,                 // offset to key within a bucket
+        value_offset: usize,               // offset to value within a bucket
+        indices_bucket_mask_offset: usize, // offset to the bucket mask of the hash index table
+        indices_ctrl_offset: usize,        // offset to the ctrl pointer of the hash index table
+        indices_items_offset: usize,       // offset to the item count of the hash index table
+        group_width: usize,                // number of control bytes probed at a time on the target
+        random_state: Option<RandomStateLayout>, // present when the hasher is std's RandomState
+    },
 }
 
 impl MapVariant {
@@ -768,7 +1020,16 @@ impl MapVariant {
         match self {
             MapVariant::HashMap { .. } => "HashMap",
             MapVariant::BTreeMap { .. } => "BTreeMap",
-            MapVariant::IndexMap => "IndexMap",
+            MapVariant::IndexMap { .. } => "IndexMap",
+        }
+    }
+
+    /// Name of the set type built on top of this map variant
+    pub fn set_name(&self) -> &'static str {
+        match self {
+            MapVariant::HashMap { .. } => "HashSet",
+            MapVariant::BTreeMap { .. } => "BTreeSet",
+            MapVariant::IndexMap { .. } => "IndexSet",
         }
     }
 }
@@ -778,6 +1039,77 @@ pub struct StringLayout<L = ()>(pub VecLayout<L>)
 where
     L: Location;
 
+/// Byte strings that aren't guaranteed to be UTF-8, such as paths and C strings
+///
+/// Offsets locate the pointer to and length of the bytes, relative to the
+/// start of the owned value or the reference.
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Update)]
+pub struct OsStringLayout {
+    pub variant: OsStringVariant,
+    pub data_ptr_offset: usize,
+    pub length_offset: usize,
+    /// How the target splits paths, which isn't necessarily how we do
+    pub path_style: PathStyle,
+}
+
+/// The path syntax of the target platform
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Update)]
+pub enum PathStyle {
+    /// `/`-separated paths that are absolute when they start with `/`
+    Unix,
+    /// `\`- or `/`-separated paths that are absolute when they start with a
+    /// drive (e.g. `C:\`) or a UNC prefix (e.g. `\\server\share`)
+    Windows,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Update)]
+pub enum OsStringVariant {
+    PathBuf,
+    /// `&Path`
+    Path,
+    OsString,
+    /// `&OsStr`
+    OsStr,
+    CString,
+    /// `&CStr`
+    CStr,
+}
+
+impl OsStringVariant {
+    pub fn name(&self) -> &'static str {
+        match self {
+            OsStringVariant::PathBuf => "PathBuf",
+            OsStringVariant::Path => "&Path",
+            OsStringVariant::OsString => "OsString",
+            OsStringVariant::OsStr => "&OsStr",
+            OsStringVariant::CString => "CString",
+            OsStringVariant::CStr => "&CStr",
+        }
+    }
+
+    /// Whether the bytes end with a nul terminator (which isn't part of the string)
+    pub fn is_nul_terminated(&self) -> bool {
+        matches!(self, OsStringVariant::CString | OsStringVariant::CStr)
+    }
+
+    /// Whether this is a path, which supports the path synthetic methods
+    pub fn is_path(&self) -> bool {
+        matches!(self, OsStringVariant::PathBuf | OsStringVariant::Path)
+    }
+
+    fn size(&self) -> usize {
+        match self {
+            OsStringVariant::PathBuf => size_of::<std::path::PathBuf>(),
+            OsStringVariant::OsString => size_of::<std::ffi::OsString>(),
+            OsStringVariant::CString => size_of::<std::ffi::CString>(),
+            // references to unsized data are wide pointers
+            OsStringVariant::Path | OsStringVariant::OsStr | OsStringVariant::CStr => {
+                size_of::<&str>()
+            }
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Hash, Update)]
 pub struct VecLayout<L = ()>
 where
@@ -804,6 +1136,46 @@ impl<L: Location + Default> VecLayout<L> {
     }
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Update)]
+pub struct VecDequeLayout<L = ()>
+where
+    L: Location,
+{
+    /// Offset to the index of the first element in the ring buffer (deque.head)
+    pub head_offset: usize,
+    /// Offset to the length field (deque.len)
+    pub length_offset: usize,
+    /// Offset to the data pointer (deque.buf.inner.ptr.pointer)
+    pub data_ptr_offset: usize,
+    /// Offset to the capacity value (deque.buf.inner.cap.__0)
+    pub capacity_offset: usize,
+    /// The element type of the VecDeque
+    pub inner_type: TypeDefinition<L>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Update)]
+pub struct LinkedListLayout<L = ()>
+where
+    L: Location,
+{
+    /// Offset to the pointer to the first node (list.head)
+    pub head_offset: usize,
+    /// Offset to the length field (list.len)
+    pub length_offset: usize,
+    /// Offset to the pointer to the next node within a node (node.next)
+    pub next_offset: usize,
+    /// Offset to the element within a node (node.element)
+    pub element_offset: usize,
+    /// The element type of the LinkedList
+    pub inner_type: TypeDefinition<L>,
+}
+
+/// A `BinaryHeap<T>` is a `Vec<T>` kept in heap order
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Update)]
+pub struct BinaryHeapLayout<L = ()>(pub VecLayout<L>)
+where
+    L: Location;
+
 #[derive(Debug, PartialEq, Eq, Clone, Hash, Update)]
 pub struct StructLayout<L = ()>
 where
@@ -824,6 +1196,18 @@ where
     pub ty: TypeDefinition<L>,
 }
 
+/// A `union`, where every field is a different interpretation of the same memory
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Update)]
+pub struct UnionLayout<L = ()>
+where
+    L: Location,
+{
+    pub name: String,
+    pub size: usize,
+    pub alignment: usize,
+    pub fields: Vec<StructField<L>>,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Hash, Update)]
 pub struct Discriminant {
     pub ty: DiscriminantType,
@@ -872,6 +1256,140 @@ where
     pub layout: TypeDefinition<L>,
 }
 
+/// A coroutine state machine, stored like an enum with one variant per state
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Update)]
+pub struct CoroutineLayout<L = ()>
+where
+    L: Location,
+{
+    pub name: String,
+    /// The `__state` field of the coroutine
+    pub discriminant: Discriminant,
+    pub states: Vec<CoroutineStateLayout<L>>,
+    pub size: usize,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Update)]
+pub struct CoroutineStateLayout<L = ()>
+where
+    L: Location,
+{
+    pub state: CoroutineState,
+    /// The discriminant value for this state, if known
+    pub discriminant: Option<i128>,
+    /// Where a suspended coroutine is parked (i.e. the await or yield point)
+    pub location: Option<SourcePosition>,
+    /// The values saved across this state, e.g. arguments and live locals
+    pub layout: TypeDefinition<L>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Update)]
+pub enum CoroutineState {
+    /// Created but never polled
+    Unresumed,
+    /// Ran to completion
+    Returned,
+    /// Panicked while being polled
+    Panicked,
+    /// Parked at the n-th suspension point
+    Suspended(usize),
+}
+
+impl CoroutineState {
+    /// Parses the name rustc gives to the variant of a state, e.g. `Suspend0`
+    pub fn from_variant_name(name: &str) -> Option<Self> {
+        match name {
+            "Unresumed" => Some(CoroutineState::Unresumed),
+            "Returned" => Some(CoroutineState::Returned),
+            "Panicked" => Some(CoroutineState::Panicked),
+            _ => name
+                .strip_prefix("Suspend")
+                .and_then(|index| index.parse().ok())
+                .map(CoroutineState::Suspended),
+        }
+    }
+}
+
+impl std::fmt::Display for CoroutineState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CoroutineState::Unresumed => write!(f, "Unresumed"),
+            CoroutineState::Returned => write!(f, "Returned"),
+            CoroutineState::Panicked => write!(f, "Panicked"),
+            CoroutineState::Suspended(index) => write!(f, "Suspend{index}"),
+        }
+    }
+}
+
+/// A closure environment, i.e. the captured variables of a closure
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Update)]
+pub struct ClosureLayout<L = ()>
+where
+    L: Location,
+{
+    /// A readable name for the closure, e.g. `my_crate::main::{closure@main.rs:8:27}`
+    ///
+    /// Falls back to the environment type name (e.g.
+    /// `my_crate::main::{closure_env#0}`) when the location is unknown.
+    pub name: String,
+    /// The path of the function the closure is defined in, e.g. `my_crate::main`
+    pub enclosing_function: String,
+    /// Where the closure is defined
+    ///
+    /// The column is that of the start of the closure body.
+    pub location: Option<SourcePosition>,
+    pub captures: Vec<ClosureCapture<L>>,
+    pub size: usize,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Update)]
+pub struct ClosureCapture<L = ()>
+where
+    L: Location,
+{
+    /// The captured variable, or place for disjoint captures (e.g. `self.name`)
+    pub name: String,
+    pub mode: CaptureMode,
+    pub offset: usize,
+    /// The type stored in the environment, i.e. a reference for by-ref captures
+    pub ty: TypeDefinition<L>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Update)]
+pub enum CaptureMode {
+    /// Captured by (shared or mutable) reference
+    ByRef,
+    /// Moved (or copied) into the closure
+    ByMove,
+}
+
+impl std::fmt::Display for CaptureMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CaptureMode::ByRef => write!(f, "by ref"),
+            CaptureMode::ByMove => write!(f, "by move"),
+        }
+    }
+}
+
+/// A line (and optionally column) in a source file
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Update)]
+pub struct SourcePosition {
+    pub file: String,
+    pub line: u64,
+    pub column: Option<u64>,
+}
+
+impl std::fmt::Display for SourcePosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.file, self.line)?;
+        if let Some(column) = self.column {
+            write!(f, ":{column}")?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Hash, Update)]
 pub struct OptionLayout<L = ()>
 where
@@ -891,13 +1409,42 @@ where
 {
     pub name: String,
     pub discriminant: Discriminant,
+    /// The discriminant value of the `Ok` variant, if known
+    ///
+    /// If `None` while the discriminant is explicit, `Ok` is the dataful
+    /// variant of a niche-packed layout and matches any other value.
+    pub ok_discriminant: Option<i128>,
     pub ok_type: TypeDefinition<L>,
     pub ok_offset: usize,
+    /// The discriminant value of the `Err` variant, if known
+    pub err_discriminant: Option<i128>,
     pub err_type: TypeDefinition<L>,
     pub err_offset: usize,
     pub size: usize,
 }
 
+/// `Cow<'_, B>`, holding either borrowed data or its owned counterpart
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Update)]
+pub struct CowLayout<L = ()>
+where
+    L: Location,
+{
+    pub name: String,
+    pub discriminant: Discriminant,
+    /// The discriminant value of the `Borrowed` variant, if known
+    ///
+    /// As with `Result`, a missing value marks the dataful variant of a
+    /// niche-packed layout.
+    pub borrowed_discriminant: Option<i128>,
+    pub borrowed_type: TypeDefinition<L>,
+    pub borrowed_offset: usize,
+    /// The discriminant value of the `Owned` variant, if known
+    pub owned_discriminant: Option<i128>,
+    pub owned_type: TypeDefinition<L>,
+    pub owned_offset: usize,
+    pub size: usize,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Hash, Update)]
 pub struct CEnumLayout {
     pub name: String,
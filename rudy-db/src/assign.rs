@@ -0,0 +1,582 @@
+//! Encoding values back into the target's memory.
+//!
+//! A value is first encoded into a list of writes according to the layout of
+//! the type being assigned to, and the writes are only made once the whole
+//! value encoded cleanly, so a rejected assignment leaves memory untouched.
+//!
+//! Only values that fit in the existing memory can be written: assigning a
+//! `String`, `Vec` or `Box` would need to allocate in the target, and is
+//! refused.
+//!
+//! `None` can only be written to an `Option` whose `None` is a null niche,
+//! e.g. `Option<&T>`, `Option<Box<T>>` or `Option<NonZeroU32>`, or which has
+//! a separate discriminant. Other niches, such as those of `Option<bool>`,
+//! `Option<char>` or `Option<Ordering>`, use a value the payload type can't
+//! take, which the debug info doesn't record.
+
+use anyhow::{Context, Result};
+use rudy_dwarf::{Die, types::DieTypeDefinition};
+use rudy_types::{
+    CEnumLayout, DiscriminantType, Layout, OptionLayout, PrimitiveLayout, StdLayout, StructLayout,
+};
+
+use crate::{
+    Value,
+    data::{names_variant, resolve_alias, type_path},
+    database::Db,
+    outputs::TypedPointer,
+};
+
+/// Bytes to write to the target, and where to write them
+type Write = (u64, Vec<u8>);
+
+/// Encodes `value` according to the type of `pointer` and writes it to memory
+pub(crate) fn write_value(
+    db: &dyn Db,
+    pointer: &TypedPointer,
+    value: &Value,
+    data_resolver: &dyn crate::DataResolver,
+) -> Result<()> {
+    let mut writes = vec![];
+    encode(db, pointer.address, &pointer.type_def, value, &mut writes)?;
+    for (address, bytes) in writes {
+        data_resolver.write_memory(address, &bytes)?;
+    }
+    Ok(())
+}
+
+/// Parses a Rust-like literal, e.g. `-42`, `'x'`, `Color::Red`, `Some(1.5)`
+/// or `(1, true)`, into a value that can be written with [`write_value`]
+///
+/// Scalars are left as text to be interpreted once the type they are assigned
+/// to is known.
+pub(crate) fn parse_literal(literal: &str) -> Result<Value> {
+    let literal = literal.trim();
+    if literal.is_empty() {
+        anyhow::bail!("Expected a value to assign");
+    }
+    if literal == "None" {
+        return Ok(Value::Enum {
+            ty: "Option".to_string(),
+            variant: "None".to_string(),
+            discriminant: 0,
+            payload: None,
+        });
+    }
+    if let Some(inner) = literal
+        .strip_prefix("Some(")
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        return Ok(Value::Enum {
+            ty: "Option".to_string(),
+            variant: "Some".to_string(),
+            discriminant: 1,
+            payload: Some(Box::new(Value::Tuple {
+                ty: "Option::Some".to_string(),
+                entries: vec![parse_literal(inner)?],
+            })),
+        });
+    }
+    if let Some(inner) = literal
+        .strip_prefix('(')
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        let entries = split_top_level(inner)
+            .into_iter()
+            .filter(|entry| !entry.trim().is_empty())
+            .map(parse_literal)
+            .collect::<Result<Vec<_>>>()?;
+        // `(x)` is just a parenthesized `x`, while `(x,)` is a tuple
+        if entries.len() == 1 && !inner.trim_end().ends_with(',') {
+            return Ok(entries.into_iter().next().unwrap());
+        }
+        return Ok(Value::Tuple {
+            ty: "tuple".to_string(),
+            entries,
+        });
+    }
+    Ok(Value::Scalar {
+        ty: "literal".to_string(),
+        value: literal.to_string(),
+    })
+}
+
+/// Splits on the commas that are not nested in brackets or quotes
+fn split_top_level(text: &str) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut escaped = false;
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' => quote = Some(c),
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(&text[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+fn encode(
+    db: &dyn Db,
+    address: u64,
+    def: &DieTypeDefinition,
+    value: &Value,
+    writes: &mut Vec<Write>,
+) -> Result<()> {
+    let def = resolve_alias(db, def)?;
+    let type_name = def.display_name();
+    match def.layout.as_ref() {
+        Layout::Primitive(primitive) => match primitive {
+            PrimitiveLayout::Int(int) => {
+                writes.push((address, encode_int(scalar(value)?, int.size, true)?));
+            }
+            PrimitiveLayout::UnsignedInt(int) => {
+                writes.push((address, encode_int(scalar(value)?, int.size, false)?));
+            }
+            PrimitiveLayout::Float(float) => {
+                let text = scalar(value)?;
+                let bytes = match float.size {
+                    4 => parse_float::<f32>(text, "f32")?.to_le_bytes().to_vec(),
+                    8 => parse_float::<f64>(text, "f64")?.to_le_bytes().to_vec(),
+                    size => anyhow::bail!("Writing {}-byte floats is not supported", size),
+                };
+                writes.push((address, bytes));
+            }
+            PrimitiveLayout::Bool(()) => {
+                let bool_value = match scalar(value)? {
+                    "true" => 1,
+                    "false" => 0,
+                    other => anyhow::bail!("'{other}' is not a bool, expected true or false"),
+                };
+                writes.push((address, vec![bool_value]));
+            }
+            PrimitiveLayout::Char(()) => {
+                let char_value = parse_char(scalar(value)?)?;
+                writes.push((address, (char_value as u32).to_le_bytes().to_vec()));
+            }
+            PrimitiveLayout::Pointer(_) | PrimitiveLayout::Reference(_) => {
+                // only thin pointers: a new pointee for a fat pointer would
+                // need its metadata (length or vtable) to match
+                let pointer_size = def.location.address_size(db)?;
+                if def.layout.size() != Some(pointer_size) {
+                    anyhow::bail!("Writing fat pointers such as {type_name} is not supported");
+                }
+                writes.push((address, encode_int(scalar(value)?, pointer_size, false)?));
+            }
+            PrimitiveLayout::Tuple(tuple) => {
+                let Value::Tuple { entries, .. } = value else {
+                    anyhow::bail!(
+                        "Expected a tuple to assign to {type_name}, got {}",
+                        describe(value)
+                    );
+                };
+                if entries.len() != tuple.elements.len() {
+                    anyhow::bail!(
+                        "Expected a tuple of {} elements to assign to {type_name}, got {}",
+                        tuple.elements.len(),
+                        entries.len()
+                    );
+                }
+                for ((offset, element_type), entry) in tuple.elements.iter().zip(entries) {
+                    encode(db, address + *offset as u64, element_type, entry, writes)?;
+                }
+            }
+            PrimitiveLayout::Unit(_) => {
+                if !matches!(value, Value::Tuple { entries, .. } if entries.is_empty()) {
+                    anyhow::bail!("Expected () to assign to (), got {}", describe(value));
+                }
+            }
+            PrimitiveLayout::Str(()) | PrimitiveLayout::StrSlice(_) | PrimitiveLayout::Slice(_) => {
+                anyhow::bail!(
+                    "Assigning to {type_name} would need to allocate memory in the target, which is not supported"
+                )
+            }
+            PrimitiveLayout::Array(_)
+            | PrimitiveLayout::Function(_)
+            | PrimitiveLayout::Never(()) => {
+                anyhow::bail!("Assigning to values of type {type_name} is not supported")
+            }
+        },
+        Layout::CEnum(c_enum) => {
+            let enum_path = type_path(db, &def);
+            writes.push((address, encode_c_enum(c_enum, &enum_path, value)?));
+        }
+        Layout::Std(StdLayout::Option(option)) => {
+            encode_option(db, address, option, value, writes)?;
+        }
+        Layout::Std(
+            StdLayout::String(_)
+            | StdLayout::OsString(_)
+            | StdLayout::Vec(_)
+            | StdLayout::VecDeque(_)
+            | StdLayout::LinkedList(_)
+            | StdLayout::BinaryHeap(_)
+            | StdLayout::Map(_)
+            | StdLayout::Set(_)
+            | StdLayout::SmartPtr(_)
+            | StdLayout::Cow(_),
+        ) => anyhow::bail!(
+            "Assigning to {type_name} would need to allocate memory in the target, which is not supported"
+        ),
+        Layout::Struct(structure) => encode_struct(db, address, structure, value, writes)?,
+        _ => anyhow::bail!("Assigning to values of type {type_name} is not supported"),
+    }
+    Ok(())
+}
+
+/// The text of a scalar literal
+fn scalar(value: &Value) -> Result<&str> {
+    match value {
+        Value::Scalar { value, .. } => Ok(value),
+        _ => anyhow::bail!("Expected a scalar value, got {}", describe(value)),
+    }
+}
+
+/// A short description of a value for error messages
+fn describe(value: &Value) -> String {
+    match value {
+        Value::Scalar { value, .. } => value.clone(),
+        Value::Enum { variant, .. } => variant.clone(),
+        Value::Tuple { entries, .. } => format!("a tuple of {} elements", entries.len()),
+        Value::Struct { ty, .. } => format!("a {ty}"),
+        Value::Array { .. } | Value::Map { .. } | Value::Set { .. } => "a collection".to_string(),
+        Value::Truncated { .. } => "a truncated value".to_string(),
+        Value::Invalid { .. } => "an invalid value".to_string(),
+        Value::Suspect { value, .. } => describe(value),
+        Value::Pointer(pointer) => format!("a pointer to {}", pointer.type_def.display_name()),
+    }
+}
+
+/// Parses an integer literal, returning whether it is negative and its magnitude
+fn parse_integer(text: &str) -> Option<(bool, u128)> {
+    let digits = text.replace('_', "");
+    let (negative, digits) = match digits.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, digits.as_str()),
+    };
+    let magnitude = if let Some(hex) = digits.strip_prefix("0x") {
+        u128::from_str_radix(hex, 16)
+    } else if let Some(binary) = digits.strip_prefix("0b") {
+        u128::from_str_radix(binary, 2)
+    } else if let Some(octal) = digits.strip_prefix("0o") {
+        u128::from_str_radix(octal, 8)
+    } else {
+        digits.parse()
+    };
+    Some((negative, magnitude.ok()?))
+}
+
+/// Encodes an integer literal as a `size`-byte integer, checking that it fits
+fn encode_int(text: &str, size: usize, signed: bool) -> Result<Vec<u8>> {
+    let (negative, magnitude) =
+        parse_integer(text).with_context(|| format!("'{text}' is not an integer"))?;
+    let bits = size * 8;
+    if size == 0 || size > 16 {
+        anyhow::bail!("Writing {size}-byte integers is not supported");
+    }
+    let fits = if signed {
+        let min = i128::MIN >> (128 - bits);
+        let max = i128::MAX >> (128 - bits);
+        let value = if negative {
+            0i128.checked_sub_unsigned(magnitude)
+        } else {
+            i128::try_from(magnitude).ok()
+        };
+        value
+            .filter(|v| (min..=max).contains(v))
+            .map(|v| v.to_le_bytes()[..size].to_vec())
+            .ok_or_else(|| format!("i{bits} ({min}..={max})"))
+    } else {
+        let max = u128::MAX >> (128 - bits);
+        if (negative && magnitude != 0) || magnitude > max {
+            Err(format!("u{bits} (0..={max})"))
+        } else {
+            Ok(magnitude.to_le_bytes()[..size].to_vec())
+        }
+    };
+    fits.map_err(|range| anyhow::anyhow!("{text} is out of range for {range}"))
+}
+
+/// Parses a float, rejecting numbers too large for the type, which Rust's
+/// parser would quietly turn into infinity
+fn parse_float<F>(text: &str, ty: &str) -> Result<F>
+where
+    F: std::str::FromStr + Into<f64> + Copy,
+    F::Err: std::error::Error + Send + Sync + 'static,
+{
+    let value = text
+        .parse::<F>()
+        .with_context(|| format!("'{text}' is not a valid {ty}"))?;
+    let infinity = text
+        .trim_start_matches(['+', '-'])
+        .to_ascii_lowercase()
+        .starts_with("inf");
+    if value.into().is_infinite() && !infinity {
+        anyhow::bail!("{text} is out of range for {ty}");
+    }
+    Ok(value)
+}
+
+/// Parses a char literal, with or without quotes
+fn parse_char(text: &str) -> Result<char> {
+    let inner = text
+        .strip_prefix('\'')
+        .and_then(|rest| rest.strip_suffix('\''))
+        .unwrap_or(text);
+    let c = match inner {
+        "\\n" => '\n',
+        "\\r" => '\r',
+        "\\t" => '\t',
+        "\\0" => '\0',
+        "\\\\" => '\\',
+        "\\'" => '\'',
+        _ => {
+            let mut chars = inner.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => c,
+                _ => anyhow::bail!("{text} is not a char"),
+            }
+        }
+    };
+    Ok(c)
+}
+
+/// Encodes `value` as a discriminant of type `ty`
+fn encode_discriminant(value: i128, ty: &DiscriminantType) -> Result<Vec<u8>> {
+    let (size, signed) = match ty {
+        DiscriminantType::Int(int) => (int.size, true),
+        DiscriminantType::UnsignedInt(int) => (int.size, false),
+        DiscriminantType::Implicit => {
+            anyhow::bail!("Writing enums with an implicit discriminant is not supported")
+        }
+    };
+    encode_int(&value.to_string(), size, signed)
+}
+
+/// Encodes a variant of a C-like enum, named with or without its enum's path
+fn encode_c_enum(c_enum: &CEnumLayout, enum_path: &str, value: &Value) -> Result<Vec<u8>> {
+    let name = match value {
+        Value::Enum {
+            variant,
+            payload: None,
+            ..
+        } => variant.as_str(),
+        _ => scalar(value)?,
+    };
+    let variant = c_enum
+        .variants
+        .iter()
+        .find(|v| names_variant(name, enum_path, &v.name))
+        .with_context(|| {
+            format!(
+                "{} has no variant {name}, expected one of: {}",
+                c_enum.name,
+                c_enum
+                    .variants
+                    .iter()
+                    .map(|v| v.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        })?;
+    let mut bytes = encode_discriminant(variant.value, &c_enum.discriminant_type)?;
+    bytes.resize(c_enum.size.max(bytes.len()), 0);
+    Ok(bytes)
+}
+
+fn encode_option(
+    db: &dyn Db,
+    address: u64,
+    option: &OptionLayout<Die>,
+    value: &Value,
+    writes: &mut Vec<Write>,
+) -> Result<()> {
+    let OptionLayout {
+        name,
+        discriminant,
+        some_offset,
+        some_type,
+        ..
+    } = option;
+
+    let payload = match value {
+        Value::Enum {
+            variant, payload, ..
+        } if variant == "None" && payload.is_none() => None,
+        Value::Enum {
+            variant,
+            payload: Some(payload),
+            ..
+        } if variant == "Some" => match payload.as_ref() {
+            Value::Tuple { entries, .. } if entries.len() == 1 => Some(&entries[0]),
+            payload => Some(payload),
+        },
+        _ => anyhow::bail!(
+            "Expected None or Some(..) to assign to {name}, got {}",
+            describe(value)
+        ),
+    };
+
+    let some_type = resolve_alias(db, some_type)?;
+    let some_size = some_type
+        .size()
+        .with_context(|| format!("{} has unknown size", some_type.display_name()))?;
+    // with a niche, the discriminant is stored in invalid values of the payload
+    let niche_offset = (*some_offset..some_offset + some_size)
+        .contains(&discriminant.offset)
+        .then(|| discriminant.offset - some_offset);
+    let discriminant_address = address + discriminant.offset as u64;
+
+    match (payload, niche_offset) {
+        (None, None) => {
+            writes.push((discriminant_address, vec![0; discriminant.size()]));
+        }
+        (None, Some(niche_offset)) => {
+            if !has_null_niche(db, &some_type, niche_offset)? {
+                anyhow::bail!(
+                    "Cannot write None to {name}: it is stored as an invalid {} whose value the debug info doesn't record \
+                     (None can only be written to Options with a separate discriminant or a null niche, such as Option<&T> or Option<Box<T>>)",
+                    some_type.display_name()
+                );
+            }
+            writes.push((discriminant_address, vec![0; discriminant.size()]));
+        }
+        (Some(payload), None) => {
+            encode(
+                db,
+                address + *some_offset as u64,
+                &some_type,
+                payload,
+                writes,
+            )?;
+            writes.push((
+                discriminant_address,
+                encode_discriminant(1, &discriminant.ty)?,
+            ));
+        }
+        (Some(payload), Some(niche_offset)) => {
+            let start = writes.len();
+            encode(
+                db,
+                address + *some_offset as u64,
+                &some_type,
+                payload,
+                writes,
+            )?;
+            if has_null_niche(db, &some_type, niche_offset)?
+                && is_zeroed(&writes[start..], discriminant_address, discriminant.size())
+            {
+                anyhow::bail!(
+                    "Cannot write Some({}) to {name}: a null {} is not a valid value",
+                    describe(payload),
+                    some_type.display_name()
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Whether the niche at `offset` in a value of type `def` is a non-null
+/// pointer or a `NonZero` integer, so that `None` is stored as zero
+fn has_null_niche(db: &dyn Db, def: &DieTypeDefinition, offset: usize) -> Result<bool> {
+    let def = resolve_alias(db, def)?;
+    Ok(match def.layout.as_ref() {
+        Layout::Primitive(PrimitiveLayout::Pointer(_) | PrimitiveLayout::Reference(_)) => true,
+        Layout::Std(StdLayout::SmartPtr(_) | StdLayout::Vec(_) | StdLayout::String(_)) => true,
+        Layout::Struct(structure)
+            if structure.name.starts_with("NonZero") || structure.name.starts_with("NonNull") =>
+        {
+            true
+        }
+        Layout::Struct(structure) => {
+            let field = structure.fields.iter().find(|field| {
+                field
+                    .ty
+                    .size()
+                    .is_some_and(|size| (field.offset..field.offset + size).contains(&offset))
+            });
+            match field {
+                Some(field) => has_null_niche(db, &field.ty, offset - field.offset)?,
+                None => false,
+            }
+        }
+        _ => false,
+    })
+}
+
+/// Whether `writes` zero every byte in `address..address + size`
+fn is_zeroed(writes: &[Write], address: u64, size: usize) -> bool {
+    (address..address + size as u64).all(|byte_address| {
+        writes.iter().rev().any(|(start, bytes)| {
+            byte_address
+                .checked_sub(*start)
+                .and_then(|i| bytes.get(i as usize))
+                .is_some_and(|byte| *byte == 0)
+        })
+    })
+}
+
+fn encode_struct(
+    db: &dyn Db,
+    address: u64,
+    structure: &StructLayout<Die>,
+    value: &Value,
+    writes: &mut Vec<Write>,
+) -> Result<()> {
+    match value {
+        Value::Struct { fields, .. } => {
+            for (name, field_value) in fields {
+                let field = structure
+                    .fields
+                    .iter()
+                    .find(|field| &field.name == name)
+                    .with_context(|| format!("{} has no field {name}", structure.name))?;
+                encode(
+                    db,
+                    address + field.offset as u64,
+                    &field.ty,
+                    field_value,
+                    writes,
+                )?;
+            }
+        }
+        // wrappers like `NonZero<u32>` or `Wrapping<i8>` are assigned their inner value
+        _ if structure.fields.len() == 1 => {
+            let field = &structure.fields[0];
+            let start = writes.len();
+            encode(db, address + field.offset as u64, &field.ty, value, writes)?;
+            if structure.name.starts_with("NonZero")
+                && writes[start..]
+                    .iter()
+                    .all(|(_, bytes)| bytes.iter().all(|b| *b == 0))
+            {
+                anyhow::bail!("{} cannot be zero", structure.name);
+            }
+        }
+        _ => anyhow::bail!(
+            "Assigning {} to {} is not supported, assign to its fields instead",
+            describe(value),
+            structure.name
+        ),
+    }
+    Ok(())
+}
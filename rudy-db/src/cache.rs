@@ -0,0 +1,274 @@
+//! Caching reads of target memory.
+//!
+//! Reading a value makes many small reads (a length here, a pointer there),
+//! and each one can be a round trip to the debugger. [`CachingResolver`]
+//! instead reads whole pages and serves later reads from them, fetching
+//! neighbouring missing pages together in a single read.
+//!
+//! The cache is only valid while the target is stopped: once it resumes (or
+//! runs code on our behalf), [`MemoryCache::invalidate`] must be called.
+
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
+
+use anyhow::Result;
+
+use crate::DataResolver;
+
+/// The default size of the blocks memory is read and cached in
+pub const DEFAULT_PAGE_SIZE: u64 = 4096;
+
+/// Reads spanning more pages than this bypass the cache, so that reading a
+/// large string or buffer doesn't evict everything else
+const MAX_CACHED_PAGES: u64 = 16;
+
+/// Pages of target memory read through a [`CachingResolver`]
+///
+/// Clones share the same pages, so a cache can outlive the resolvers using
+/// it, e.g. to be kept for as long as the target stays stopped.
+#[derive(Clone, Debug)]
+pub struct MemoryCache {
+    state: Rc<RefCell<CacheState>>,
+}
+
+#[derive(Debug)]
+struct CacheState {
+    page_size: u64,
+    pages: HashMap<u64, Vec<u8>>,
+    /// Pages that haven't been read but are known to be readable
+    readable: HashSet<u64>,
+}
+
+impl Default for MemoryCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_PAGE_SIZE)
+    }
+}
+
+impl MemoryCache {
+    /// Creates an empty cache holding `page_size`-byte pages
+    ///
+    /// # Panics
+    ///
+    /// If `page_size` is not a power of two
+    pub fn new(page_size: u64) -> Self {
+        assert!(
+            page_size.is_power_of_two(),
+            "page size must be a power of two, got {page_size}"
+        );
+        Self {
+            state: Rc::new(RefCell::new(CacheState {
+                page_size,
+                pages: HashMap::new(),
+                readable: HashSet::new(),
+            })),
+        }
+    }
+
+    /// Forgets every cached page
+    ///
+    /// Must be called whenever the target's memory may have changed, i.e.
+    /// when it resumes or runs code such as a method call.
+    ///
+    /// Debuggers may also change memory or registers without the target
+    /// running, e.g. with LLDB's `memory write` or `register write`. LLDB
+    /// neither reports these as a new stop nor broadcasts an event for them,
+    /// so rudy-lldb, which invalidates the cache on each new stop, can serve
+    /// values from before such an edit until the target next stops.
+    pub fn invalidate(&self) {
+        let mut state = self.state.borrow_mut();
+        state.pages.clear();
+        // running code may map or unmap memory
+        state.readable.clear();
+    }
+
+    /// The number of pages currently cached
+    pub fn cached_pages(&self) -> usize {
+        self.state.borrow().pages.len()
+    }
+}
+
+/// A [`DataResolver`] adapter that reads memory in page-aligned blocks and
+/// serves overlapping reads from a [`MemoryCache`]
+///
+/// Registers are not cached. Writes go straight through to the inner
+/// resolver and update any cached pages they touch, while allocating memory
+/// (which runs code in the target) invalidates the cache.
+pub struct CachingResolver<R> {
+    inner: R,
+    cache: MemoryCache,
+}
+
+impl<R: DataResolver> CachingResolver<R> {
+    /// Wraps `inner` with a new, empty cache
+    pub fn new(inner: R) -> Self {
+        Self::with_cache(inner, MemoryCache::default())
+    }
+
+    /// Wraps `inner`, sharing the pages of an existing cache
+    pub fn with_cache(inner: R, cache: MemoryCache) -> Self {
+        Self { inner, cache }
+    }
+
+    /// The wrapped resolver
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+
+    /// The cache reads are served from
+    pub fn cache(&self) -> &MemoryCache {
+        &self.cache
+    }
+
+    /// Forgets every cached page, see [`MemoryCache::invalidate`]
+    pub fn invalidate(&self) {
+        self.cache.invalidate();
+    }
+
+    /// Reads the missing pages in `first..=last` into the cache, fetching
+    /// each run of consecutive missing pages with a single read
+    fn fill(&self, first: u64, last: u64, page_size: u64) -> Result<()> {
+        let missing: Vec<u64> = {
+            let state = self.cache.state.borrow();
+            (first..=last)
+                .step_by(page_size as usize)
+                .filter(|page| !state.pages.contains_key(page))
+                .collect()
+        };
+
+        for (start, end) in runs(missing, page_size) {
+            let data = self.inner.read_memory(start, (end - start) as usize)?;
+            if data.len() as u64 != end - start {
+                anyhow::bail!(
+                    "short read at {start:#x}: expected {} bytes, got {}",
+                    end - start,
+                    data.len()
+                );
+            }
+            let mut state = self.cache.state.borrow_mut();
+            for (i, page) in data.chunks(page_size as usize).enumerate() {
+                state
+                    .pages
+                    .insert(start + i as u64 * page_size, page.to_vec());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<R: DataResolver> DataResolver for CachingResolver<R> {
+    fn read_memory(&self, address: u64, size: usize) -> Result<Vec<u8>> {
+        let page_size = self.cache.state.borrow().page_size;
+        let Some(end) = address.checked_add(size as u64).filter(|_| size > 0) else {
+            return self.inner.read_memory(address, size);
+        };
+        let first = address & !(page_size - 1);
+        let last = (end - 1) & !(page_size - 1);
+        if (last - first) / page_size >= MAX_CACHED_PAGES {
+            return self.inner.read_memory(address, size);
+        }
+
+        if let Err(e) = self.fill(first, last, page_size) {
+            // the surrounding pages may not all be readable even though the
+            // requested bytes are, e.g. at the end of a mapping
+            tracing::debug!("failed to cache pages for {address:#x}+{size}: {e}");
+            return self.inner.read_memory(address, size);
+        }
+
+        let state = self.cache.state.borrow();
+        let mut data = Vec::with_capacity(size);
+        let mut position = address;
+        while position < end {
+            let page_start = position & !(page_size - 1);
+            let page = &state.pages[&page_start];
+            let offset = (position - page_start) as usize;
+            let len = (page_size - offset as u64).min(end - position) as usize;
+            data.extend_from_slice(&page[offset..offset + len]);
+            position += len as u64;
+        }
+        Ok(data)
+    }
+
+    fn is_readable(&self, address: u64, size: usize) -> bool {
+        let page_size = self.cache.state.borrow().page_size;
+        let Some(end) = address.checked_add(size as u64).filter(|_| size > 0) else {
+            return self.inner.is_readable(address, size);
+        };
+        let first = address & !(page_size - 1);
+        let last = (end - 1) & !(page_size - 1);
+        if (last - first) / page_size >= MAX_CACHED_PAGES {
+            return self.inner.is_readable(address, size);
+        }
+
+        let unknown: Vec<u64> = {
+            let state = self.cache.state.borrow();
+            (first..=last)
+                .step_by(page_size as usize)
+                .filter(|page| !state.pages.contains_key(page) && !state.readable.contains(page))
+                .collect()
+        };
+
+        for (start, end) in runs(unknown, page_size) {
+            if !self.inner.is_readable(start, (end - start) as usize) {
+                // the requested bytes may not cover the unreadable part of
+                // the pages, so check exactly those
+                return self.inner.is_readable(address, size);
+            }
+            let mut state = self.cache.state.borrow_mut();
+            state
+                .readable
+                .extend((start..end).step_by(page_size as usize));
+        }
+        true
+    }
+
+    fn get_register(&self, idx: usize) -> Result<u64> {
+        self.inner.get_register(idx)
+    }
+
+    fn get_stack_pointer(&self) -> Result<u64> {
+        self.inner.get_stack_pointer()
+    }
+
+    fn allocate_memory(&self, size: usize) -> Result<u64> {
+        // allocating runs code in the target, which may touch any memory
+        self.invalidate();
+        self.inner.allocate_memory(size)
+    }
+
+    fn write_memory(&self, address: u64, data: &[u8]) -> Result<()> {
+        let result = self.inner.write_memory(address, data);
+        if result.is_err() {
+            // the write may have partially succeeded
+            self.invalidate();
+            return result;
+        }
+
+        let mut state = self.cache.state.borrow_mut();
+        let page_size = state.page_size;
+        for (i, byte) in data.iter().enumerate() {
+            let position = address + i as u64;
+            let page_start = position & !(page_size - 1);
+            if let Some(page) = state.pages.get_mut(&page_start) {
+                page[(position - page_start) as usize] = *byte;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Groups page-aligned addresses into runs of consecutive pages, as
+/// `(start, end)` address ranges
+fn runs(pages: Vec<u64>, page_size: u64) -> Vec<(u64, u64)> {
+    let mut runs: Vec<(u64, u64)> = vec![];
+    for page in pages {
+        match runs.last_mut() {
+            Some((_, end)) if *end == page => *end += page_size,
+            _ => runs.push((page, page + page_size)),
+        }
+    }
+    runs
+}
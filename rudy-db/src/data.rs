@@ -1,16 +1,22 @@
 //! Data resolver trait for reading variables from memory during debugging.
 
-use std::collections::BTreeMap;
+use std::{cell::Cell, collections::BTreeMap, ops::Range};
 
 use anyhow::{Context, Result};
 use rudy_dwarf::{Die, types::DieTypeDefinition};
 use rudy_types::{
-    ArrayLayout, BTreeNodeLayout, CEnumLayout, EnumLayout, Layout, MapLayout, MapVariant,
-    OptionLayout, PointerLayout, PrimitiveLayout, ReferenceLayout, SliceLayout, SmartPtrVariant,
-    StdLayout, StrSliceLayout, VecLayout,
+    ArrayLayout, BTreeNodeLayout, BTreeRootLayout, BinaryHeapLayout, CEnumLayout, CaptureMode,
+    CoroutineLayout, CoroutineState, CowLayout, Discriminant, EnumLayout, Layout, LinkedListLayout,
+    MapLayout, MapVariant, OptionLayout, OsStringLayout, PointerLayout, PointerMetadata,
+    PrimitiveLayout, ReferenceLayout, ResultLayout, SliceLayout, SmartPtrLayout, SmartPtrState,
+    SmartPtrVariant, StdLayout, StrSliceLayout, UnsignedIntLayout, VecDequeLayout, VecLayout,
 };
 
-use crate::{Value, database::Db, outputs::TypedPointer};
+use crate::{
+    Value,
+    database::Db,
+    outputs::{ChildPage, Children, TypedPointer, truncation_marker},
+};
 
 /// Trait for resolving data from memory during debugging.
 ///
@@ -90,6 +96,17 @@ pub trait DataResolver {
         ))
     }
 
+    /// Checks whether `size` bytes at `address` can be read.
+    ///
+    /// Used to reject implausible values, e.g. a `Vec` read from
+    /// uninitialized memory whose buffer points nowhere, before trying to
+    /// read them. Resolvers that can't tell should return `true`, which is
+    /// the default.
+    fn is_readable(&self, address: u64, size: usize) -> bool {
+        let _ = (address, size);
+        true
+    }
+
     /// Writes data to memory in the target process.
     ///
     /// # Arguments
@@ -118,12 +135,682 @@ impl<'a, R: DataResolver + ?Sized> rudy_dwarf::expressions::ExpressionContext
     }
 }
 
+/// Limits on how much of a value [`DebugInfo::read_value`](crate::DebugInfo::read_value) reads
+///
+/// Collections and strings beyond these limits are cut short with a
+/// [`Value::Truncated`] marker, or a count in the map's `truncated`, while nested values beyond `max_depth`, or
+/// left over once `max_bytes_read` is spent, are returned as unread
+/// [`Value::Pointer`]s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadOptions {
+    /// How many levels of values to read, counting the value itself
+    pub max_depth: usize,
+    /// The most items (or entries) to read from any one collection
+    pub max_items: usize,
+    /// The most bytes to read from any one string
+    pub max_string_bytes: usize,
+    /// The most bytes to read from memory in total
+    pub max_bytes_read: usize,
+}
+
+impl Default for ReadOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: 8,
+            max_items: 100,
+            max_string_bytes: 1024,
+            max_bytes_read: 1 << 20,
+        }
+    }
+}
+
+impl ReadOptions {
+    /// Options that read the whole value, however large it is
+    pub fn unlimited() -> Self {
+        Self {
+            max_depth: usize::MAX,
+            max_items: usize::MAX,
+            max_string_bytes: usize::MAX,
+            max_bytes_read: usize::MAX,
+        }
+    }
+}
+
+/// A [`DataResolver`] that refuses to read more than a fixed number of bytes
+struct BudgetedResolver<'a> {
+    inner: &'a dyn DataResolver,
+    remaining: Cell<usize>,
+    exhausted: Cell<bool>,
+}
+
+impl<'a> BudgetedResolver<'a> {
+    fn new(inner: &'a dyn DataResolver, budget: usize) -> Self {
+        Self {
+            inner,
+            remaining: Cell::new(budget),
+            exhausted: Cell::new(false),
+        }
+    }
+
+    fn spend(&self, size: usize) -> Result<()> {
+        match self.remaining.get().checked_sub(size) {
+            Some(remaining) => {
+                self.remaining.set(remaining);
+                Ok(())
+            }
+            None => {
+                self.exhausted.set(true);
+                anyhow::bail!("memory read budget exhausted")
+            }
+        }
+    }
+}
+
+impl DataResolver for BudgetedResolver<'_> {
+    fn read_memory(&self, address: u64, size: usize) -> Result<Vec<u8>> {
+        self.spend(size)?;
+        self.inner.read_memory(address, size)
+    }
+
+    fn read_address(&self, address: u64) -> Result<u64> {
+        self.spend(std::mem::size_of::<u64>())?;
+        self.inner.read_address(address)
+    }
+
+    fn get_register(&self, idx: usize) -> Result<u64> {
+        self.inner.get_register(idx)
+    }
+
+    fn get_stack_pointer(&self) -> Result<u64> {
+        self.inner.get_stack_pointer()
+    }
+
+    fn is_readable(&self, address: u64, size: usize) -> bool {
+        self.inner.is_readable(address, size)
+    }
+}
+
+/// The error for values that can't be valid, e.g. because they were read
+/// from uninitialized or corrupted memory
+///
+/// Reading a value that fails with this error gives a [`Value::Invalid`]
+/// in its place, rather than failing the whole read.
+#[derive(Debug)]
+struct Implausible(String);
+
+impl std::fmt::Display for Implausible {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Implausible {}
+
+macro_rules! implausible {
+    ($($arg:tt)*) => {
+        anyhow::Error::new(Implausible(format!($($arg)*)))
+    };
+}
+
+/// The layout of the bytes of strings, for checking their buffers
+const BYTE: Layout<Die> =
+    Layout::Primitive(PrimitiveLayout::UnsignedInt(UnsignedIntLayout { size: 1 }));
+
+/// Checks that `length` elements of `element_type` at `data_address` could
+/// be a live buffer, so that garbage lengths don't make us read gigabytes
+fn check_buffer(
+    data_address: u64,
+    length: usize,
+    element_type: &Layout<Die>,
+    data_resolver: &dyn crate::DataResolver,
+) -> Result<()> {
+    if length == 0 {
+        return Ok(());
+    }
+    if data_address == 0 {
+        return Err(implausible!("null data pointer with length {length}"));
+    }
+    if let Some(alignment) = alignment(element_type)
+        && data_address % alignment as u64 != 0
+    {
+        return Err(implausible!(
+            "data pointer {data_address:#x} is not aligned to {alignment} bytes"
+        ));
+    }
+    let Some(element_size) = element_type.size() else {
+        return Ok(());
+    };
+    let size = length
+        .checked_mul(element_size)
+        .filter(|size| *size <= isize::MAX as usize)
+        .ok_or_else(|| implausible!("length {length} is too large"))?;
+    if size > 0 && !data_resolver.is_readable(data_address, size) {
+        return Err(implausible!(
+            "{size} bytes at {data_address:#x} are not readable"
+        ));
+    }
+    Ok(())
+}
+
+/// The alignment of values of the given type, where it is known
+fn alignment(layout: &Layout<Die>) -> Option<usize> {
+    match layout {
+        Layout::Primitive(primitive) => match primitive {
+            PrimitiveLayout::Bool(()) => Some(1),
+            PrimitiveLayout::Char(()) => Some(4),
+            PrimitiveLayout::Int(int) => Some(int.size.min(16)),
+            PrimitiveLayout::UnsignedInt(int) => Some(int.size.min(16)),
+            PrimitiveLayout::Float(float) => Some(float.size.min(16)),
+            PrimitiveLayout::Pointer(_)
+            | PrimitiveLayout::Reference(_)
+            | PrimitiveLayout::Slice(_)
+            | PrimitiveLayout::StrSlice(_) => Some(std::mem::size_of::<usize>()),
+            _ => None,
+        },
+        Layout::Struct(struct_def) => Some(struct_def.alignment).filter(|a| a.is_power_of_two()),
+        _ => None,
+    }
+}
+
+/// Reads a value along with the values nested within it, within the limits of `options`
+///
+/// The value itself is always read, even when `options.max_depth` is zero.
+pub(crate) fn read_value(
+    db: &dyn Db,
+    pointer: &TypedPointer,
+    options: &ReadOptions,
+    data_resolver: &dyn DataResolver,
+) -> Result<Value> {
+    let data_resolver = BudgetedResolver::new(data_resolver, options.max_bytes_read);
+    // the value itself is always read, and errors if it can't be
+    let value = read_from_memory_with_options(
+        db,
+        pointer.address,
+        &pointer.type_def,
+        options,
+        &data_resolver,
+    )?;
+    read_nested_value(db, value, 1, options, &data_resolver)
+}
+
+/// Reads the values that the pointers within `value` point to, within the
+/// limits of `options`, as if `value` had been read by [`read_value`]
+pub(crate) fn read_pointers(
+    db: &dyn Db,
+    value: Value,
+    options: &ReadOptions,
+    data_resolver: &dyn DataResolver,
+) -> Result<Value> {
+    let data_resolver = BudgetedResolver::new(data_resolver, options.max_bytes_read);
+    read_nested_value(db, value, 1, options, &data_resolver)
+}
+
+fn read_nested_value(
+    db: &dyn Db,
+    value: Value,
+    depth: usize,
+    options: &ReadOptions,
+    data_resolver: &BudgetedResolver,
+) -> Result<Value> {
+    let read_all = |values: Vec<Value>| {
+        values
+            .into_iter()
+            .map(|v| read_nested_value(db, v, depth, options, data_resolver))
+            .collect::<Result<Vec<_>>>()
+    };
+
+    Ok(match value {
+        Value::Pointer(pointer) => {
+            if depth >= options.max_depth || data_resolver.exhausted.get() {
+                return Ok(Value::Pointer(pointer));
+            }
+            match read_from_memory_with_options(
+                db,
+                pointer.address,
+                &pointer.type_def,
+                options,
+                data_resolver,
+            ) {
+                Ok(value) => read_nested_value(db, value, depth + 1, options, data_resolver)?,
+                // leave what we couldn't afford to read for later
+                Err(_) if data_resolver.exhausted.get() => Value::Pointer(pointer),
+                // don't lose the rest of the value to one unreadable part
+                Err(e) => Value::Invalid {
+                    ty: pointer.type_def.display_name(),
+                    reason: format!("{e:#}"),
+                },
+            }
+        }
+        v @ (Value::Scalar { .. } | Value::Truncated { .. } | Value::Invalid { .. }) => v,
+        Value::Suspect { reason, value } => Value::Suspect {
+            reason,
+            value: Box::new(read_nested_value(
+                db,
+                *value,
+                depth,
+                options,
+                data_resolver,
+            )?),
+        },
+        Value::Array { ty, items } => Value::Array {
+            ty,
+            items: read_all(items)?,
+        },
+        Value::Struct { ty, fields } => Value::Struct {
+            ty,
+            fields: fields
+                .into_iter()
+                .map(|(name, v)| {
+                    read_nested_value(db, v, depth, options, data_resolver).map(|v| (name, v))
+                })
+                .collect::<Result<_>>()?,
+        },
+        Value::Tuple { ty, entries } => Value::Tuple {
+            ty,
+            entries: read_all(entries)?,
+        },
+        Value::Map {
+            ty,
+            entries,
+            truncated,
+        } => Value::Map {
+            ty,
+            truncated,
+            entries: entries
+                .into_iter()
+                .map(|(k, v)| {
+                    Ok((
+                        read_nested_value(db, k, depth, options, data_resolver)?,
+                        read_nested_value(db, v, depth, options, data_resolver)?,
+                    ))
+                })
+                .collect::<Result<_>>()?,
+        },
+        Value::Set { ty, items } => Value::Set {
+            ty,
+            items: read_all(items)?,
+        },
+        Value::Enum {
+            ty,
+            variant,
+            discriminant,
+            payload,
+        } => Value::Enum {
+            ty,
+            variant,
+            discriminant,
+            payload: payload
+                .map(|p| read_nested_value(db, *p, depth, options, data_resolver).map(Box::new))
+                .transpose()?,
+        },
+    })
+}
+
+/// Creates the items of a collection of `length` items, stopping after
+/// `options.max_items` of them with a [`Value::Truncated`] marker
+fn limited_items(
+    length: usize,
+    options: &ReadOptions,
+    item: impl FnMut(usize) -> Value,
+) -> Vec<Value> {
+    let shown = length.min(options.max_items);
+    let mut items: Vec<_> = (0..shown).map(item).collect();
+    if shown < length {
+        items.push(Value::Truncated {
+            remaining: length - shown,
+        });
+    }
+    items
+}
+
+/// Reads up to `options.max_string_bytes` bytes of the string at `data_address`
+///
+/// `str` and `String` are always valid UTF-8, so one that isn't is marked as
+/// suspect.
+fn read_string_value(
+    ty: &str,
+    data_address: u64,
+    length: usize,
+    options: &ReadOptions,
+    data_resolver: &dyn crate::DataResolver,
+) -> Result<Value> {
+    let (value, invalid_at) =
+        read_lossy_string_value(ty, data_address, length, options, data_resolver)?;
+    Ok(match invalid_at {
+        // a `str` is always valid UTF-8, so this is probably not a string at all
+        Some(offset) => Value::Suspect {
+            reason: format!("invalid UTF-8 at byte {offset}"),
+            value: Box::new(value),
+        },
+        None => value,
+    })
+}
+
+/// Reads up to `options.max_string_bytes` bytes of the string at
+/// `data_address`, replacing invalid UTF-8, and returns where the first
+/// invalid byte was, if any
+fn read_lossy_string_value(
+    ty: &str,
+    data_address: u64,
+    length: usize,
+    options: &ReadOptions,
+    data_resolver: &dyn crate::DataResolver,
+) -> Result<(Value, Option<usize>)> {
+    let mut bytes =
+        data_resolver.read_memory(data_address, length.min(options.max_string_bytes))?;
+    let mut invalid_at = None;
+    if let Err(e) = std::str::from_utf8(&bytes) {
+        if bytes.len() < length && e.error_len().is_none() {
+            // don't cut a character in half
+            bytes.truncate(e.valid_up_to());
+        } else {
+            invalid_at = Some(e.valid_up_to());
+        }
+    }
+    let value = if bytes.len() < length {
+        format!(
+            "\"{}\"{} bytes",
+            String::from_utf8_lossy(&bytes),
+            truncation_marker(length - bytes.len())
+        )
+    } else {
+        format!("\"{}\"", String::from_utf8_lossy(&bytes))
+    };
+    let value = Value::Scalar {
+        ty: ty.to_string(),
+        value,
+    };
+    Ok((value, invalid_at))
+}
+
+/// Reads the children of the collection or string at `address` that fall in `range`
+///
+/// Ranges count elements, entries or (for strings) bytes, and are clamped
+/// to the size of the value. Only the memory needed for the window is read,
+/// beyond what it takes to find it (e.g. walking a linked list).
+pub(crate) fn read_children(
+    db: &dyn Db,
+    address: u64,
+    ty: &DieTypeDefinition,
+    range: Range<usize>,
+    data_resolver: &dyn crate::DataResolver,
+) -> Result<ChildPage> {
+    tracing::trace!("read_children {address:#x} {} {range:?}", ty.display_name());
+
+    // the range of elements starting at `data_address`
+    let items = |data_address: u64, element_type: &DieTypeDefinition, total: usize| {
+        let element_type = resolve_alias(db, element_type)?;
+        let element_size = element_type.size().with_context(|| {
+            format!(
+                "inner type: {} has unknown size",
+                element_type.display_name()
+            )
+        })? as u64;
+        let items = clamp_range(&range, total)
+            .map(|i| TypedPointer {
+                address: data_address + i as u64 * element_size,
+                type_def: element_type.clone(),
+            })
+            .collect();
+        Ok(ChildPage {
+            total,
+            children: Children::Items(items),
+        })
+    };
+    // the range of bytes of a string starting at `data_address`
+    let text = |data_address: u64, total: usize| {
+        let window = clamp_range(&range, total);
+        let bytes = data_resolver.read_memory(data_address + window.start as u64, window.len())?;
+        Ok(ChildPage {
+            total,
+            children: Children::Text(String::from_utf8_lossy(&bytes).into_owned()),
+        })
+    };
+
+    match ty.layout.as_ref() {
+        Layout::Alias { .. } => {
+            let underlying_type = rudy_dwarf::types::resolve_type_offset(db, ty.location)?;
+            read_children(db, address, &underlying_type, range, data_resolver)
+        }
+        Layout::Primitive(PrimitiveLayout::Array(array_def)) => {
+            items(address, &array_def.element_type, array_def.length)
+        }
+        Layout::Primitive(PrimitiveLayout::Slice(slice_def)) => {
+            let length = data_resolver.read_address(address + slice_def.length_offset as u64)?;
+            let data_address =
+                data_resolver.read_address(address + slice_def.data_ptr_offset as u64)?;
+            items(data_address, &slice_def.element_type, length as usize)
+        }
+        Layout::Primitive(PrimitiveLayout::StrSlice(str_def)) => {
+            let length = data_resolver.read_address(address + str_def.length_offset as u64)?;
+            let data_address =
+                data_resolver.read_address(address + str_def.data_ptr_offset as u64)?;
+            text(data_address, length as usize)
+        }
+        Layout::Primitive(
+            PrimitiveLayout::Pointer(PointerLayout { pointed_type, .. })
+            | PrimitiveLayout::Reference(ReferenceLayout { pointed_type, .. }),
+        ) => {
+            // look through references to the collection
+            let address = data_resolver.read_address(address)?;
+            read_children(db, address, pointed_type, range, data_resolver)
+        }
+        Layout::Std(StdLayout::Vec(vec_def) | StdLayout::BinaryHeap(BinaryHeapLayout(vec_def))) => {
+            let (data_address, length) = extract_vec_info(address, vec_def, data_resolver)?;
+            items(data_address, &vec_def.inner_type, length)
+        }
+        Layout::Std(StdLayout::VecDeque(def)) => {
+            let element_type = resolve_alias(db, &def.inner_type)?;
+            let element_size = element_type.size().with_context(|| {
+                format!(
+                    "inner type: {} has unknown size",
+                    element_type.display_name()
+                )
+            })? as u64;
+            let (addresses, length) =
+                vec_deque_element_addresses(address, def, element_size, range, data_resolver)?;
+            let items = addresses
+                .into_iter()
+                .map(|address| TypedPointer {
+                    address,
+                    type_def: element_type.clone(),
+                })
+                .collect();
+            Ok(ChildPage {
+                total: length,
+                children: Children::Items(items),
+            })
+        }
+        Layout::Std(StdLayout::LinkedList(def)) => {
+            // the nodes before the range still have to be walked
+            let (addresses, length) =
+                linked_list_element_addresses(address, def, range.end, data_resolver)?;
+            let element_type = resolve_alias(db, &def.inner_type)?;
+            let items = addresses
+                .into_iter()
+                .skip(range.start)
+                .map(|address| TypedPointer {
+                    address,
+                    type_def: element_type.clone(),
+                })
+                .collect();
+            Ok(ChildPage {
+                total: length,
+                children: Children::Items(items),
+            })
+        }
+        Layout::Std(StdLayout::String(string_def)) => {
+            let (data_address, length) = extract_vec_info(address, &string_def.0, data_resolver)?;
+            text(data_address, length)
+        }
+        Layout::Std(StdLayout::OsString(def)) => {
+            let (data_address, mut length) = os_string_data(address, def, data_resolver)?;
+            if def.variant.is_nul_terminated() {
+                // C strings always end with their nul terminator
+                length = length.saturating_sub(1);
+            }
+            text(data_address, length)
+        }
+        Layout::Std(StdLayout::Map(def)) => {
+            let (entries, length) = read_map_entries_range(address, def, range, data_resolver)?;
+            Ok(ChildPage {
+                total: length,
+                children: Children::Entries(entries),
+            })
+        }
+        Layout::Std(StdLayout::Set(def)) => {
+            let (entries, length) = read_map_entries_range(
+                address + def.map_offset as u64,
+                &def.map,
+                range,
+                data_resolver,
+            )?;
+            Ok(ChildPage {
+                total: length,
+                children: Children::Items(entries.into_iter().map(|(key, _)| key).collect()),
+            })
+        }
+        Layout::Std(StdLayout::SmartPtr(def))
+            if matches!(
+                def.variant,
+                SmartPtrVariant::Box | SmartPtrVariant::Rc | SmartPtrVariant::Arc
+            ) =>
+        {
+            let data_address = data_resolver.read_address(address + def.inner_ptr_offset as u64)?;
+            let data_address = match def.variant {
+                SmartPtrVariant::Box => data_address,
+                _ => data_address + def.data_ptr_offset as u64,
+            };
+            match def.metadata {
+                PointerMetadata::None => {
+                    read_children(db, data_address, &def.inner_type, range, data_resolver)
+                }
+                PointerMetadata::Length { length_offset } => {
+                    let length =
+                        data_resolver.read_address(address + length_offset as u64)? as usize;
+                    if let Layout::Primitive(PrimitiveLayout::Str(_)) =
+                        def.inner_type.layout.as_ref()
+                    {
+                        text(data_address, length)
+                    } else {
+                        items(data_address, &def.inner_type, length)
+                    }
+                }
+                PointerMetadata::VTable { .. } => {
+                    anyhow::bail!("{} has no children to page through", ty.display_name())
+                }
+            }
+        }
+        _ => anyhow::bail!("{} has no children to page through", ty.display_name()),
+    }
+}
+
+/// The part of `range` that lies within `0..total`
+fn clamp_range(range: &Range<usize>, total: usize) -> Range<usize> {
+    let end = range.end.min(total);
+    range.start.min(end)..end
+}
+
+/// How many HashMap control bytes to read at a time
+const HASHMAP_CTRL_CHUNK: usize = 4096;
+
+/// The header of a hashbrown table, checked to be plausible by [`read_raw_table`]
+pub(crate) struct RawTable {
+    /// The number of buckets, always a power of two
+    pub(crate) buckets: usize,
+    /// Where the control bytes start
+    pub(crate) ctrl_ptr: u64,
+    /// The number of full buckets
+    pub(crate) items: usize,
+}
+
+impl RawTable {
+    /// The address of the bucket at `index`, for buckets of `bucket_size` bytes
+    ///
+    /// Buckets are stored backwards, just before the control bytes.
+    pub(crate) fn bucket(&self, index: usize, bucket_size: usize) -> u64 {
+        debug_assert!(index < self.buckets);
+        // `read_raw_table` checked that every bucket fits before `ctrl_ptr`
+        self.ctrl_ptr - ((index + 1) * bucket_size) as u64
+    }
+}
+
+/// Reads the header of the hashbrown table at `address`, and checks that its
+/// buckets and control bytes could be live memory
+pub(crate) fn read_raw_table(
+    address: u64,
+    bucket_mask_offset: usize,
+    ctrl_offset: usize,
+    items_offset: usize,
+    bucket_size: usize,
+    group_width: usize,
+    data_resolver: &dyn crate::DataResolver,
+) -> Result<RawTable> {
+    let items = data_resolver.read_memory(address + items_offset as u64, 8)?;
+    let items = usize::from_le_bytes(items.try_into().unwrap());
+    let bucket_mask = data_resolver.read_memory(address + bucket_mask_offset as u64, 8)?;
+    let bucket_mask = usize::from_le_bytes(bucket_mask.try_into().unwrap());
+    let ctrl_ptr = data_resolver.read_address(address + ctrl_offset as u64)?;
+
+    let buckets = bucket_mask
+        .checked_add(1)
+        .filter(|buckets| buckets.is_power_of_two())
+        .ok_or_else(|| {
+            implausible!("bucket mask {bucket_mask:#x} is not one less than a power of two")
+        })?;
+    if items > buckets {
+        return Err(implausible!("{items} items don't fit in {buckets} buckets"));
+    }
+    let table = RawTable {
+        buckets,
+        ctrl_ptr,
+        items,
+    };
+    if items == 0 {
+        // empty tables share static control bytes, and are never read
+        return Ok(table);
+    }
+
+    if ctrl_ptr == 0 {
+        return Err(implausible!("null control bytes with {items} items"));
+    }
+    if group_width > 0 && ctrl_ptr % group_width as u64 != 0 {
+        return Err(implausible!(
+            "control bytes at {ctrl_ptr:#x} are not aligned to {group_width} bytes"
+        ));
+    }
+    let data_size = buckets
+        .checked_mul(bucket_size)
+        .filter(|size| *size as u64 <= ctrl_ptr)
+        .ok_or_else(|| {
+            implausible!("{buckets} buckets of {bucket_size} bytes don't fit before {ctrl_ptr:#x}")
+        })?;
+    check_buffer(ctrl_ptr - data_size as u64, data_size, &BYTE, data_resolver)?;
+    // the control bytes are followed by a copy of the first group
+    check_buffer(ctrl_ptr, buckets + group_width, &BYTE, data_resolver)?;
+    Ok(table)
+}
+
 /// Returns a list of map entries from a memory address.
 pub fn read_map_entries(
     address: u64,
     def: &MapLayout<Die>,
     data_resolver: &dyn crate::DataResolver,
 ) -> Result<Vec<(TypedPointer, TypedPointer)>> {
+    read_map_entries_range(address, def, 0..usize::MAX, data_resolver).map(|(entries, _)| entries)
+}
+
+/// Returns the map entries at positions `range` (in iteration order) from a
+/// memory address, along with the number of entries in the map.
+///
+/// Entries are found incrementally, so only the memory needed to reach the
+/// end of the range is read.
+fn read_map_entries_range(
+    address: u64,
+    def: &MapLayout<Die>,
+    range: Range<usize>,
+    data_resolver: &dyn crate::DataResolver,
+) -> Result<(Vec<(TypedPointer, TypedPointer)>, usize)> {
     tracing::trace!("read_map_entries {address:#x} {}", def.display_name());
 
     match def.variant.clone() {
@@ -134,65 +821,69 @@ pub fn read_map_entries(
             pair_size,
             key_offset,
             value_offset,
+            group_width,
+            ..
         } => {
-            let bucket_mask_address = address + bucket_mask_offset as u64;
-            let ctrl_address = address + ctrl_offset as u64;
-            let items_address = address + items_offset as u64;
-            // Read item count
-            let items = data_resolver.read_memory(items_address, 8)?;
-            let items = usize::from_le_bytes(items.try_into().unwrap());
+            tracing::trace!("reading HashMap at {address:#x}");
+            let table = read_raw_table(
+                address,
+                bucket_mask_offset,
+                ctrl_offset,
+                items_offset,
+                pair_size,
+                group_width,
+                data_resolver,
+            )?;
+            let RawTable {
+                buckets,
+                ctrl_ptr,
+                items,
+            } = table;
 
             if items == 0 {
-                return Ok(vec![]);
+                return Ok((vec![], 0));
             }
 
-            tracing::trace!(
-                "reading HashMap at {address:#x}, items: {items}, bucket_mask_addr: {bucket_mask_address:#x}, ctrl_addr: {ctrl_address:#x}"
-            );
-
-            // Read bucket mask to get capacity
-            let bucket_mask = data_resolver.read_memory(bucket_mask_address, 8)?;
-            let capacity = usize::from_le_bytes(bucket_mask.try_into().unwrap()) + 1;
-
-            // Read control bytes pointer
-            let ctrl_ptr = data_resolver.read_address(ctrl_address)?;
-
-            tracing::trace!(
-                "HashMap capacity: {capacity}, ctrl_ptr: {ctrl_ptr:#x}, items: {items}"
-            );
-
-            // Calculate size of key-value pair
-            // Data starts BEFORE the control bytes, counting backwards!
-            let mut slot_addr = ctrl_ptr - pair_size as u64;
-
-            // Read control bytes
-            let ctrl_bytes = data_resolver.read_memory(ctrl_ptr, capacity)?;
+            tracing::trace!("HashMap buckets: {buckets}, ctrl_ptr: {ctrl_ptr:#x}, items: {items}");
 
+            let end = items.min(range.end);
+            let mut seen = 0;
             let mut entries = Vec::new();
 
-            for &ctrl in &ctrl_bytes {
-                if ctrl < 0x80 {
-                    // Occupied slot
-                    let key = TypedPointer {
-                        address: slot_addr + key_offset as u64,
-                        type_def: def.key_type.clone(),
-                    };
-                    let value = TypedPointer {
-                        address: slot_addr + value_offset as u64,
-                        type_def: def.value_type.clone(),
-                    };
-
-                    entries.push((key, value));
-
-                    // Stop if we've found all items
-                    if entries.len() >= items {
-                        break;
+            // Read control bytes a chunk at a time, so we can stop
+            // as soon as we have the entries we want
+            let mut chunk_start = 0;
+            while seen < end && chunk_start < buckets {
+                let chunk_len = HASHMAP_CTRL_CHUNK.min(buckets - chunk_start);
+                let ctrl_bytes =
+                    data_resolver.read_memory(ctrl_ptr + chunk_start as u64, chunk_len)?;
+
+                for (i, &ctrl) in ctrl_bytes.iter().enumerate() {
+                    if ctrl < 0x80 {
+                        // Occupied slot
+                        if seen >= range.start {
+                            let slot_addr = table.bucket(chunk_start + i, pair_size);
+                            let key = TypedPointer {
+                                address: slot_addr + key_offset as u64,
+                                type_def: def.key_type.clone(),
+                            };
+                            let value = TypedPointer {
+                                address: slot_addr + value_offset as u64,
+                                type_def: def.value_type.clone(),
+                            };
+                            entries.push((key, value));
+                        }
+                        seen += 1;
+
+                        // Stop if we've found all items
+                        if seen >= end {
+                            break;
+                        }
                     }
                 }
-                // decrement address for the next slot
-                slot_addr -= pair_size as u64;
+                chunk_start += chunk_len;
             }
-            Ok(entries)
+            Ok((entries, items))
         }
         MapVariant::BTreeMap {
             length_offset,
@@ -208,30 +899,14 @@ pub fn read_map_entries(
             tracing::trace!("BTreeMap at {address:#x}, length: {length}");
 
             if length == 0 {
-                return Ok(vec![]);
+                return Ok((vec![], 0));
             }
 
-            // Read the root field (Option<Root>)
-            let root_addr = address + root_offset as u64;
-
-            // Check if the Option is Some (non-zero discriminant)
-            let discriminant_bytes = data_resolver.read_memory(root_addr, 8)?;
-            let discriminant = u64::from_le_bytes(discriminant_bytes.try_into().unwrap());
-
-            if discriminant == 0 {
-                // None variant - empty map
-                return Ok(vec![]);
-            }
-
-            // Read the root node pointer and height
-            let node_ptr_addr = root_addr + root_layout.node_offset as u64;
-            let height_addr = root_addr + root_layout.height_offset as u64;
-
-            let node_ptr = data_resolver.read_address(node_ptr_addr)?;
-            let height_bytes = data_resolver.read_memory(height_addr, 8)?;
-            let height = usize::from_le_bytes(height_bytes.try_into().unwrap());
-
-            tracing::trace!("BTreeMap root node: {node_ptr:#x}, height: {height}");
+            let Some((node_ptr, height)) =
+                btree_root(address + root_offset as u64, &root_layout, data_resolver)?
+            else {
+                return Ok((vec![], 0));
+            };
 
             // Traverse the tree starting from the root
             let mut entries = Vec::new();
@@ -241,16 +916,69 @@ pub fn read_map_entries(
                 &def.key_type,
                 &def.value_type,
                 &node_layout,
+                &range,
+                &mut 0,
                 data_resolver,
                 &mut entries,
             )?;
 
-            Ok(entries)
+            Ok((entries, length))
         }
-        MapVariant::IndexMap => {
-            unimplemented!(
-                "read_std_from_memory: MapVariant::IndexMap not implemented yet: {def:#?}"
-            )
+        MapVariant::IndexMap {
+            entries_ptr_offset,
+            entries_len_offset,
+            bucket_size,
+            key_offset,
+            value_offset,
+            indices_items_offset,
+            ..
+        } => {
+            let length = data_resolver
+                .read_memory(address + entries_len_offset as u64, 8)?
+                .try_into()
+                .map(usize::from_le_bytes)
+                .map_err(|_| {
+                    anyhow::anyhow!("Failed to read length for IndexMap at address {address:#x}")
+                })?;
+            let indexed = data_resolver
+                .read_memory(address + indices_items_offset as u64, 8)?
+                .try_into()
+                .map(usize::from_le_bytes)
+                .map_err(|_| {
+                    anyhow::anyhow!("Failed to read index count for IndexMap at {address:#x}")
+                })?;
+
+            tracing::trace!("IndexMap at {address:#x}, length: {length}, indexed: {indexed}");
+
+            // every entry should have a slot in the index table
+            if indexed != length {
+                return Err(implausible!(
+                    "IndexMap at {address:#x} has {length} entries but {indexed} indices"
+                ));
+            }
+
+            if length == 0 {
+                return Ok((vec![], 0));
+            }
+
+            // Entries are stored densely in insertion order
+            let entries_ptr = data_resolver.read_address(address + entries_ptr_offset as u64)?;
+            let entries = (range.start.min(length) as u64..range.end.min(length) as u64)
+                .map(|i| {
+                    let bucket_addr = entries_ptr + i * bucket_size as u64;
+                    (
+                        TypedPointer {
+                            address: bucket_addr + key_offset as u64,
+                            type_def: def.key_type.clone(),
+                        },
+                        TypedPointer {
+                            address: bucket_addr + value_offset as u64,
+                            type_def: def.value_type.clone(),
+                        },
+                    )
+                })
+                .collect();
+            Ok((entries, length))
         }
     }
 }
@@ -259,6 +987,7 @@ fn read_enum(
     db: &dyn Db,
     address: u64,
     enum_def: &EnumLayout<Die>,
+    options: &ReadOptions,
     data_resolver: &dyn crate::DataResolver,
 ) -> Result<Value> {
     tracing::trace!("read_enum {address:#x} {enum_def:#?}");
@@ -270,49 +999,11 @@ fn read_enum(
         ..
     } = enum_def;
 
-    let disc_offset = discriminant.offset as u64;
-    let disc_address = address + disc_offset;
+    let disc_address = address + discriminant.offset as u64;
 
     let explicit_disc = !matches!(discriminant.ty, rudy_types::DiscriminantType::Implicit);
 
-    let disc_value = match &discriminant.ty {
-        rudy_types::DiscriminantType::Int(int_def) => {
-            let memory = data_resolver.read_memory(disc_address, int_def.size)?;
-
-            match int_def.size {
-                1 => i8::from_le_bytes(memory.try_into().unwrap()) as i128,
-                2 => i16::from_le_bytes(memory.try_into().unwrap()) as i128,
-                4 => i32::from_le_bytes(memory.try_into().unwrap()) as i128,
-                8 => i64::from_le_bytes(memory.try_into().unwrap()) as i128,
-                _ => {
-                    anyhow::bail!(
-                        "read_primitive_from_memory: unsupported IntDef size {} at address {address:#x}",
-                        int_def.size
-                    )
-                }
-            }
-        }
-        rudy_types::DiscriminantType::UnsignedInt(unsigned_int_def) => {
-            let memory = data_resolver.read_memory(disc_address, unsigned_int_def.size)?;
-            match unsigned_int_def.size {
-                1 => u8::from_le_bytes(memory.try_into().unwrap()) as i128,
-                2 => u16::from_le_bytes(memory.try_into().unwrap()) as i128,
-                4 => u32::from_le_bytes(memory.try_into().unwrap()) as i128,
-                8 => u64::from_le_bytes(memory.try_into().unwrap()) as i128,
-                _ => {
-                    anyhow::bail!(
-                        "read_primitive_from_memory: unsupported UnsignedIntDef size {} at address {address:#x}",
-                        unsigned_int_def.size
-                    )
-                }
-            }
-        }
-        rudy_types::DiscriminantType::Implicit => {
-            // I guess we'll just read 4 bytes and see what happens?
-            let memory = data_resolver.read_memory(disc_address + disc_offset, 4)?;
-            i32::from_le_bytes(memory.try_into().unwrap()) as i128
-        }
-    };
+    let disc_value = read_discriminant(address, discriminant, data_resolver)?;
 
     tracing::trace!("read_enum: at {disc_address:#x}, discriminant value: {disc_value}");
 
@@ -332,53 +1023,130 @@ fn read_enum(
         (None, true) => {
             // if we have _no_ discriminant, we assume this is the
             // fancy niche optimization that Rust does to pack in values
-            variants.iter().find(|v| v.discriminant.is_none()).with_context(|| {
-                format!(
-                    "read_enum: No matching variant found for discriminant value {disc_value} in {name} with explicit discriminant"
-                )
-            })?
+            variants
+                .iter()
+                .find(|v| v.discriminant.is_none())
+                .ok_or_else(|| {
+                    implausible!("discriminant {disc_value} matches no variant of {name}")
+                })?
         }
         (None, false) => {
-            anyhow::bail!(
-                "read_enum: No matching variant found for discriminant value {disc_value} in {name} with implicit discriminant"
-            )
+            return Err(implausible!(
+                "discriminant {disc_value} matches no variant of {name}"
+            ));
         }
     };
 
+    // niche-packed enums store niche values rather than discriminants,
+    // but they can't have custom discriminants, so the index is the discriminant
+    let niche_packed = variants.iter().any(|v| v.discriminant.is_none());
+    let variant_discriminant = match matching_variant.discriminant {
+        Some(discriminant) if explicit_disc && !niche_packed => discriminant,
+        _ => variants
+            .iter()
+            .position(|v| std::ptr::eq(v, matching_variant))
+            .unwrap_or_default() as i128,
+    };
+
     tracing::trace!("found matching variant: {matching_variant:#?}");
 
     // read the inner value
-    let inner = read_from_memory(db, address, &matching_variant.layout, data_resolver)?;
+    let inner = read_from_memory_with_options(
+        db,
+        address,
+        &matching_variant.layout,
+        options,
+        data_resolver,
+    )?;
 
     // re-format the value based on what we get back
-    Ok(match inner {
+    let payload_ty = format!("{name}::{}", matching_variant.name);
+    let payload = match inner {
         Value::Struct { fields, .. } => {
             if fields.is_empty() {
                 // unit variant
-                Value::Scalar {
-                    ty: format!("{name}::{}", matching_variant.name),
-                    value: matching_variant.name.to_string(),
-                }
+                None
             } else if fields.keys().all(|k| k.starts_with("__")) {
                 // anonymous struct, likely a tuple variant
                 let values: Vec<_> = fields.into_values().collect();
-                Value::Tuple {
-                    ty: format!("{name}::{}", matching_variant.name),
+                Some(Value::Tuple {
+                    ty: payload_ty,
                     entries: values,
-                }
+                })
             } else {
                 // struct variant
-                Value::Struct {
-                    ty: format!("{name}::{}", matching_variant.name),
+                Some(Value::Struct {
+                    ty: payload_ty,
                     fields,
-                }
+                })
             }
         }
         v => {
             // unexpected, but we can at least return it
             tracing::error!("read_enum: Expected a struct for enum variant, got: {v:#?}");
-            v
+            Some(v)
+        }
+    };
+
+    Ok(Value::Enum {
+        ty: name.clone(),
+        variant: matching_variant.name.clone(),
+        discriminant: variant_discriminant,
+        payload: payload.map(Box::new),
+    })
+}
+
+/// Reads the state of a coroutine, such as an `async fn` future
+///
+/// Suspended coroutines show the locals they hold across the await point
+/// they are parked at, along with the location of that await point.
+fn read_coroutine(
+    db: &dyn Db,
+    address: u64,
+    coroutine_def: &CoroutineLayout<Die>,
+    options: &ReadOptions,
+    data_resolver: &dyn crate::DataResolver,
+) -> Result<Value> {
+    let CoroutineLayout {
+        name,
+        discriminant,
+        states,
+        ..
+    } = coroutine_def;
+
+    let disc_value = read_discriminant(address, discriminant, data_resolver)?;
+    let state = states
+        .iter()
+        .find(|s| s.discriminant == Some(disc_value))
+        .with_context(|| {
+            format!("read_coroutine: no state found for discriminant value {disc_value} in {name}")
+        })?;
+    tracing::trace!("read_coroutine: {name} is in state {}", state.state);
+
+    let ty = match &state.location {
+        Some(location) => format!("{name}::{} at {location}", state.state),
+        None => format!("{name}::{}", state.state),
+    };
+
+    let fields = match state.state {
+        // nothing is live anymore
+        CoroutineState::Returned | CoroutineState::Panicked => BTreeMap::new(),
+        CoroutineState::Unresumed | CoroutineState::Suspended(_) => {
+            match read_from_memory_with_options(db, address, &state.layout, options, data_resolver)?
+            {
+                Value::Struct { fields, .. } => fields,
+                v => anyhow::bail!("read_coroutine: expected a struct for {ty}, got: {v:#?}"),
+            }
+        }
+    };
+
+    Ok(if fields.is_empty() {
+        Value::Scalar {
+            ty,
+            value: state.state.to_string(),
         }
+    } else {
+        Value::Struct { ty, fields }
     })
 }
 
@@ -434,28 +1202,61 @@ fn read_c_enum(
     let matching_variant = variants
         .iter()
         .find_map(|v| (v.value == disc_value).then_some(&v.name))
-        .ok_or_else(|| {
-            anyhow::anyhow!(
-                "read_c_enum: No matching variant found for discriminant value {disc_value} in {name}"
-            )
-        })?;
+        .ok_or_else(|| implausible!("discriminant {disc_value} matches no variant of {name}"))?;
 
-    Ok(Value::Scalar {
-        ty: format!("{name}::{matching_variant}"),
-        value: disc_value.to_string(),
+    Ok(Value::Enum {
+        ty: name.clone(),
+        variant: matching_variant.clone(),
+        discriminant: disc_value,
+        payload: None,
+    })
+}
+
+pub fn read_from_memory(
+    db: &dyn Db,
+    address: u64,
+    ty: &DieTypeDefinition,
+    data_resolver: &dyn crate::DataResolver,
+) -> Result<Value> {
+    read_from_memory_with_options(db, address, ty, &ReadOptions::unlimited(), data_resolver)
+}
+
+/// Reads one level of a value, keeping at most `options.max_items` items of
+/// collections and `options.max_string_bytes` bytes of strings
+///
+/// Values that can't be valid are read as [`Value::Invalid`].
+fn read_from_memory_with_options(
+    db: &dyn Db,
+    address: u64,
+    ty: &DieTypeDefinition,
+    options: &ReadOptions,
+    data_resolver: &dyn crate::DataResolver,
+) -> Result<Value> {
+    read_layout_from_memory(db, address, ty, options, data_resolver).or_else(|e| match e
+        .downcast_ref::<Implausible>()
+    {
+        Some(Implausible(reason)) => {
+            tracing::debug!("invalid {} at {address:#x}: {reason}", ty.display_name());
+            Ok(Value::Invalid {
+                ty: ty.display_name(),
+                reason: reason.clone(),
+            })
+        }
+        None => Err(e),
     })
 }
 
-pub fn read_from_memory(
+fn read_layout_from_memory(
     db: &dyn Db,
     address: u64,
     ty: &DieTypeDefinition,
+    options: &ReadOptions,
     data_resolver: &dyn crate::DataResolver,
 ) -> Result<Value> {
     tracing::trace!("read_from_memory {address:#x} {}", ty.display_name());
     match ty.layout.as_ref() {
         Layout::Primitive(primitive_def) => {
-            read_primitive_from_memory(db, address, primitive_def, data_resolver)
+            read_primitive_from_memory(db, address, primitive_def, options, data_resolver)
         }
         Layout::Struct(struct_def) => {
             let mut fields = BTreeMap::new();
@@ -475,19 +1276,63 @@ pub fn read_from_memory(
                 fields,
             })
         }
-        Layout::Std(std_def) => read_std_from_memory(db, address, std_def, data_resolver),
-        Layout::Enum(enum_def) => read_enum(db, address, enum_def, data_resolver),
+        Layout::Union(union_def) => {
+            // we can't know which field is active, so we show every interpretation
+            let fields = union_def
+                .fields
+                .iter()
+                .map(|field| {
+                    let field_value = Value::Pointer(TypedPointer {
+                        address: address + field.offset as u64,
+                        type_def: field.ty.clone(),
+                    });
+                    (field.name.clone(), field_value)
+                })
+                .collect();
+            Ok(Value::Struct {
+                ty: union_def.name.clone(),
+                fields,
+            })
+        }
+        Layout::Std(std_def) => read_std_from_memory(db, address, std_def, options, data_resolver),
+        Layout::Enum(enum_def) => read_enum(db, address, enum_def, options, data_resolver),
+        Layout::Coroutine(coroutine_def) => {
+            read_coroutine(db, address, coroutine_def, options, data_resolver)
+        }
+        Layout::Closure(closure_def) => {
+            // by-ref captures are references, so they read as `&T`, and
+            // are named `&name` as they would be in a C++ capture list
+            let fields = closure_def
+                .captures
+                .iter()
+                .map(|capture| {
+                    let capture_value = Value::Pointer(TypedPointer {
+                        address: address + capture.offset as u64,
+                        type_def: capture.ty.clone(),
+                    });
+                    let name = match capture.mode {
+                        CaptureMode::ByRef => format!("&{}", capture.name),
+                        CaptureMode::ByMove => capture.name.clone(),
+                    };
+                    (name, capture_value)
+                })
+                .collect();
+            Ok(Value::Struct {
+                ty: closure_def.name.clone(),
+                fields,
+            })
+        }
         Layout::CEnum(c_enum_def) => read_c_enum(address, c_enum_def, data_resolver),
         Layout::Alias { .. } => {
             // For aliases, we'll resolve the underlying type and read that
             let underlying_type = rudy_dwarf::types::resolve_type_offset(db, ty.location)?;
             // now read the memory itself
-            read_from_memory(db, address, &underlying_type, data_resolver)
+            read_from_memory_with_options(db, address, &underlying_type, options, data_resolver)
         }
     }
 }
 
-/// Extract pointer, length, and capacity from a Vec Value
+/// Extract the data pointer and length of a Vec, checking they are plausible
 pub fn extract_vec_info(
     base_address: u64,
     def: &VecLayout<Die>,
@@ -496,16 +1341,24 @@ pub fn extract_vec_info(
     let VecLayout {
         length_offset,
         data_ptr_offset,
-        ..
+        capacity_offset,
+        inner_type,
     } = def;
-    let length = data_resolver
-        .read_memory(base_address + *length_offset as u64, 8)?
-        .try_into()
-        .map(usize::from_le_bytes)
-        .map_err(|_| {
-            anyhow::anyhow!("Failed to read length for Vec at address {base_address:#x}")
-        })?;
-    tracing::trace!("Vec length: {length}");
+    let read_usize = |offset: usize, field: &str| -> Result<usize> {
+        data_resolver
+            .read_memory(base_address + offset as u64, 8)?
+            .try_into()
+            .map(usize::from_le_bytes)
+            .map_err(|_| {
+                anyhow::anyhow!("Failed to read {field} for Vec at address {base_address:#x}")
+            })
+    };
+    let length = read_usize(*length_offset, "length")?;
+    let capacity = read_usize(*capacity_offset, "capacity")?;
+    tracing::trace!("Vec length: {length}, capacity: {capacity}");
+    if length > capacity {
+        return Err(implausible!("length {length} exceeds capacity {capacity}"));
+    }
     let address = data_resolver
         .read_address(base_address + *data_ptr_offset as u64)
         .with_context(|| {
@@ -514,19 +1367,144 @@ pub fn extract_vec_info(
                 base_address + *data_ptr_offset as u64
             )
         })?;
+    // zero-sized elements have a capacity of `usize::MAX`, so only the
+    // capacity of real allocations is limited
+    if let Some(element_size @ 1..) = inner_type.size()
+        && capacity
+            .checked_mul(element_size)
+            .is_none_or(|size| size > isize::MAX as usize)
+    {
+        return Err(implausible!("capacity {capacity} is too large"));
+    }
+    check_buffer(address, length, &inner_type.layout, data_resolver)?;
     Ok((address, length))
 }
 
+/// Extract the data pointer, head index, length and capacity of a VecDeque
+pub fn extract_vec_deque_info(
+    base_address: u64,
+    def: &VecDequeLayout<Die>,
+    data_resolver: &dyn crate::DataResolver,
+) -> Result<(u64, usize, usize, usize)> {
+    let read_usize = |offset: usize, field: &str| -> Result<usize> {
+        data_resolver
+            .read_memory(base_address + offset as u64, 8)?
+            .try_into()
+            .map(usize::from_le_bytes)
+            .map_err(|_| {
+                anyhow::anyhow!("Failed to read {field} for VecDeque at address {base_address:#x}")
+            })
+    };
+    let head = read_usize(def.head_offset, "head")?;
+    let length = read_usize(def.length_offset, "length")?;
+    // like `RawVec::capacity`, zero-sized items have as much room as they
+    // can index, though the stored capacity is 0
+    let capacity = match def.inner_type.layout.size() {
+        Some(0) => usize::MAX,
+        _ => read_usize(def.capacity_offset, "capacity")?,
+    };
+    tracing::trace!("VecDeque head: {head}, length: {length}, capacity: {capacity}");
+    if length > capacity || (capacity > 0 && head >= capacity) {
+        return Err(implausible!(
+            "head {head} and length {length} don't fit capacity {capacity}"
+        ));
+    }
+    let address = data_resolver
+        .read_address(base_address + def.data_ptr_offset as u64)
+        .with_context(|| {
+            format!(
+                "Failed to read VecDeque data pointer at {:#x}",
+                base_address + def.data_ptr_offset as u64
+            )
+        })?;
+    // the items wrap around the whole buffer, not just the first `length`
+    let buffer_size = if length > 0 { capacity } else { 0 };
+    check_buffer(address, buffer_size, &def.inner_type.layout, data_resolver)?;
+    Ok((address, head, length, capacity))
+}
+
+/// Returns the addresses of the elements of a VecDeque within `range` in
+/// logical order, along with the length of the VecDeque.
+///
+/// The range is clamped to the length, and the addresses account for the ring
+/// buffer wrapping around the end of the allocation.
+pub fn vec_deque_element_addresses(
+    base_address: u64,
+    def: &VecDequeLayout<Die>,
+    element_size: u64,
+    range: Range<usize>,
+    data_resolver: &dyn crate::DataResolver,
+) -> Result<(Vec<u64>, usize)> {
+    let (data_ptr, head, length, capacity) =
+        extract_vec_deque_info(base_address, def, data_resolver)?;
+    // the logical order starts at the head of the ring buffer, wrapping
+    // around to the start without computing `head + i`, which overflows
+    // for zero-sized types (whose capacity is `usize::MAX`)
+    let addresses = clamp_range(&range, length)
+        .map(|i| {
+            let index = i.checked_sub(capacity - head).unwrap_or_else(|| head + i);
+            data_ptr + index as u64 * element_size
+        })
+        .collect();
+    Ok((addresses, length))
+}
+
+/// Returns the addresses of the first `max_elements` elements of a LinkedList
+/// in order, along with the length of the list.
+///
+/// Follows the `next` pointers from the head node, stopping after `len`
+/// nodes and bailing out on null pointers or cycles.
+pub fn linked_list_element_addresses(
+    base_address: u64,
+    def: &LinkedListLayout<Die>,
+    max_elements: usize,
+    data_resolver: &dyn crate::DataResolver,
+) -> Result<(Vec<u64>, usize)> {
+    let length = data_resolver
+        .read_memory(base_address + def.length_offset as u64, 8)?
+        .try_into()
+        .map(usize::from_le_bytes)
+        .map_err(|_| {
+            anyhow::anyhow!("Failed to read length for LinkedList at address {base_address:#x}")
+        })?;
+    tracing::trace!("LinkedList length: {length}");
+
+    let mut visited = std::collections::HashSet::new();
+    let mut elements = Vec::new();
+    let mut node = data_resolver.read_address(base_address + def.head_offset as u64)?;
+    while elements.len() < length.min(max_elements) {
+        if node == 0 {
+            return Err(implausible!(
+                "LinkedList at {base_address:#x} ended after {} of {length} nodes",
+                elements.len()
+            ));
+        }
+        if !visited.insert(node) {
+            return Err(implausible!(
+                "Cycle detected in LinkedList at {base_address:#x} (node {node:#x})"
+            ));
+        }
+        elements.push(node + def.element_offset as u64);
+        node = data_resolver.read_address(node + def.next_offset as u64)?;
+    }
+    Ok((elements, length))
+}
+
 fn read_primitive_from_memory(
     db: &dyn Db,
     address: u64,
     def: &PrimitiveLayout<Die>,
+    options: &ReadOptions,
     data_resolver: &dyn crate::DataResolver,
 ) -> Result<Value> {
     let value = match def {
         PrimitiveLayout::Bool(_) => {
             let memory = data_resolver.read_memory(address, 1)?;
-            let bool_value = memory[0] != 0;
+            let bool_value = match memory[0] {
+                0 => false,
+                1 => true,
+                byte => return Err(implausible!("{byte:#04x} is not a valid bool")),
+            };
             Value::Scalar {
                 ty: "bool".to_string(),
                 value: bool_value.to_string(),
@@ -534,8 +1512,9 @@ fn read_primitive_from_memory(
         }
         PrimitiveLayout::Char(()) => {
             let memory = data_resolver.read_memory(address, 4)?;
-            let char_value = char::from_u32(u32::from_le_bytes(memory.try_into().unwrap()))
-                .ok_or_else(|| anyhow::anyhow!("Invalid char value at address {address:#x}"))?;
+            let code = u32::from_le_bytes(memory.try_into().unwrap());
+            let char_value = char::from_u32(code)
+                .ok_or_else(|| implausible!("{code:#x} is not a valid char"))?;
             Value::Scalar {
                 ty: "char".to_string(),
                 value: format!("'{char_value}'"),
@@ -557,17 +1536,13 @@ fn read_primitive_from_memory(
                 )
             })? as u64;
 
-            let mut address = address;
-            let mut values = Vec::with_capacity(*length);
-            for _ in 0..*length {
-                // Return pointers instead of recursively reading
-                let value = Value::Pointer(TypedPointer {
-                    address,
+            // Return pointers instead of recursively reading
+            let values = limited_items(*length, options, |i| {
+                Value::Pointer(TypedPointer {
+                    address: address + i as u64 * element_size,
                     type_def: element_type.clone(),
-                });
-                values.push(value);
-                address += element_size;
-            }
+                })
+            });
             Value::Array {
                 ty: format!("[{}; {length}]", element_type.display_name()),
                 items: values,
@@ -575,11 +1550,23 @@ fn read_primitive_from_memory(
         }
         PrimitiveLayout::Pointer(PointerLayout { pointed_type, .. }) => {
             let address = data_resolver.read_address(address)?;
-            read_from_memory(db, address, pointed_type, data_resolver)?.prefix_type("*")
+            read_from_memory_with_options(db, address, pointed_type, options, data_resolver)?
+                .prefix_type("*")
         }
         PrimitiveLayout::Reference(ReferenceLayout { pointed_type, .. }) => {
             let address = data_resolver.read_address(address)?;
-            read_from_memory(db, address, pointed_type, data_resolver)?.prefix_type("&")
+            if address == 0 {
+                return Err(implausible!("null reference"));
+            }
+            if let Some(alignment) = alignment(&pointed_type.layout)
+                && address % alignment as u64 != 0
+            {
+                return Err(implausible!(
+                    "reference {address:#x} is not aligned to {alignment} bytes"
+                ));
+            }
+            read_from_memory_with_options(db, address, pointed_type, options, data_resolver)?
+                .prefix_type("&")
         }
         PrimitiveLayout::Slice(SliceLayout {
             element_type,
@@ -600,17 +1587,19 @@ fn read_primitive_from_memory(
                 )
             })? as u64;
             let data_ptr_address = data_resolver.read_address(address + *data_ptr_offset as u64)?;
-            let mut current_addr = data_ptr_address;
-            let mut values = Vec::with_capacity(length as usize);
-            for _ in 0..length {
-                // Return pointers instead of recursively reading
-                let value = Value::Pointer(TypedPointer {
-                    address: current_addr,
+            check_buffer(
+                data_ptr_address,
+                length as usize,
+                &element_type.layout,
+                data_resolver,
+            )?;
+            // Return pointers instead of recursively reading
+            let values = limited_items(length as usize, options, |i| {
+                Value::Pointer(TypedPointer {
+                    address: data_ptr_address + i as u64 * element_size,
                     type_def: element_type.clone(),
-                });
-                values.push(value);
-                current_addr += element_size;
-            }
+                })
+            });
             Value::Array {
                 ty: format!("&[{}]", element_type.display_name()),
                 items: values,
@@ -627,13 +1616,9 @@ fn read_primitive_from_memory(
             let memory = data_resolver.read_memory(length, 8)?;
             let length = u64::from_le_bytes(memory.try_into().unwrap());
             tracing::trace!("length: {length}");
+            check_buffer(data_address, length as usize, &BYTE, data_resolver)?;
 
-            let memory = data_resolver.read_memory(data_address, length as usize)?;
-            let string_value = String::from_utf8_lossy(&memory).to_string();
-            Value::Scalar {
-                ty: "str".to_string(),
-                value: format!("\"{string_value}\""),
-            }
+            read_string_value("str", data_address, length as usize, options, data_resolver)?
         }
         PrimitiveLayout::UnsignedInt(unsigned_int_def) => {
             let memory = data_resolver.read_memory(address, unsigned_int_def.size)?;
@@ -706,12 +1691,33 @@ fn read_primitive_from_memory(
             }
         }
         PrimitiveLayout::Str(()) => {
-            unimplemented!("read_primitive_from_memory: bare `str` is not supported yet");
+            // the length of a `str` lives in the (wide) pointer to it,
+            // so there is nothing we can read from the bytes alone
+            anyhow::bail!(
+                "Cannot read unsized `str` at address {address:#x} without a pointer to it"
+            );
         }
         PrimitiveLayout::Tuple(tuple_def) => {
-            unimplemented!(
-                "read_primitive_from_memory: TupleDef not implemented yet: {tuple_def:#?}"
-            );
+            let entries = tuple_def
+                .elements
+                .iter()
+                .map(|(offset, element_type)| {
+                    // Return pointers instead of recursively reading
+                    Value::Pointer(TypedPointer {
+                        address: address + *offset as u64,
+                        type_def: element_type.clone(),
+                    })
+                })
+                .collect();
+            let element_names: Vec<_> = tuple_def
+                .elements
+                .iter()
+                .map(|(_, element_type)| element_type.display_name())
+                .collect();
+            Value::Tuple {
+                ty: format!("({})", element_names.join(", ")),
+                entries,
+            }
         }
         PrimitiveLayout::Unit(_) => {
             // The Unit type is a zero-sized type, so we return a placeholder value.
@@ -725,7 +1731,7 @@ fn read_primitive_from_memory(
     Ok(value)
 }
 
-fn resolve_alias(db: &dyn Db, def: &DieTypeDefinition) -> Result<DieTypeDefinition> {
+pub(crate) fn resolve_alias(db: &dyn Db, def: &DieTypeDefinition) -> Result<DieTypeDefinition> {
     if let Layout::Alias { name } = def.layout.as_ref() {
         rudy_dwarf::types::resolve_type_offset(db, def.location)
             .with_context(|| format!("Failed to resolve alias for {name}"))
@@ -734,10 +1740,167 @@ fn resolve_alias(db: &dyn Db, def: &DieTypeDefinition) -> Result<DieTypeDefiniti
     }
 }
 
+/// The full path of the type `def`, e.g. `my_crate::shapes::Color`, without
+/// its generic arguments
+pub(crate) fn type_path(db: &dyn Db, def: &DieTypeDefinition) -> String {
+    let display_name = def.display_name();
+    let name = display_name.split('<').next().unwrap_or(&display_name);
+    match rudy_dwarf::modules::get_containing_module(db, def.location) {
+        Some(module_path) if !module_path.is_empty() => {
+            format!("{}::{name}", module_path.join("::"))
+        }
+        _ => name.to_string(),
+    }
+}
+
+/// Whether `name` names `variant` of the enum whose full path is `enum_path`
+///
+/// The variant can be named on its own (`Red`), or with a path that must be
+/// the end of the enum's path (`Color::Red`, `shapes::Color::Red`), so that
+/// a variant of the same name in another enum (`Light::Red`) doesn't match.
+pub(crate) fn names_variant(name: &str, enum_path: &str, variant: &str) -> bool {
+    match name.rsplit_once("::") {
+        None => name == variant,
+        Some((qualifier, name)) => {
+            name == variant
+                && enum_path
+                    .strip_suffix(qualifier)
+                    .is_some_and(|rest| rest.is_empty() || rest.ends_with("::"))
+        }
+    }
+}
+
+/// Reads the raw discriminant value of an enum-like type located at `address`
+fn read_discriminant(
+    address: u64,
+    discriminant: &Discriminant,
+    data_resolver: &dyn crate::DataResolver,
+) -> Result<i128> {
+    let disc_address = address + discriminant.offset as u64;
+
+    Ok(match &discriminant.ty {
+        rudy_types::DiscriminantType::Int(int_def) => {
+            let memory = data_resolver.read_memory(disc_address, int_def.size)?;
+
+            match int_def.size {
+                1 => i8::from_le_bytes(memory.try_into().unwrap()) as i128,
+                2 => i16::from_le_bytes(memory.try_into().unwrap()) as i128,
+                4 => i32::from_le_bytes(memory.try_into().unwrap()) as i128,
+                8 => i64::from_le_bytes(memory.try_into().unwrap()) as i128,
+                _ => {
+                    anyhow::bail!(
+                        "read_discriminant: unsupported IntDef size {} at address {address:#x}",
+                        int_def.size
+                    )
+                }
+            }
+        }
+        rudy_types::DiscriminantType::UnsignedInt(unsigned_int_def) => {
+            let memory = data_resolver.read_memory(disc_address, unsigned_int_def.size)?;
+            match unsigned_int_def.size {
+                1 => u8::from_le_bytes(memory.try_into().unwrap()) as i128,
+                2 => u16::from_le_bytes(memory.try_into().unwrap()) as i128,
+                4 => u32::from_le_bytes(memory.try_into().unwrap()) as i128,
+                8 => u64::from_le_bytes(memory.try_into().unwrap()) as i128,
+                _ => {
+                    anyhow::bail!(
+                        "read_discriminant: unsupported UnsignedIntDef size {} at address {address:#x}",
+                        unsigned_int_def.size
+                    )
+                }
+            }
+        }
+        rudy_types::DiscriminantType::Implicit => {
+            // I guess we'll just read 4 bytes and see what happens?
+            let memory = data_resolver.read_memory(disc_address, 4)?;
+            i32::from_le_bytes(memory.try_into().unwrap()) as i128
+        }
+    })
+}
+
+/// Determines whether the `Result` located at `address` holds its `Ok` variant
+///
+/// Handles both explicitly tagged results and niche-packed ones (e.g.
+/// `Result<(), Box<E>>`), where only one variant has a discriminant value
+/// and the other is the "dataful" variant matching everything else.
+pub(crate) fn result_is_ok(
+    address: u64,
+    result_def: &ResultLayout<Die>,
+    data_resolver: &dyn crate::DataResolver,
+) -> Result<bool> {
+    is_first_variant(
+        address,
+        &result_def.discriminant,
+        result_def.ok_discriminant,
+        result_def.err_discriminant,
+        data_resolver,
+    )
+    .context("could not tell whether Result is Ok or Err")
+}
+
+/// Determines whether the `Cow` located at `address` holds its `Borrowed` variant
+pub(crate) fn cow_is_borrowed(
+    address: u64,
+    cow_def: &CowLayout<Die>,
+    data_resolver: &dyn crate::DataResolver,
+) -> Result<bool> {
+    is_first_variant(
+        address,
+        &cow_def.discriminant,
+        cow_def.borrowed_discriminant,
+        cow_def.owned_discriminant,
+        data_resolver,
+    )
+    .context("could not tell whether Cow is Borrowed or Owned")
+}
+
+/// Determines whether a two-variant enum holds its first variant
+fn is_first_variant(
+    address: u64,
+    discriminant: &Discriminant,
+    first_discriminant: Option<i128>,
+    second_discriminant: Option<i128>,
+    data_resolver: &dyn crate::DataResolver,
+) -> Result<bool> {
+    let disc_value = read_discriminant(address, discriminant, data_resolver)?;
+
+    if matches!(discriminant.ty, rudy_types::DiscriminantType::Implicit) {
+        // no explicit discriminant, so the variant index is the value
+        return match disc_value {
+            0 => Ok(true),
+            1 => Ok(false),
+            _ => Err(implausible!(
+                "invalid implicit discriminant {disc_value} at {address:#x}"
+            )),
+        };
+    }
+
+    // DWARF may encode the variant values with a different signedness than
+    // the discriminant type, so only compare the bits the discriminant occupies
+    let mask = match discriminant.size() {
+        size @ 1..=8 => (1u128 << (size * 8)) - 1,
+        _ => u128::MAX,
+    };
+    let matches = |value: i128| (value as u128 & mask) == (disc_value as u128 & mask);
+
+    match (first_discriminant, second_discriminant) {
+        (Some(first), _) if matches(first) => Ok(true),
+        (_, Some(second)) if matches(second) => Ok(false),
+        // neither value matched, so the active variant is whichever one
+        // carries the niche (i.e. has no discriminant value of its own)
+        (None, Some(_)) => Ok(true),
+        (Some(_), None) => Ok(false),
+        _ => Err(implausible!(
+            "discriminant {disc_value} at {address:#x} matches no variant"
+        )),
+    }
+}
+
 fn read_option_from_memory(
     db: &dyn Db,
     address: u64,
     opt_def: &OptionLayout<Die>,
+    options: &ReadOptions,
     data_resolver: &dyn crate::DataResolver,
 ) -> Result<Value> {
     let OptionLayout {
@@ -751,30 +1914,171 @@ fn read_option_from_memory(
         address + opt_def.discriminant.offset as u64,
         discriminant.size(),
     )?;
+    let ty = format!("Option<{}>", some_type.display_name());
     Ok(if first_byte[0] == 0 {
-        Value::Scalar {
-            ty: some_type.display_name(),
-            value: "None".to_string(),
+        Value::Enum {
+            ty,
+            variant: "None".to_string(),
+            discriminant: 0,
+            payload: None,
         }
     } else {
         tracing::debug!("Found Some variant at {address:#x}: {some_type:#?}");
         // we have a `Some` variant
         // we should get the address of the inner value
-        read_from_memory(db, address + *some_offset as u64, some_type, data_resolver)?
+        let inner = read_from_memory_with_options(
+            db,
+            address + *some_offset as u64,
+            some_type,
+            options,
+            data_resolver,
+        )?;
+        Value::Enum {
+            variant: "Some".to_string(),
+            discriminant: 1,
+            payload: Some(Box::new(Value::Tuple {
+                ty: format!("{ty}::Some"),
+                entries: vec![inner],
+            })),
+            ty,
+        }
+    })
+}
+
+/// Reads the `(strong, weak)` reference counts of an `Rc`, `Arc` or `Weak`
+///
+/// Counts are reported the way `Rc::strong_count`/`Rc::weak_count` would:
+/// the implicit weak reference held by the strong pointers is not included,
+/// and a dangling `Weak` (e.g. from `Weak::new()`) has no references at all.
+pub(crate) fn read_ref_counts(
+    address: u64,
+    def: &SmartPtrLayout<Die>,
+    data_resolver: &dyn crate::DataResolver,
+) -> Result<(usize, usize)> {
+    let SmartPtrState::RefCounts {
+        strong_offset,
+        weak_offset,
+    } = def.state
+    else {
+        anyhow::bail!("{} has no reference counts", def.variant.name());
+    };
+
+    let inner_address = data_resolver.read_address(address + def.inner_ptr_offset as u64)?;
+    if inner_address == usize::MAX as u64 {
+        // `Weak::new()` uses a dangling sentinel rather than allocating
+        return Ok((0, 0));
+    }
+
+    let strong = data_resolver.read_address(inner_address + strong_offset as u64)? as usize;
+    let weak = data_resolver.read_address(inner_address + weak_offset as u64)? as usize;
+
+    if strong == 0 {
+        Ok((0, 0))
+    } else {
+        Ok((strong, weak.saturating_sub(1)))
+    }
+}
+
+/// Reads the bytes of a `PathBuf`, `OsString` or `CString` (or their borrowed forms)
+///
+/// The nul terminator of C strings is not included.
+pub(crate) fn read_os_string_bytes(
+    address: u64,
+    def: &OsStringLayout,
+    data_resolver: &dyn crate::DataResolver,
+) -> Result<Vec<u8>> {
+    let (data, length) = os_string_data(address, def, data_resolver)?;
+    let mut bytes = data_resolver.read_memory(data, length)?;
+    if def.variant.is_nul_terminated() && bytes.last() == Some(&0) {
+        bytes.pop();
+    }
+    Ok(bytes)
+}
+
+/// Reads the data pointer and length (including any nul terminator) of a
+/// `PathBuf`, `OsString` or `CString`
+fn os_string_data(
+    address: u64,
+    def: &OsStringLayout,
+    data_resolver: &dyn crate::DataResolver,
+) -> Result<(u64, usize)> {
+    let length = data_resolver.read_address(address + def.length_offset as u64)? as usize;
+    let data_address = address + def.data_ptr_offset as u64;
+    let data = data_resolver.read_address(data_address).with_context(|| {
+        format!(
+            "Failed to read {} data pointer at {data_address:#x}",
+            def.variant.name()
+        )
+    })?;
+    check_buffer(data, length, &BYTE, data_resolver)?;
+    Ok((data, length))
+}
+
+/// Reads the (possibly unsized) data a `Box`, `Rc`, `Arc` or `Weak` points to
+///
+/// `address` is the location of the smart pointer itself, which holds the
+/// metadata of wide pointers.
+fn read_smart_ptr_data(
+    db: &dyn Db,
+    address: u64,
+    data_address: u64,
+    def: &SmartPtrLayout<Die>,
+    options: &ReadOptions,
+    data_resolver: &dyn crate::DataResolver,
+) -> Result<Value> {
+    match def.metadata {
+        PointerMetadata::None => {
+            read_from_memory_with_options(db, data_address, &def.inner_type, options, data_resolver)
+        }
+        PointerMetadata::Length { length_offset } => {
+            let length = data_resolver.read_address(address + length_offset as u64)? as usize;
+            if let Layout::Primitive(PrimitiveLayout::Str(_)) = def.inner_type.layout.as_ref() {
+                check_buffer(data_address, length, &BYTE, data_resolver)?;
+                return read_string_value("str", data_address, length, options, data_resolver);
+            }
+
+            let element_type = resolve_alias(db, &def.inner_type)?;
+            let element_size = element_type.size().with_context(|| {
+                format!(
+                    "inner type: {} has unknown size",
+                    element_type.display_name()
+                )
+            })? as u64;
+            check_buffer(data_address, length, &element_type.layout, data_resolver)?;
+            let items = limited_items(length, options, |i| {
+                Value::Pointer(TypedPointer {
+                    address: data_address + i as u64 * element_size,
+                    type_def: element_type.clone(),
+                })
+            });
+            Ok(Value::Array {
+                ty: format!("[{}]", element_type.display_name()),
+                items,
+            })
+        }
+        PointerMetadata::VTable { vtable_offset } => {
+            // we don't know the concrete type behind a trait object, so we
+            // only show where the data and its vtable live
+            let vtable = data_resolver.read_address(address + vtable_offset as u64)?;
+            Ok(Value::Scalar {
+                ty: def.inner_type.display_name(),
+                value: format!("{data_address:#x} (vtable: {vtable:#x})"),
+            })
+        }
     }
-    .wrap_type("Option"))
 }
 
 fn read_std_from_memory(
     db: &dyn Db,
     address: u64,
     def: &StdLayout<Die>,
+    options: &ReadOptions,
     data_resolver: &dyn crate::DataResolver,
 ) -> Result<Value> {
     let value = match def {
         StdLayout::Option(enum_def) => {
             tracing::trace!("reading Option at {address:#x}");
-            read_option_from_memory(db, address, enum_def, data_resolver)?
+            read_option_from_memory(db, address, enum_def, options, data_resolver)?
         }
         StdLayout::Vec(
             v @ VecLayout {
@@ -796,93 +2100,258 @@ fn read_std_from_memory(
                 )
             })? as u64;
 
-            let (mut address, length) = extract_vec_info(address, v, data_resolver)?;
-            let mut values = Vec::with_capacity(length);
+            let (address, length) = extract_vec_info(address, v, data_resolver)?;
             tracing::trace!("reading Vec data at {address:#016x}");
-            for _ in 0..length {
-                // Return pointers instead of recursively reading
-                let value = Value::Pointer(TypedPointer {
-                    address,
+            // Return pointers instead of recursively reading
+            let values = limited_items(length, options, |i| {
+                Value::Pointer(TypedPointer {
+                    address: address + i as u64 * element_size,
                     type_def: element_type.clone(),
-                });
-                values.push(value);
-                address += element_size;
-            }
+                })
+            });
             Value::Array {
                 ty: format!("Vec<{}>", element_type.display_name()),
                 items: values,
             }
         }
+        StdLayout::VecDeque(def) => {
+            tracing::trace!("reading VecDeque at {address:#x}");
+            let element_type = resolve_alias(db, &def.inner_type)?;
+            let element_size = element_type.size().with_context(|| {
+                format!(
+                    "inner type: {} has unknown size",
+                    element_type.display_name()
+                )
+            })? as u64;
+            let (addresses, length) = vec_deque_element_addresses(
+                address,
+                def,
+                element_size,
+                0..options.max_items,
+                data_resolver,
+            )?;
+            let items = limited_items(length, options, |i| {
+                Value::Pointer(TypedPointer {
+                    address: addresses[i],
+                    type_def: element_type.clone(),
+                })
+            });
+            Value::Array {
+                ty: format!("VecDeque<{}>", element_type.display_name()),
+                items,
+            }
+        }
+        StdLayout::LinkedList(def) => {
+            tracing::trace!("reading LinkedList at {address:#x}");
+            let element_type = resolve_alias(db, &def.inner_type)?;
+            let (addresses, length) =
+                linked_list_element_addresses(address, def, options.max_items, data_resolver)?;
+            let items = limited_items(length, options, |i| {
+                Value::Pointer(TypedPointer {
+                    address: addresses[i],
+                    type_def: element_type.clone(),
+                })
+            });
+            Value::Array {
+                ty: format!("LinkedList<{}>", element_type.display_name()),
+                items,
+            }
+        }
+        StdLayout::BinaryHeap(heap) => {
+            // The heap is stored as a Vec, which we show in storage order
+            read_std_from_memory(
+                db,
+                address,
+                &StdLayout::Vec(heap.0.clone()),
+                options,
+                data_resolver,
+            )?
+            .map_type(|ty| ty.replacen("Vec", "BinaryHeap", 1))
+        }
         StdLayout::String(s) => {
             let v = &s.0;
             tracing::trace!(
                 "reading String length at {:#x}",
                 address + v.length_offset as u64
             );
-            let length = data_resolver
-                .read_memory(address + v.length_offset as u64, 8)?
-                .try_into()
-                .map(usize::from_le_bytes)
-                .map_err(|_| {
-                    anyhow::anyhow!("Failed to read length for Vec at address {address:#x}")
-                })?;
-            tracing::trace!("String length: {length}");
-            // read the bytes from the data pointer
-            let data_address = address + s.0.data_ptr_offset as u64;
-            let data = data_resolver.read_address(data_address).with_context(|| {
-                format!("Failed to read String data pointer at {data_address:#x}")
-            })?;
+            let (data, length) = extract_vec_info(address, v, data_resolver)?;
             tracing::trace!("reading String data at {data:#016x}");
-            let bytes = data_resolver.read_memory(data, length)?;
-            let value = String::from_utf8_lossy(&bytes).to_string();
-            Value::Scalar {
-                ty: "String".to_string(),
-                value: format!("\"{value}\""),
+            read_string_value("String", data, length, options, data_resolver)?
+        }
+        StdLayout::OsString(def) => {
+            tracing::trace!("reading {} at {address:#x}", def.variant.name());
+            let (data, mut length) = os_string_data(address, def, data_resolver)?;
+            if def.variant.is_nul_terminated() {
+                // C strings always end with their nul terminator
+                length = length.saturating_sub(1);
             }
+            // these needn't be UTF-8, so invalid bytes are expected
+            read_lossy_string_value(def.variant.name(), data, length, options, data_resolver)?.0
         }
         StdLayout::Map(def) => {
-            let entries = read_map_entries(address, def, data_resolver)?
+            let (entries, length) =
+                read_map_entries_range(address, def, 0..options.max_items, data_resolver)?;
+            let truncated = (entries.len() < length).then(|| length - entries.len());
+            let entries = entries
                 .into_iter()
-                .map(|(key, value)| Ok((Value::Pointer(key), Value::Pointer(value))))
-                .collect::<Result<Vec<_>>>()?;
+                .map(|(key, value)| (Value::Pointer(key), Value::Pointer(value)))
+                .collect();
             Value::Map {
                 ty: def.display_name(),
                 entries,
+                truncated,
+            }
+        }
+        StdLayout::Set(def) => {
+            // sets are maps with zero-sized values, so we only keep the keys
+            let (entries, length) = read_map_entries_range(
+                address + def.map_offset as u64,
+                &def.map,
+                0..options.max_items,
+                data_resolver,
+            )?;
+            let mut items: Vec<_> = entries
+                .into_iter()
+                .map(|(key, _)| Value::Pointer(key))
+                .collect();
+            if items.len() < length {
+                items.push(Value::Truncated {
+                    remaining: length - items.len(),
+                });
+            }
+            Value::Set {
+                ty: def.display_name(),
+                items,
             }
         }
         StdLayout::SmartPtr(s) => match s.variant {
-            SmartPtrVariant::Mutex | SmartPtrVariant::RefCell => {
+            SmartPtrVariant::Mutex
+            | SmartPtrVariant::RwLock
+            | SmartPtrVariant::RefCell
+            | SmartPtrVariant::Cell
+            | SmartPtrVariant::UnsafeCell
+            | SmartPtrVariant::ManuallyDrop => {
                 let inner_type = s.inner_type.clone();
                 let address = address + s.inner_ptr_offset as u64;
-                read_from_memory(db, address, &inner_type, data_resolver)?
+                read_from_memory_with_options(db, address, &inner_type, options, data_resolver)?
                     .wrap_type(s.variant.name())
             }
+            SmartPtrVariant::MaybeUninit => {
+                // we have no way to tell whether the value was ever written
+                let address = address + s.inner_ptr_offset as u64;
+                let value = read_from_memory_with_options(
+                    db,
+                    address,
+                    &s.inner_type,
+                    options,
+                    data_resolver,
+                )?;
+                Value::Suspect {
+                    reason: "may be uninitialized".to_string(),
+                    value: Box::new(value),
+                }
+            }
             SmartPtrVariant::Box => {
-                let inner_type = s.inner_type.clone();
-                let address = data_resolver.read_address(address)?;
-                read_from_memory(db, address, &inner_type, data_resolver)?
+                let data_address =
+                    data_resolver.read_address(address + s.inner_ptr_offset as u64)?;
+                read_smart_ptr_data(db, address, data_address, s, options, data_resolver)?
                     .wrap_type(s.variant.name())
             }
             SmartPtrVariant::Rc | SmartPtrVariant::Arc => {
-                let inner_type = s.inner_type.clone();
                 let inner_address =
                     data_resolver.read_address(address + s.inner_ptr_offset as u64)?;
                 let data_address = inner_address + s.data_ptr_offset as u64;
-                read_from_memory(db, data_address, &inner_type, data_resolver)?
+                read_smart_ptr_data(db, address, data_address, s, options, data_resolver)?
                     .wrap_type(s.variant.name())
             }
-            _ => {
-                unimplemented!("read_std_from_memory: SmartPtrVariant not implemented yet: {s:#?}")
+            SmartPtrVariant::Weak => {
+                let inner_address =
+                    data_resolver.read_address(address + s.inner_ptr_offset as u64)?;
+                if read_ref_counts(address, s, data_resolver)?.0 == 0 {
+                    // nothing left to upgrade to
+                    Value::Scalar {
+                        ty: s.inner_type.display_name(),
+                        value: "(dropped)".to_string(),
+                    }
+                } else {
+                    let data_address = inner_address + s.data_ptr_offset as u64;
+                    read_smart_ptr_data(db, address, data_address, s, options, data_resolver)?
+                }
+                .wrap_type(s.variant.name())
             }
         },
+        StdLayout::Cow(cow_def) => {
+            // show the content, whether it is borrowed or owned
+            let (offset, payload_type) = if cow_is_borrowed(address, cow_def, data_resolver)? {
+                (cow_def.borrowed_offset, &cow_def.borrowed_type)
+            } else {
+                (cow_def.owned_offset, &cow_def.owned_type)
+            };
+            read_from_memory_with_options(
+                db,
+                address + offset as u64,
+                payload_type,
+                options,
+                data_resolver,
+            )?
+            .map_type(|_| cow_def.name.clone())
+        }
         StdLayout::Result(result_def) => {
-            unimplemented!("read_std_from_memory: ResultDef not implemented yet: {result_def:#?}")
+            let (variant, discriminant, offset, payload_type) =
+                if result_is_ok(address, result_def, data_resolver)? {
+                    ("Ok", 0, result_def.ok_offset, &result_def.ok_type)
+                } else {
+                    ("Err", 1, result_def.err_offset, &result_def.err_type)
+                };
+            tracing::trace!("reading Result::{variant} at {address:#x}");
+            let payload = read_from_memory_with_options(
+                db,
+                address + offset as u64,
+                payload_type,
+                options,
+                data_resolver,
+            )?;
+            Value::Enum {
+                ty: result_def.name.clone(),
+                variant: variant.to_string(),
+                discriminant,
+                payload: Some(Box::new(Value::Tuple {
+                    ty: format!("{}::{variant}", result_def.name),
+                    entries: vec![payload],
+                })),
+            }
         }
     };
 
     Ok(value)
 }
 
+/// Reads the root node pointer and height of a BTreeMap from its
+/// `Option<Root>` at `root_addr`, or `None` if the map has no root
+pub(crate) fn btree_root(
+    root_addr: u64,
+    root_layout: &BTreeRootLayout,
+    data_resolver: &dyn crate::DataResolver,
+) -> Result<Option<(u64, usize)>> {
+    // The root node pointer is never null, so a zero means `None`
+    let discriminant_bytes = data_resolver.read_memory(root_addr, 8)?;
+    let discriminant = u64::from_le_bytes(discriminant_bytes.try_into().unwrap());
+    if discriminant == 0 {
+        return Ok(None);
+    }
+
+    // Read the root node pointer and height
+    let node_ptr_addr = root_addr + root_layout.node_offset as u64;
+    let height_addr = root_addr + root_layout.height_offset as u64;
+
+    let node_ptr = data_resolver.read_address(node_ptr_addr)?;
+    let height_bytes = data_resolver.read_memory(height_addr, 8)?;
+    let height = usize::from_le_bytes(height_bytes.try_into().unwrap());
+
+    tracing::trace!("BTreeMap root node: {node_ptr:#x}, height: {height}");
+    Ok(Some((node_ptr, height)))
+}
+
 /// Recursively read entries from a BTree node following the algorithm from the Python code
 #[allow(clippy::too_many_arguments)]
 fn read_btree_node_entries(
@@ -891,6 +2360,8 @@ fn read_btree_node_entries(
     key_type: &DieTypeDefinition,
     value_type: &DieTypeDefinition,
     node_layout: &BTreeNodeLayout,
+    range: &Range<usize>,
+    seen: &mut usize,
     data_resolver: &dyn crate::DataResolver,
     entries: &mut Vec<(TypedPointer, TypedPointer)>,
 ) -> Result<()> {
@@ -915,6 +2386,12 @@ fn read_btree_node_entries(
     let keys_addr = node_ptr + node_layout.keys_offset as u64;
     let vals_addr = node_ptr + node_layout.vals_offset as u64;
 
+    // Leaves entirely before the range only need counting
+    if height == 0 && *seen + len <= range.start {
+        *seen += len;
+        return Ok(());
+    }
+
     // If this is an internal node (height > 0), we need to traverse edges
     let edges_addr = if height > 0 {
         Some(node_ptr + node_layout.edges_offset as u64)
@@ -924,13 +2401,16 @@ fn read_btree_node_entries(
 
     // For each index from 0 to len (inclusive for edges)
     for i in 0..=len {
+        if *seen >= range.end {
+            break;
+        }
+
         // If this is an internal node, traverse the edge before processing the key/value
         if let Some(edges_base) = edges_addr
             && i <= len
         {
             // Read the edge pointer (edges[i])
-            // Edges are MaybeUninit<NodePtr>, so we need to handle the pointer size
-            let edge_addr = edges_base + (i * 8) as u64; // Assume 8-byte pointers
+            let edge_addr = edges_base + (i * node_layout.edge_size) as u64;
             let edge_ptr = data_resolver.read_address(edge_addr)?;
 
             if edge_ptr != 0 {
@@ -941,6 +2421,8 @@ fn read_btree_node_entries(
                     key_type,
                     value_type,
                     node_layout,
+                    range,
+                    seen,
                     data_resolver,
                     entries,
                 )?;
@@ -948,25 +2430,26 @@ fn read_btree_node_entries(
         }
 
         // Process the key-value pair (only if i < len)
-        if i < len {
-            // Read key and value from the MaybeUninit arrays
-            // Skip zero-sized types as per the Python code
-            if key_size > 0 && value_size > 0 {
-                let key_addr = keys_addr + (i * key_size) as u64;
-                let value_addr = vals_addr + (i * value_size) as u64;
-
-                let key_ptr = TypedPointer {
-                    address: key_addr,
-                    type_def: key_type.clone(),
-                };
+        if i < len && *seen < range.end {
+            // Read key and value from the MaybeUninit arrays. Zero-sized
+            // types (e.g. the values of a BTreeSet) all live at the array base.
+            let key_addr = keys_addr + (i * key_size) as u64;
+            let value_addr = vals_addr + (i * value_size) as u64;
+
+            let key_ptr = TypedPointer {
+                address: key_addr,
+                type_def: key_type.clone(),
+            };
 
-                let value_ptr = TypedPointer {
-                    address: value_addr,
-                    type_def: value_type.clone(),
-                };
+            let value_ptr = TypedPointer {
+                address: value_addr,
+                type_def: value_type.clone(),
+            };
 
+            if *seen >= range.start {
                 entries.push((key_ptr, value_ptr));
             }
+            *seen += 1;
         }
     }
 
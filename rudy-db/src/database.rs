@@ -46,7 +46,10 @@
 use std::path::PathBuf;
 
 use anyhow::Result;
-use rudy_dwarf::{Binary, DwarfDb, file::File};
+use rudy_dwarf::{
+    Binary, DwarfDb,
+    file::{File, RustSrc},
+};
 
 #[salsa::db]
 pub trait Db: salsa::Database + DwarfDb {}
@@ -56,11 +59,13 @@ pub trait Db: salsa::Database + DwarfDb {}
 pub struct DebugDatabaseImpl {
     storage: salsa::Storage<Self>,
     source_map: Vec<(PathBuf, PathBuf)>,
+    rust_src: Option<RustSrc>,
 }
 
 pub struct DebugDbRef {
     handle: salsa::StorageHandle<DebugDatabaseImpl>,
     source_map: Vec<(PathBuf, PathBuf)>,
+    rust_src: Option<RustSrc>,
 }
 
 impl DebugDbRef {
@@ -68,6 +73,7 @@ impl DebugDbRef {
         DebugDatabaseImpl {
             storage: self.handle.into_storage(),
             source_map: self.source_map,
+            rust_src: self.rust_src,
         }
     }
 }
@@ -94,6 +100,7 @@ impl DebugDatabaseImpl {
         Self {
             storage: salsa::Storage::default(),
             source_map: Default::default(),
+            rust_src: None,
         }
     }
 
@@ -117,6 +124,7 @@ impl DebugDatabaseImpl {
         Self {
             storage: salsa::Storage::new(event_callback),
             source_map: Default::default(),
+            rust_src: None,
         }
     }
 
@@ -125,6 +133,15 @@ impl DebugDatabaseImpl {
         self
     }
 
+    /// Remaps the standard library's `/rustc/<commit>/` source paths to
+    /// the given `rust-src` component, e.g. as found by [`RustSrc::detect`]
+    ///
+    /// Only paths from the same `rustc` commit as the component are remapped.
+    pub fn with_rust_src(mut self, rust_src: RustSrc) -> Self {
+        self.rust_src = Some(rust_src);
+        self
+    }
+
     pub(crate) fn load_binary(&self, binary_file: PathBuf) -> Result<Binary> {
         let file = File::build(self, binary_file, None)?;
         Ok(Binary::new(self, file))
@@ -134,6 +151,7 @@ impl DebugDatabaseImpl {
         DebugDbRef {
             handle: self.storage.clone().into_zalsa_handle(),
             source_map: self.source_map.clone(),
+            rust_src: self.rust_src.clone(),
         }
     }
 }
@@ -146,6 +164,10 @@ impl DwarfDb for DebugDatabaseImpl {
     fn get_source_map(&self) -> &[(PathBuf, PathBuf)] {
         &self.source_map
     }
+
+    fn get_rust_src(&self) -> Option<&RustSrc> {
+        self.rust_src.as_ref()
+    }
 }
 
 #[salsa::db]
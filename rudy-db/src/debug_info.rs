@@ -1,12 +1,18 @@
-use std::{collections::BTreeMap, fmt, path::PathBuf};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    path::{Path, PathBuf},
+};
 
 use anyhow::{Context, Result};
+use itertools::Itertools;
 use rudy_dwarf::{
     Die, SourceFile, SymbolName,
+    file::SourceOrigin,
     function::resolve_function_variables,
     types::{DieTypeDefinition, resolve_type_offset},
 };
-use rudy_types::{Layout, PrimitiveLayout, StdLayout};
+use rudy_types::{Layout, MapLayout, MapVariant, PrimitiveLayout, StdLayout};
 
 use crate::{
     DiscoveredMethod, ResolvedLocation,
@@ -14,7 +20,7 @@ use crate::{
     function_discovery::SymbolAnalysisResult,
     index,
     outputs::{ResolvedFunction, TypedPointer},
-    query::{lookup_address, lookup_position},
+    query::{lookup_address, lookup_file_lines, lookup_module, lookup_position, lookup_positions},
 };
 
 /// Main interface for accessing debug information from binary files.
@@ -193,13 +199,14 @@ impl<'db> DebugInfo<'db> {
     ///
     /// # Arguments
     ///
-    /// * `file` - The source file path
+    /// * `file` - The source file path, or a unique suffix of it (e.g. `src/main.rs`)
     /// * `line` - The line number in the source file
     /// * `column` - Optional column number
     ///
     /// # Returns
     ///
-    /// The memory address if the position can be resolved
+    /// The memory address if the position can be resolved, or an error
+    /// listing the candidates if `file` matches multiple source files
     ///
     /// # Examples
     ///
@@ -217,35 +224,322 @@ impl<'db> DebugInfo<'db> {
         line: u64,
         column: Option<u64>,
     ) -> Result<Option<crate::ResolvedAddress>> {
+        let Some(file) = self.resolve_source_file(file)? else {
+            return Ok(None);
+        };
+
+        let query = rudy_dwarf::file::SourceLocation::new(file, line, column);
+        let pos = lookup_position(self.db, self.binary, query);
+        Ok(pos.map(|address| crate::ResolvedAddress { address }))
+    }
+
+    /// Resolves a source file position to every memory address it corresponds to.
+    ///
+    /// A line inside a generic function has one address per monomorphization,
+    /// and a line inside an `#[inline]` function may have been copied into
+    /// many callers. This returns all of them so that a complete set of
+    /// breakpoints can be set.
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - The source file path, or a unique suffix of it (e.g. `src/main.rs`)
+    /// * `line` - The line number in the source file
+    /// * `column` - Optional column number
+    ///
+    /// # Returns
+    ///
+    /// All matching addresses, sorted by address, each tagged with
+    /// the function containing it and whether it is an inlined copy.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use rudy_db::{DebugDb, DebugInfo};
+    /// # let db = DebugDb::new();
+    /// # let debug_info = DebugInfo::new(&db, "binary").unwrap();
+    /// for bp in debug_info.find_all_addresses_from_source_location("src/main.rs", 42, None).unwrap() {
+    ///     println!("{:#x} in {} (inlined: {})", bp.address, bp.function, bp.inlined);
+    /// }
+    /// ```
+    pub fn find_all_addresses_from_source_location(
+        &self,
+        file: &str,
+        line: u64,
+        column: Option<u64>,
+    ) -> Result<Vec<crate::ResolvedBreakpoint>> {
+        let Some(file) = self.resolve_source_file(file)? else {
+            return Ok(vec![]);
+        };
+
+        let query = rudy_dwarf::file::SourceLocation::new(file, line, column);
+        Ok(lookup_positions(self.db, self.binary, query)
+            .into_iter()
+            .map(|m| crate::ResolvedBreakpoint {
+                address: m.address,
+                function: m.function_name,
+                inlined: m.inlined,
+            })
+            .collect())
+    }
+
+    /// Lists all source files referenced by the debug info, grouped by crate.
+    ///
+    /// Crates are sorted local first, then dependencies, then the standard library.
+    /// Dependency and standard library crate names are derived from their paths,
+    /// while local crate names are read from the nearest `Cargo.toml`, if the
+    /// sources are available on disk.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use rudy_db::{DebugDb, DebugInfo};
+    /// # let db = DebugDb::new();
+    /// # let debug_info = DebugInfo::new(&db, "binary").unwrap();
+    /// for krate in debug_info.source_files() {
+    ///     println!("{:?} ({:?}): {} files", krate.name, krate.origin, krate.files.len());
+    /// }
+    /// ```
+    pub fn source_files(&self) -> Vec<crate::CrateSourceFiles> {
         let index = crate::index::debug_index(self.db, self.binary);
 
+        let debug_files: BTreeSet<_> = index
+            .source_to_file(self.db)
+            .values()
+            .flatten()
+            .copied()
+            .collect();
+        let local_crates: BTreeMap<PathBuf, String> = debug_files
+            .into_iter()
+            .flat_map(|debug_file| {
+                rudy_dwarf::file::index_local_crates(self.db, debug_file).clone()
+            })
+            .collect();
+
+        let mut crates: BTreeMap<(SourceOrigin, Option<String>), Vec<String>> = BTreeMap::new();
+        for file in index.source_to_file(self.db).keys() {
+            let (origin, name) = match file.origin() {
+                (SourceOrigin::Local, _) => (
+                    SourceOrigin::Local,
+                    local_crate_name(&file.path, &local_crates),
+                ),
+                origin => origin,
+            };
+            crates
+                .entry((origin, name))
+                .or_default()
+                .push(file.path_str().to_string());
+        }
+
+        crates
+            .into_iter()
+            .map(|((origin, name), files)| crate::CrateSourceFiles {
+                name,
+                origin,
+                files,
+            })
+            .collect()
+    }
+
+    /// Lists the functions defined in a source file, along with the lines they span.
+    ///
+    /// Each generic function is listed once, regardless of how many times it
+    /// has been monomorphized. Functions that were entirely optimized out
+    /// are not listed.
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - The source file path, or a unique suffix of it (e.g. `src/main.rs`)
+    ///
+    /// # Returns
+    ///
+    /// The functions sorted by their starting line, or an error
+    /// listing the candidates if `file` matches multiple source files
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use rudy_db::{DebugDb, DebugInfo};
+    /// # let db = DebugDb::new();
+    /// # let debug_info = DebugInfo::new(&db, "binary").unwrap();
+    /// for function in debug_info.functions_in_file("src/main.rs").unwrap() {
+    ///     println!("{}: lines {}-{}", function.name, function.start_line, function.end_line);
+    /// }
+    /// ```
+    pub fn functions_in_file(&self, file: &str) -> Result<Vec<crate::FunctionSpan>> {
+        let Some(file) = self.resolve_source_file(file)? else {
+            return Ok(vec![]);
+        };
+
+        let mut functions: Vec<crate::FunctionSpan> = vec![];
+        for function in lookup_file_lines(self.db, self.binary, file).functions {
+            let name = function.function.to_string();
+            // monomorphizations share the same name and start line
+            if let Some(existing) = functions
+                .iter_mut()
+                .find(|f| f.name == name && f.start_line == function.start_line)
+            {
+                existing.end_line = existing.end_line.max(function.end_line);
+                continue;
+            }
+            functions.push(crate::FunctionSpan {
+                name,
+                start_line: function.start_line,
+                end_line: function.end_line,
+            });
+        }
+
+        Ok(functions)
+    }
+
+    /// Lists the lines of a source file that a breakpoint can be set on.
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - The source file path, or a unique suffix of it (e.g. `src/main.rs`)
+    ///
+    /// # Returns
+    ///
+    /// The sorted line numbers that have code associated with them, or an error
+    /// listing the candidates if `file` matches multiple source files
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use rudy_db::{DebugDb, DebugInfo};
+    /// # let db = DebugDb::new();
+    /// # let debug_info = DebugInfo::new(&db, "binary").unwrap();
+    /// let lines = debug_info.breakable_lines("src/main.rs").unwrap();
+    /// println!("breakable lines: {lines:?}");
+    /// ```
+    pub fn breakable_lines(&self, file: &str) -> Result<Vec<u64>> {
+        let Some(file) = self.resolve_source_file(file)? else {
+            return Ok(vec![]);
+        };
+
+        Ok(lookup_file_lines(self.db, self.binary, file)
+            .lines
+            .into_iter()
+            .collect())
+    }
+
+    /// Lists the contents of a module: its child modules, and the types,
+    /// functions and statics declared within it.
+    ///
+    /// Generic types and functions are listed once, along with
+    /// each of their instantiations found in the binary.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The full path of the module (e.g. `my_crate::server`),
+    ///   or an empty string to list the crates in the binary
+    ///
+    /// # Returns
+    ///
+    /// The module's contents, or `None` if the module is not found
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use rudy_db::{DebugDb, DebugInfo};
+    /// # let db = DebugDb::new();
+    /// # let debug_info = DebugInfo::new(&db, "binary").unwrap();
+    /// if let Some(module) = debug_info.module("std::collections") {
+    ///     for child in &module.modules {
+    ///         println!("mod {child}");
+    ///     }
+    ///     for ty in &module.types {
+    ///         println!("type {} ({} instantiations)", ty.name, ty.instantiations.len());
+    ///     }
+    /// }
+    /// ```
+    pub fn module(&self, path: &str) -> Option<crate::ModuleInfo> {
+        let module_path = path
+            .split("::")
+            .map(str::trim)
+            .filter(|segment| !segment.is_empty())
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+
+        let items = lookup_module(self.db, self.binary, module_path.clone())?;
+
+        fn module_items(
+            items: BTreeMap<String, std::collections::BTreeSet<String>>,
+        ) -> Vec<crate::ModuleItem> {
+            items
+                .into_iter()
+                .map(|(name, instantiations)| {
+                    let instantiations = if instantiations.iter().all(|i| *i == name) {
+                        vec![]
+                    } else {
+                        instantiations.into_iter().collect()
+                    };
+                    crate::ModuleItem {
+                        name,
+                        instantiations,
+                    }
+                })
+                .collect()
+        }
+
+        Some(crate::ModuleInfo {
+            path: module_path.join("::"),
+            modules: items.modules.into_iter().collect(),
+            types: module_items(items.types),
+            functions: module_items(items.functions),
+            statics: items.statics.into_iter().collect(),
+        })
+    }
+
+    /// Finds the indexed source file matching the provided path
+    ///
+    /// Tries an exact match first, then a unique match on a suffix of the
+    /// path (e.g. `src/main.rs`), and finally a fuzzy match on the file name and
+    /// parent directories (e.g. `my_crate/main.rs`). Returns an error listing
+    /// the candidates if the path is ambiguous.
+    fn resolve_source_file(&self, file: &str) -> Result<Option<SourceFile>> {
+        let index = crate::index::debug_index(self.db, self.binary);
+        let source_files = index.source_to_file(self.db);
+
         let path = PathBuf::from(file.to_string());
-        let source_file = SourceFile::new(path);
+        let source_file = SourceFile::new(path.clone());
 
-        let file = if index.source_to_file(self.db).contains_key(&source_file) {
+        if source_files.contains_key(&source_file) {
             // already indexed file, so we can use it directly
-            source_file
-        } else {
-            // otherwise, we need to find the file in the index
-            if let Some(source_file) = index
-                .source_to_file(self.db)
+            return Ok(Some(source_file));
+        }
+
+        // otherwise, we need to find the file in the index
+        let mut candidates = source_files
+            .keys()
+            .filter(|f| f.matches_suffix(&path))
+            .collect::<Vec<_>>();
+        if candidates.is_empty() {
+            candidates = source_files
                 .keys()
-                .find(|f| f.path.ends_with(file))
-            {
+                .filter(|f| f.matches_fuzzy(&path))
+                .collect();
+        }
+
+        match candidates.as_slice() {
+            [] => {
+                tracing::warn!("file `{file}` not found in debug index");
+                Ok(None)
+            }
+            [source_file] => {
                 tracing::debug!(
                     "found file `{file}` in debug index as `{}`",
                     source_file.path_str()
                 );
-                source_file.clone()
-            } else {
-                tracing::warn!("file `{file}` not found in debug index");
-                return Ok(None);
+                Ok(Some((*source_file).clone()))
             }
-        };
-
-        let query = rudy_dwarf::file::SourceLocation::new(file, line, column);
-        let pos = lookup_position(self.db, self.binary, query);
-        Ok(pos.map(|address| crate::ResolvedAddress { address }))
+            candidates => Err(anyhow::anyhow!(
+                "ambiguous source file `{file}`, could be any of:\n{}",
+                candidates
+                    .iter()
+                    .map(|f| format!("  {}", f.path_str()))
+                    .join("\n")
+            )),
+        }
     }
 
     /// Gets metadata for a specific variable at a memory address without reading its value.
@@ -475,6 +769,167 @@ impl<'db> DebugInfo<'db> {
         crate::data::read_from_memory(self.db, *address, type_def, data_resolver)
     }
 
+    /// Read a value from memory along with the values nested within it
+    ///
+    /// Unlike [`read_pointer`](Self::read_pointer), which reads one level and
+    /// leaves [`Value::Pointer`](crate::Value::Pointer)s to the children, this
+    /// keeps reading until it reaches the limits in `options`.
+    ///
+    /// # Arguments
+    ///
+    /// * `typed_pointer` - The value to read
+    /// * `options` - Limits on how much of the value to read
+    /// * `data_resolver` - Interface for reading memory and register values
+    ///
+    /// # Returns
+    ///
+    /// The interpreted value, where collections and strings cut short by the
+    /// limits end with a truncation marker (e.g. `… 9,990 more`), and values
+    /// nested too deep to read are left as pointers
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use rudy_db::{DebugDb, DebugInfo, ReadOptions, TypedPointer, DataResolver};
+    /// # struct MyResolver;
+    /// # impl DataResolver for MyResolver { }
+    /// # let db = DebugDb::new();
+    /// # let debug_info = DebugInfo::new(&db, "binary").unwrap();
+    /// # let resolver = MyResolver;
+    /// # let pointer: TypedPointer = unimplemented!();
+    /// let options = ReadOptions {
+    ///     max_items: 10,
+    ///     ..ReadOptions::default()
+    /// };
+    /// let value = debug_info.read_value(&pointer, options, &resolver).unwrap();
+    /// ```
+    pub fn read_value(
+        &self,
+        typed_pointer: &TypedPointer,
+        options: crate::ReadOptions,
+        data_resolver: &dyn crate::DataResolver,
+    ) -> Result<crate::Value> {
+        crate::data::read_value(self.db, typed_pointer, &options, data_resolver)
+    }
+
+    /// Read the values that the pointers within a value point to
+    ///
+    /// This finishes reading a value that was put together without reading
+    /// what it points to, such as the result of
+    /// [`evaluate_synthetic_method`](Self::evaluate_synthetic_method), the
+    /// same way [`read_value`](Self::read_value) would: `value` counts as
+    /// the first level, and the limits in `options` apply to all of it.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The value holding the pointers
+    /// * `options` - Limits on how much to read
+    /// * `data_resolver` - Interface for reading memory and register values
+    pub fn read_pointers(
+        &self,
+        value: crate::Value,
+        options: crate::ReadOptions,
+        data_resolver: &dyn crate::DataResolver,
+    ) -> Result<crate::Value> {
+        crate::data::read_pointers(self.db, value, &options, data_resolver)
+    }
+
+    /// Write a value to memory, encoding it according to the pointer's type
+    ///
+    /// Supports integers (checked against the range of the target type),
+    /// floats, bools, chars, C-like enums, tuples, `Option`s and the fields of
+    /// structs. Scalars are given as `Value::Scalar`s holding a Rust literal,
+    /// e.g. `-42`, `0xff`, `'x'` or `Color::Red`.
+    ///
+    /// Values that would need memory to be allocated in the target, such as
+    /// `String`, `Vec` or `Box`, are refused. Nothing is written unless the
+    /// whole value could be encoded.
+    ///
+    /// # Arguments
+    ///
+    /// * `typed_pointer` - The memory to write to
+    /// * `value` - The value to write
+    /// * `data_resolver` - Interface for writing memory
+    pub fn write_value(
+        &self,
+        typed_pointer: &TypedPointer,
+        value: &crate::Value,
+        data_resolver: &dyn crate::DataResolver,
+    ) -> Result<()> {
+        crate::assign::write_value(self.db, typed_pointer, value, data_resolver)
+    }
+
+    /// Parse a Rust-like literal and write it to memory
+    ///
+    /// Accepts the same literals as [`write_value`](Self::write_value), as
+    /// well as `None`, `Some(..)` and tuples such as `(1, true)`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use rudy_db::{DebugDb, DebugInfo, TypedPointer, DataResolver};
+    /// # struct MyResolver;
+    /// # impl DataResolver for MyResolver { }
+    /// # let db = DebugDb::new();
+    /// # let debug_info = DebugInfo::new(&db, "binary").unwrap();
+    /// # let resolver = MyResolver;
+    /// # let pointer: TypedPointer = unimplemented!();
+    /// debug_info.write_literal(&pointer, "Some(42)", &resolver).unwrap();
+    /// ```
+    pub fn write_literal(
+        &self,
+        typed_pointer: &TypedPointer,
+        literal: &str,
+        data_resolver: &dyn crate::DataResolver,
+    ) -> Result<()> {
+        let value = crate::assign::parse_literal(literal)?;
+        self.write_value(typed_pointer, &value, data_resolver)
+    }
+
+    /// Read a window of the children of a collection or string
+    ///
+    /// Supports arrays, slices, `Vec`, `VecDeque`, `LinkedList`, maps, sets
+    /// and strings, including behind references and smart pointers. Only the
+    /// memory needed to find the requested children is read, so frontends can
+    /// page through values with millions of items.
+    ///
+    /// # Arguments
+    ///
+    /// * `typed_pointer` - The collection or string
+    /// * `range` - The positions of the children to read, in iteration order
+    ///   (or bytes, for strings). Clamped to the number of children.
+    /// * `data_resolver` - Interface for reading memory and register values
+    ///
+    /// # Returns
+    ///
+    /// The total number of children, along with pointers to those in the range
+    /// (or the text, for strings)
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use rudy_db::{Children, DebugDb, DebugInfo, TypedPointer, DataResolver};
+    /// # struct MyResolver;
+    /// # impl DataResolver for MyResolver { }
+    /// # let db = DebugDb::new();
+    /// # let debug_info = DebugInfo::new(&db, "binary").unwrap();
+    /// # let resolver = MyResolver;
+    /// # let map: TypedPointer = unimplemented!();
+    /// let page = debug_info.children(&map, 100..150, &resolver).unwrap();
+    /// if let Children::Entries(entries) = page.children {
+    ///     println!("entries 100-150 of {}: {entries:?}", page.total);
+    /// }
+    /// ```
+    pub fn children(
+        &self,
+        typed_pointer: &TypedPointer,
+        range: std::ops::Range<usize>,
+        data_resolver: &dyn crate::DataResolver,
+    ) -> Result<crate::ChildPage> {
+        let TypedPointer { address, type_def } = typed_pointer;
+        crate::data::read_children(self.db, *address, type_def, range, data_resolver)
+    }
+
     /// Access a field of a struct/union/enum value
     ///
     /// # Arguments
@@ -483,6 +938,9 @@ impl<'db> DebugInfo<'db> {
     /// * `base_type` - Type definition of the base value
     /// * `field_name` - Name of the field to access
     ///
+    /// Fields whose location depends on the value, like the payload of a
+    /// `Result`, need to read memory: use [`Self::get_field`] for those.
+    ///
     /// # Returns
     ///
     /// Variable information for the field if found
@@ -524,6 +982,62 @@ impl<'db> DebugInfo<'db> {
                     type_def: field.ty.clone(),
                 })
             }
+            Layout::Union(union_def) => {
+                let field = union_def
+                    .fields
+                    .iter()
+                    .find(|f| f.name == field_name)
+                    .ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "Field '{}' not found in union '{}'",
+                            field_name,
+                            union_def.name
+                        )
+                    })?;
+
+                Ok(TypedPointer {
+                    address: base_address + field.offset as u64,
+                    type_def: field.ty.clone(),
+                })
+            }
+            Layout::Closure(closure_def) => {
+                let capture = closure_def
+                    .captures
+                    .iter()
+                    .find(|c| c.name == field_name)
+                    .ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "Capture '{}' not found in closure '{}'",
+                            field_name,
+                            closure_def.name
+                        )
+                    })?;
+
+                Ok(TypedPointer {
+                    address: base_address + capture.offset as u64,
+                    type_def: capture.ty.clone(),
+                })
+            }
+            Layout::Primitive(PrimitiveLayout::Tuple(tuple_def)) => {
+                // tuple elements are accessed by index, e.g. `pair.0`
+                let (offset, type_def) = field_name
+                    .trim_start_matches("__")
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|index| tuple_def.elements.get(index))
+                    .ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "Field '{}' not found in tuple '{}'",
+                            field_name,
+                            base_type.display_name()
+                        )
+                    })?;
+
+                Ok(TypedPointer {
+                    address: base_address + *offset as u64,
+                    type_def: type_def.clone(),
+                })
+            }
             Layout::Enum(enum_def) => {
                 // For enums, field access might be variant data access
                 // This is complex - for now return an error
@@ -540,6 +1054,57 @@ impl<'db> DebugInfo<'db> {
         }
     }
 
+    /// Access a field of a value, reading memory if the field's location
+    /// depends on the value
+    ///
+    /// This supports everything [`Self::get_struct_field`] does, plus the
+    /// field `0` (or `__0`) of a `Result`, which refers to the payload of
+    /// whichever variant is currently active.
+    ///
+    /// # Arguments
+    ///
+    /// * `typed_pointer` - The value to access the field of
+    /// * `field_name` - Name of the field to access
+    /// * `data_resolver` - Interface for reading memory and register values
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use rudy_db::{DebugDb, DebugInfo, TypedPointer, DataResolver};
+    /// # struct MyResolver;
+    /// # impl DataResolver for MyResolver { }
+    /// # let db = DebugDb::new();
+    /// # let debug_info = DebugInfo::new(&db, "binary").unwrap();
+    /// # let resolver = MyResolver;
+    /// # let result: TypedPointer = unimplemented!();
+    /// if let Ok(payload) = debug_info.get_field(&result, "0", &resolver) {
+    ///     println!("Payload of type {} at {:#x}", payload.type_def.display_name(), payload.address);
+    /// }
+    /// ```
+    pub fn get_field(
+        &self,
+        typed_pointer: &TypedPointer,
+        field_name: &str,
+        data_resolver: &dyn crate::DataResolver,
+    ) -> Result<TypedPointer> {
+        let TypedPointer { address, type_def } = typed_pointer;
+        match type_def.layout.as_ref() {
+            Layout::Std(StdLayout::Result(result_def)) if matches!(field_name, "0" | "__0") => {
+                let (offset, type_def) =
+                    if crate::data::result_is_ok(*address, result_def, data_resolver)? {
+                        (result_def.ok_offset, &result_def.ok_type)
+                    } else {
+                        (result_def.err_offset, &result_def.err_type)
+                    };
+                Ok(TypedPointer {
+                    address: address + offset as u64,
+                    type_def: type_def.clone(),
+                })
+            }
+            _ => self.get_struct_field(*address, type_def, field_name),
+        }
+    }
+
     /// Index into an array/slice/vector by integer index
     ///
     /// # Arguments
@@ -654,6 +1219,56 @@ impl<'db> DebugInfo<'db> {
                         type_def: vec_def.inner_type.clone(),
                     })
                 }
+                StdLayout::BinaryHeap(heap_def) => self.index_array_or_slice(
+                    &TypedPointer {
+                        address: base_address,
+                        type_def: DieTypeDefinition::new(
+                            base_type.location,
+                            Layout::Std(StdLayout::Vec(heap_def.0.clone())),
+                        ),
+                    },
+                    index,
+                    data_resolver,
+                ),
+                StdLayout::VecDeque(vec_deque_def) => {
+                    let element_size = vec_deque_def.inner_type.size().with_context(|| {
+                        format!(
+                            "Failed to get size for VecDeque element type '{}'",
+                            vec_deque_def.inner_type.display_name()
+                        )
+                    })? as u64;
+                    let index = index as usize;
+                    let (elements, len) = crate::data::vec_deque_element_addresses(
+                        base_address,
+                        vec_deque_def,
+                        element_size,
+                        index..index.saturating_add(1),
+                        data_resolver,
+                    )?;
+                    let element_address = elements.first().with_context(|| {
+                        format!("Index {index} out of bounds for VecDeque of length {len}")
+                    })?;
+                    Ok(TypedPointer {
+                        address: *element_address,
+                        type_def: vec_deque_def.inner_type.clone(),
+                    })
+                }
+                StdLayout::LinkedList(list_def) => {
+                    // only walk as far as the element we want
+                    let (elements, length) = crate::data::linked_list_element_addresses(
+                        base_address,
+                        list_def,
+                        (index as usize).saturating_add(1),
+                        data_resolver,
+                    )?;
+                    let element_address = elements.get(index as usize).with_context(|| {
+                        format!("Index {index} out of bounds for LinkedList of length {length}")
+                    })?;
+                    Ok(TypedPointer {
+                        address: *element_address,
+                        type_def: list_def.inner_type.clone(),
+                    })
+                }
                 _ => Err(anyhow::anyhow!(
                     "Cannot index std type '{}' by integer",
                     base_type.display_name()
@@ -668,6 +1283,8 @@ impl<'db> DebugInfo<'db> {
 
     /// Index into a map/dictionary by value key
     ///
+    /// Sets can be indexed too, in which case the matching element is returned.
+    ///
     /// # Arguments
     ///
     /// * `base_address` - Memory address of the base map
@@ -703,31 +1320,28 @@ impl<'db> DebugInfo<'db> {
     ) -> Result<TypedPointer> {
         match base_type.layout.as_ref() {
             Layout::Std(StdLayout::Map(map_def)) => {
-                // For maps, we'll iterate through all key-value pairs
-                // and return the variable info for the value that matches the key.
-                let map_entries =
-                    crate::data::read_map_entries(base_address, map_def, data_resolver)?;
-
-                for (k, v) in map_entries {
-                    let map_key = crate::data::read_from_memory(
-                        self.db,
-                        k.address,
-                        &k.type_def,
-                        data_resolver,
-                    )?;
-
-                    if values_equal(key, &map_key) {
-                        return Ok(TypedPointer {
-                            address: v.address,
-                            type_def: v.type_def.clone(),
-                        });
-                    }
+                match self.find_map_entry(base_address, map_def, key, data_resolver)? {
+                    Some((_, value)) => Ok(value),
+                    None => Err(anyhow::anyhow!(
+                        "Key '{}' not found in map",
+                        format_value_key(key)
+                    )),
+                }
+            }
+            Layout::Std(StdLayout::Set(set_def)) => {
+                // For sets, the "value" at a key is the matching element itself
+                match self.find_map_entry(
+                    base_address + set_def.map_offset as u64,
+                    &set_def.map,
+                    key,
+                    data_resolver,
+                )? {
+                    Some((element, _)) => Ok(element),
+                    None => Err(anyhow::anyhow!(
+                        "Element '{}' not found in set",
+                        format_value_key(key)
+                    )),
                 }
-
-                Err(anyhow::anyhow!(
-                    "Key '{}' not found in map",
-                    format_value_key(key)
-                ))
             }
             _ => Err(anyhow::anyhow!(
                 "Value-based indexing not supported for type '{}'",
@@ -736,6 +1350,191 @@ impl<'db> DebugInfo<'db> {
         }
     }
 
+    /// Evaluate a synthetic method on the value at `pointer`
+    ///
+    /// This supports everything [`crate::evaluate_synthetic_method`] does, plus
+    /// methods that need to read and compare values from the target, such as
+    /// `contains` on a `HashSet` or `BTreeSet`, and `first_key_value`,
+    /// `last_key_value` and `range` on a `BTreeMap`.
+    ///
+    /// The `BTreeMap` methods only read the nodes they need, and refer to the
+    /// entries they find with [`crate::Value::Pointer`]s. `range` takes a
+    /// struct with optional `start` and `end` fields, like a `Range` read
+    /// from memory, and returns at most [`crate::ReadOptions::default`]'s
+    /// `max_items` entries, with the number left out in the map's `truncated`:
+    /// use [`evaluate_synthetic_method_with_options`](Self::evaluate_synthetic_method_with_options)
+    /// to read more or fewer.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use rudy_db::{DebugDb, DebugInfo, DataResolver, TypedPointer, Value};
+    /// # struct MyResolver;
+    /// # impl DataResolver for MyResolver { }
+    /// # let db = DebugDb::new();
+    /// # let debug_info = DebugInfo::new(&db, "binary").unwrap();
+    /// # let resolver = MyResolver;
+    /// # let set: TypedPointer = unimplemented!();
+    /// let needle = Value::Scalar { ty: "u32".to_string(), value: "42".to_string() };
+    /// let found = debug_info.evaluate_synthetic_method(&set, "contains", &[needle], &resolver);
+    /// ```
+    pub fn evaluate_synthetic_method(
+        &self,
+        pointer: &TypedPointer,
+        method: &str,
+        args: &[crate::Value],
+        data_resolver: &dyn crate::DataResolver,
+    ) -> Result<crate::Value> {
+        self.evaluate_synthetic_method_with_options(
+            pointer,
+            method,
+            args,
+            crate::ReadOptions::default(),
+            data_resolver,
+        )
+    }
+
+    /// Evaluate a synthetic method on the value at `pointer`, returning at
+    /// most `options.max_items` entries from methods such as `BTreeMap::range`
+    ///
+    /// See [`evaluate_synthetic_method`](Self::evaluate_synthetic_method).
+    pub fn evaluate_synthetic_method_with_options(
+        &self,
+        pointer: &TypedPointer,
+        method: &str,
+        args: &[crate::Value],
+        options: crate::ReadOptions,
+        data_resolver: &dyn crate::DataResolver,
+    ) -> Result<crate::Value> {
+        let TypedPointer { address, type_def } = pointer;
+        match (type_def.layout.as_ref(), method) {
+            (Layout::Std(StdLayout::Set(set_def)), "contains") => {
+                let [needle] = args else {
+                    anyhow::bail!(
+                        "contains() expects exactly one argument, got {}",
+                        args.len()
+                    );
+                };
+                let found = self
+                    .find_map_entry(
+                        *address + set_def.map_offset as u64,
+                        &set_def.map,
+                        needle,
+                        data_resolver,
+                    )?
+                    .is_some();
+                Ok(crate::Value::Scalar {
+                    ty: "bool".to_string(),
+                    value: found.to_string(),
+                })
+            }
+            (Layout::Std(StdLayout::Map(map_def)), "first_key_value" | "last_key_value")
+                if matches!(map_def.variant, MapVariant::BTreeMap { .. }) =>
+            {
+                if !args.is_empty() {
+                    anyhow::bail!("{method}() takes no arguments");
+                }
+                let ty = format!(
+                    "Option<(&{}, &{})>",
+                    map_def.key_type.display_name(),
+                    map_def.value_type.display_name()
+                );
+                let entry = crate::map_lookup::btree_map_end(
+                    *address,
+                    map_def,
+                    method == "last_key_value",
+                    data_resolver,
+                )?;
+                Ok(match entry {
+                    Some((key, value)) => crate::Value::Enum {
+                        ty,
+                        variant: "Some".to_string(),
+                        discriminant: 1,
+                        payload: Some(Box::new(crate::Value::Tuple {
+                            ty: format!(
+                                "(&{}, &{})",
+                                map_def.key_type.display_name(),
+                                map_def.value_type.display_name()
+                            ),
+                            entries: vec![crate::Value::Pointer(key), crate::Value::Pointer(value)],
+                        })),
+                    },
+                    None => crate::Value::Enum {
+                        ty,
+                        variant: "None".to_string(),
+                        discriminant: 0,
+                        payload: None,
+                    },
+                })
+            }
+            (Layout::Std(StdLayout::Map(map_def)), "range")
+                if matches!(map_def.variant, MapVariant::BTreeMap { .. }) =>
+            {
+                let [crate::Value::Struct { fields, .. }] = args else {
+                    anyhow::bail!("range() expects a single range argument, e.g. `range(1..10)`");
+                };
+                let (entries, count) = crate::map_lookup::btree_map_range(
+                    *address,
+                    map_def,
+                    fields.get("start"),
+                    fields.get("end"),
+                    options.max_items,
+                    data_resolver,
+                )?;
+                let truncated = (entries.len() < count).then(|| count - entries.len());
+                let entries = entries
+                    .into_iter()
+                    .map(|(key, value)| (crate::Value::Pointer(key), crate::Value::Pointer(value)))
+                    .collect();
+                Ok(crate::Value::Map {
+                    ty: format!(
+                        "Range<'_, {}, {}>",
+                        map_def.key_type.display_name(),
+                        map_def.value_type.display_name()
+                    ),
+                    entries,
+                    truncated,
+                })
+            }
+            _ => crate::synthetic_methods::evaluate_synthetic_method(
+                *address,
+                type_def,
+                method,
+                args,
+                data_resolver,
+            ),
+        }
+    }
+
+    /// Find the entry whose key equals `key` in the map at `address`
+    ///
+    /// For supported key types, std `HashMap`s are probed directly by hashing
+    /// the key and `BTreeMap`s are searched from the root, while other maps
+    /// are scanned entry by entry.
+    fn find_map_entry(
+        &self,
+        address: u64,
+        map_def: &MapLayout<Die>,
+        key: &crate::Value,
+        data_resolver: &dyn crate::DataResolver,
+    ) -> Result<Option<(TypedPointer, TypedPointer)>> {
+        let candidates =
+            match crate::map_lookup::lookup_candidates(address, map_def, key, data_resolver)? {
+                Some(candidates) => candidates,
+                None => crate::data::read_map_entries(address, map_def, data_resolver)?,
+            };
+
+        let key_path = crate::data::type_path(self.db, &map_def.key_type);
+        for (k, v) in candidates {
+            let map_key =
+                crate::data::read_from_memory(self.db, k.address, &k.type_def, data_resolver)?;
+            if values_equal(key, &map_key, &key_path) {
+                return Ok(Some((k, v)));
+            }
+        }
+        Ok(None)
+    }
+
     pub fn discover_all_methods(&self) -> Result<BTreeMap<String, Vec<DiscoveredMethod>>> {
         crate::function_discovery::discover_all_methods(self.db, self.binary)
     }
@@ -825,6 +1624,66 @@ impl<'db> DebugInfo<'db> {
         crate::function_discovery::discover_all_functions(self.db, self.binary)
     }
 
+    /// Lists the traits implemented by a type in the binary
+    ///
+    /// Trait impls are found from the symbols of their methods, and from the
+    /// vtables and inlined methods in the debug info. Impls of marker traits
+    /// are only listed if they are used as trait objects.
+    ///
+    /// # Arguments
+    ///
+    /// * `type_name` - The type's path, or a suffix of it, with or without
+    ///   generic arguments (e.g. `String`, `alloc::string::String`)
+    ///
+    /// # Returns
+    ///
+    /// The trait impls for all matching types, each with the methods available in the binary
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use rudy_db::{DebugDb, DebugInfo};
+    /// # let db = DebugDb::new();
+    /// # let debug_info = DebugInfo::new(&db, "binary").unwrap();
+    /// for imp in debug_info.traits_implemented_by("String") {
+    ///     println!("impl {} for {}", imp.trait_name, imp.type_name);
+    /// }
+    /// ```
+    pub fn traits_implemented_by(&self, type_name: &str) -> Vec<crate::TraitImpl> {
+        crate::function_discovery::trait_impl_index(self.db, self.binary)
+            .traits_implemented_by(type_name)
+    }
+
+    /// Lists the types implementing a trait in the binary
+    ///
+    /// Like [`Self::traits_implemented_by`], impls without any methods in
+    /// the binary are only found if they are used as trait objects.
+    ///
+    /// # Arguments
+    ///
+    /// * `trait_name` - The trait's path, or a suffix of it, with or without
+    ///   generic arguments (e.g. `Debug`, `core::fmt::Debug`)
+    ///
+    /// # Returns
+    ///
+    /// The impls of all matching traits, each with the methods available in the binary
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use rudy_db::{DebugDb, DebugInfo};
+    /// # let db = DebugDb::new();
+    /// # let debug_info = DebugInfo::new(&db, "binary").unwrap();
+    /// for imp in debug_info.implementors_of("my_crate::Handler") {
+    ///     let methods: Vec<_> = imp.methods.iter().map(|m| m.name.as_str()).collect();
+    ///     println!("{}: {}", imp.type_name, methods.join(", "));
+    /// }
+    /// ```
+    pub fn implementors_of(&self, trait_name: &str) -> Vec<crate::TraitImpl> {
+        crate::function_discovery::trait_impl_index(self.db, self.binary)
+            .implementors_of(trait_name)
+    }
+
     /// Create a typed value in the target process based on the target type.
     ///
     /// This method uses DWARF type information to determine the correct conversion
@@ -1115,6 +1974,15 @@ fn variable_info(
     })
 }
 
+/// Finds the name of the local crate whose root source file is in the
+/// closest directory above `path`
+fn local_crate_name(path: &Path, local_crates: &BTreeMap<PathBuf, String>) -> Option<String> {
+    path.ancestors()
+        .skip(1)
+        .find_map(|dir| local_crates.get(dir))
+        .cloned()
+}
+
 /// Extract pointer and length from a slice Value
 fn extract_slice_info(slice_value: &crate::Value) -> Result<(u64, u64)> {
     match slice_value {
@@ -1161,7 +2029,10 @@ fn extract_numeric_value(value: &crate::Value) -> Result<u64> {
 }
 
 /// Compare two Values for equality (approximate, for HashMap key matching)
-fn values_equal(a: &crate::Value, b: &crate::Value) -> bool {
+///
+/// `enum_path` is the full path of the type of the values, against which
+/// variants named with a path are checked.
+fn values_equal(a: &crate::Value, b: &crate::Value, enum_path: &str) -> bool {
     match (a, b) {
         (crate::Value::Scalar { value: a_val, .. }, crate::Value::Scalar { value: b_val, .. }) => {
             // For strings, compare the actual string content (strip quotes if present)
@@ -1169,6 +2040,44 @@ fn values_equal(a: &crate::Value, b: &crate::Value) -> bool {
             let b_clean = b_val.trim_matches('"');
             a_clean == b_clean
         }
+        // Unit variants can be given by name, optionally qualified (e.g. `Color::Red`)
+        (
+            crate::Value::Scalar { value, .. },
+            crate::Value::Enum {
+                variant,
+                payload: None,
+                ..
+            },
+        )
+        | (
+            crate::Value::Enum {
+                variant,
+                payload: None,
+                ..
+            },
+            crate::Value::Scalar { value, .. },
+        ) => crate::data::names_variant(value.trim_matches('"'), enum_path, variant),
+        (
+            crate::Value::Enum {
+                variant: a_variant,
+                payload: a_payload,
+                ..
+            },
+            crate::Value::Enum {
+                variant: b_variant,
+                payload: b_payload,
+                ..
+            },
+        ) => {
+            a_variant == b_variant
+                && match (a_payload, b_payload) {
+                    (None, None) => true,
+                    // the payload's type isn't known here, so only unqualified
+                    // variants match within it
+                    (Some(a), Some(b)) => values_equal(a, b, ""),
+                    _ => false,
+                }
+        }
         // For more complex types, could add more sophisticated comparison
         _ => false,
     }
@@ -1181,6 +2090,12 @@ fn format_value_key(value: &crate::Value) -> String {
             // Strip quotes for cleaner display
             value.trim_matches('"').to_string()
         }
+        crate::Value::Enum {
+            ty,
+            variant,
+            payload: None,
+            ..
+        } => format!("{ty}::{variant}"),
         _ => format!("{value:?}"),
     }
 }
@@ -1,4 +1,4 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use anyhow::Result;
 use rudy_dwarf::{
@@ -47,6 +47,158 @@ pub enum SymbolAnalysisResult {
     },
 }
 
+/// Index of the trait implementations in a binary
+///
+/// Built from symbol names like `<alloc::string::String as core::fmt::Debug>::fmt`,
+/// and from the vtables and inlined trait methods in the debug info, which
+/// also find impls without any method symbols (e.g. marker traits used as
+/// trait objects).
+#[derive(Debug, Default, Clone, PartialEq, Eq, salsa::Update)]
+pub struct TraitImplIndex {
+    /// (type name, trait name) -> method name -> (symbol name, address)
+    impls: BTreeMap<(String, String), BTreeMap<String, (String, u64)>>,
+    /// trait name -> implementing types
+    by_trait: BTreeMap<String, BTreeSet<String>>,
+    /// type name -> implemented traits
+    by_type: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl TraitImplIndex {
+    /// Finds the impls of all traits implemented by types matching `type_name`
+    pub fn traits_implemented_by(&self, type_name: &str) -> Vec<crate::TraitImpl> {
+        self.by_type
+            .iter()
+            .filter(|(ty, _)| path_matches(ty, type_name))
+            .flat_map(|(ty, traits)| traits.iter().map(move |tr| (ty, tr)))
+            .map(|(ty, tr)| self.trait_impl(ty, tr))
+            .collect()
+    }
+
+    /// Finds the impls of all traits matching `trait_name`
+    pub fn implementors_of(&self, trait_name: &str) -> Vec<crate::TraitImpl> {
+        self.by_trait
+            .iter()
+            .filter(|(tr, _)| path_matches(tr, trait_name))
+            .flat_map(|(tr, types)| types.iter().map(move |ty| (ty, tr)))
+            .map(|(ty, tr)| self.trait_impl(ty, tr))
+            .collect()
+    }
+
+    /// Records that `type_name` implements `trait_name`, returning the key
+    /// the impl is stored under
+    ///
+    /// The debug info names the concrete types an impl was used with (e.g.
+    /// `Vec<u8, alloc::alloc::Global>`), while symbols name the impl's own
+    /// generic parameters (e.g. `Vec<T,A>`), so impls are keyed by the type
+    /// without its generic arguments, and commas in the trait's generic
+    /// arguments are written the same way whichever source they came from.
+    fn insert(&mut self, type_name: &str, trait_name: &str) -> (String, String) {
+        let type_name = strip_generics(type_name);
+        let trait_name = trait_name.replace(", ", ",");
+        self.by_trait
+            .entry(trait_name.clone())
+            .or_default()
+            .insert(type_name.clone());
+        self.by_type
+            .entry(type_name.clone())
+            .or_default()
+            .insert(trait_name.clone());
+        (type_name, trait_name)
+    }
+
+    fn trait_impl(&self, type_name: &str, trait_name: &str) -> crate::TraitImpl {
+        let methods = self
+            .impls
+            .get(&(type_name.to_string(), trait_name.to_string()))
+            .into_iter()
+            .flatten()
+            .map(|(name, (full_name, address))| crate::TraitImplMethod {
+                name: name.clone(),
+                full_name: full_name.clone(),
+                address: *address,
+            })
+            .collect();
+
+        crate::TraitImpl {
+            type_name: type_name.to_string(),
+            trait_name: trait_name.to_string(),
+            methods,
+        }
+    }
+}
+
+/// Checks if a type or trait path matches a (possibly partial) query
+///
+/// The query matches if it is the full path, or a suffix of the path
+/// with or without generic arguments. e.g. `Vec`, `vec::Vec` and
+/// `alloc::vec::Vec<u8>` all match `alloc::vec::Vec`.
+fn path_matches(path: &str, query: &str) -> bool {
+    let query = query.trim();
+    [
+        (path.to_string(), query.to_string()),
+        (strip_generics(path), strip_generics(query)),
+    ]
+    .into_iter()
+    .any(|(p, q)| {
+        p == q
+            || p.strip_suffix(&q)
+                .is_some_and(|prefix| prefix.ends_with("::"))
+    })
+}
+
+/// Removes the generic arguments from a type, e.g.
+/// `&alloc::vec::Vec<T,A>` becomes `&alloc::vec::Vec`
+fn strip_generics(name: &str) -> String {
+    let mut stripped = String::with_capacity(name.len());
+    let mut depth = 0usize;
+    let mut previous = None;
+    for c in name.chars() {
+        match c {
+            '<' => depth += 1,
+            // the `>` of a function type's `->` doesn't close anything
+            '>' if previous != Some('-') => depth = depth.saturating_sub(1),
+            _ if depth == 0 => stripped.push(c),
+            _ => {}
+        }
+        previous = Some(c);
+    }
+    stripped
+}
+
+/// Build the trait impl index from the function symbols in the binary,
+/// and the debug info of the indexed debug files
+#[salsa::tracked(returns(ref))]
+pub fn trait_impl_index(db: &dyn Db, binary: Binary) -> TraitImplIndex {
+    let index = crate::index::debug_index(db, binary);
+    let symbol_index = index.symbol_index(db);
+
+    let mut trait_impls = TraitImplIndex::default();
+
+    for debug_file in index.indexed_debug_files(db) {
+        for (type_name, trait_name) in rudy_dwarf::trait_impls::trait_impls(db, debug_file) {
+            trait_impls.insert(type_name, trait_name);
+        }
+    }
+
+    for symbol in symbol_index.functions.values().flat_map(|map| map.values()) {
+        let Some((type_name, trait_name)) = symbol.name.trait_impl() else {
+            continue;
+        };
+
+        let key = trait_impls.insert(&type_name, &trait_name);
+        // generic impls may have many copies of the same method,
+        // so we just keep the first one
+        trait_impls
+            .impls
+            .entry(key)
+            .or_default()
+            .entry(symbol.name.lookup_name.clone())
+            .or_insert_with(|| (symbol.name.to_string(), symbol.address));
+    }
+
+    trait_impls
+}
+
 /// Discover all methods in the binary and organize them by symbol name
 ///
 /// This returns debugging information about each symbol, including whether it was successfully analyzed,
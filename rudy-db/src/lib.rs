@@ -26,14 +26,72 @@
 //!     Ok(())
 //! }
 //! ```
+//!
+//! ## Serialization
+//!
+//! With the `serde` feature enabled, [`Value`], [`TypedPointer`],
+//! [`Variable`], [`VariableInfo`], [`ResolvedFunction`],
+//! [`DiscoveredMethod`], [`DiscoveredFunction`], [`ModuleInfo`] and
+//! [`ModuleItem`] implement
+//! `serde::Serialize`. The JSON they produce is stable: fields may be added,
+//! but existing ones won't be renamed or removed.
+//!
+//! Addresses are hex strings (e.g. `"0x7ffd5fbff5c0"`), and types are
+//! written as their display names (e.g. `"Vec<u8>"`). Values are objects
+//! with a `kind` and, except for `truncated` and `suspect`, a `type`:
+//!
+//! | `kind` | Other fields |
+//! |---|---|
+//! | `scalar` | `value`: the value as a string, e.g. `"42"` or `"\"hello\""` |
+//! | `array`, `set` | `items`: a list of values |
+//! | `tuple` | `entries`: a list of values |
+//! | `struct` | `fields`: an object of field names to values |
+//! | `map` | `entries`: a list of `{"key": value, "value": value}` objects, `truncated`: how many entries were not read, or `null` |
+//! | `enum` | `variant`: the variant name, `discriminant`: the integer as a string, e.g. `"1"`, `payload`: a `tuple` or `struct` value for variants with fields, otherwise `null` |
+//! | `truncated` | `remaining`: how many items were not read |
+//! | `invalid` | `reason`: why the memory can't hold a valid value of the type |
+//! | `suspect` | `reason`: why the value looks wrong, `value`: the value as read |
+//! | `pointer` | `address`: where the (unread) value is |
+//!
+//! A `truncated` value can only be the last item of an array, set or tuple.
+//!
+//! ```json
+//! {
+//!   "kind": "enum",
+//!   "type": "Option<Vec<u8>>",
+//!   "variant": "Some",
+//!   "discriminant": "1",
+//!   "payload": {
+//!     "kind": "tuple",
+//!     "type": "Option<Vec<u8>>::Some",
+//!     "entries": [{
+//!       "kind": "array",
+//!       "type": "Vec<u8>",
+//!       "items": [
+//!         { "kind": "scalar", "type": "u8", "value": "1" },
+//!         { "kind": "truncated", "remaining": 99 }
+//!       ]
+//!     }]
+//!   }
+//! }
+//! ```
+//!
+//! The other types serialize as objects with the same field names as their
+//! Rust definitions, except that type definitions become a `type` (or
+//! `return_type`) name and parameters are `{"name", "type"}` objects.
 
+mod assign;
+mod cache;
 mod data;
 mod database;
 mod debug_info;
 mod function_discovery;
 mod index;
+mod map_lookup;
 mod outputs;
 mod query;
+#[cfg(feature = "serde")]
+mod serialize;
 mod synthetic_methods;
 #[cfg(test)]
 pub mod test_utils;
@@ -43,12 +101,15 @@ pub use rudy_dwarf;
 pub use rudy_types;
 
 // common type re-exports
-pub use data::DataResolver;
+pub use cache::{CachingResolver, DEFAULT_PAGE_SIZE, MemoryCache};
+pub use data::{DataResolver, ReadOptions};
 pub use database::DebugDatabaseImpl as DebugDb;
 pub use debug_info::DebugInfo;
 pub use outputs::{
-    DiscoveredFunction, DiscoveredMethod, FunctionParameter, ResolvedAddress, ResolvedLocation,
-    Type, TypedPointer, Value, Variable, VariableInfo,
+    ChildPage, Children, CrateSourceFiles, DiscoveredFunction, DiscoveredMethod, FunctionParameter,
+    FunctionSpan, ModuleInfo, ModuleItem, ResolvedAddress, ResolvedBreakpoint, ResolvedFunction,
+    ResolvedLocation, TraitImpl, TraitImplMethod, Type, TypedPointer, Value, Variable,
+    VariableInfo, truncation_marker,
 };
-pub use rudy_dwarf::function::SelfType;
+pub use rudy_dwarf::{file::RustSrc, function::SelfType};
 pub use synthetic_methods::{SyntheticMethod, evaluate_synthetic_method, get_synthetic_methods};
@@ -0,0 +1,645 @@
+//! Finding map entries by key without reading the whole map.
+//!
+//! std's `HashMap` hashes keys with SipHash-1-3, seeded by the two keys stored
+//! in its `RandomState`. Reading those from the target lets us hash a lookup
+//! key exactly the way the target would and probe the hashbrown table
+//! directly, instead of comparing against every entry. An `IndexMap` using
+//! `RandomState` is probed the same way, through its table of indices into
+//! the entries.
+//!
+//! `BTreeMap` keeps the keys of each node sorted, so for keys we know how to
+//! order we can binary search our way down from the root, only reading the
+//! nodes on the path to the key (or to the ends of a range).
+
+use anyhow::Result;
+use rudy_dwarf::Die;
+use rudy_types::{
+    BTreeNodeLayout, BTreeRootLayout, Layout, MapLayout, MapVariant, PrimitiveLayout,
+    RandomStateLayout, StdLayout, StringLayout,
+};
+
+use crate::{
+    Value,
+    data::{RawTable, read_raw_table},
+    outputs::TypedPointer,
+};
+
+/// Returns the entries that may hold `key`, found without reading the whole map.
+///
+/// Only a handful of entries are returned, and the caller still needs to
+/// compare their keys. Returns `None` when the map's kind, hasher or key type
+/// is not supported, in which case the caller should scan every entry.
+pub(crate) fn lookup_candidates(
+    address: u64,
+    def: &MapLayout<Die>,
+    key: &Value,
+    data_resolver: &dyn crate::DataResolver,
+) -> Result<Option<Vec<(TypedPointer, TypedPointer)>>> {
+    match &def.variant {
+        MapVariant::HashMap { .. } => hash_map_candidates(address, def, key, data_resolver),
+        MapVariant::BTreeMap { .. } => btree_map_candidates(address, def, key, data_resolver),
+        MapVariant::IndexMap { .. } => index_map_candidates(address, def, key, data_resolver),
+    }
+}
+
+/// A lookup key, or a key read from the target, in a form that hashes and
+/// orders the way the target's key type does
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Key {
+    Int(i128),
+    UnsignedInt(u128),
+    Bool(bool),
+    Char(char),
+    Str(Vec<u8>),
+}
+
+/// Interprets `key` as a value of the key type `layout`, or returns `None`
+/// if we don't know how keys of that type hash and compare.
+///
+/// A key that doesn't parse as the map's key type can't be in the map, so
+/// an approximate parse only ever means a failed lookup.
+fn parse_key(layout: &Layout<Die>, key: &Value) -> Option<Key> {
+    let Value::Scalar { value, .. } = key else {
+        return None;
+    };
+    let value = value.trim_matches('"');
+    Some(match layout {
+        Layout::Primitive(PrimitiveLayout::Int(_)) => Key::Int(value.parse().ok()?),
+        Layout::Primitive(PrimitiveLayout::UnsignedInt(_)) => Key::UnsignedInt(value.parse().ok()?),
+        Layout::Primitive(PrimitiveLayout::Bool(())) => Key::Bool(value.parse().ok()?),
+        Layout::Primitive(PrimitiveLayout::Char(())) => {
+            let mut chars = value.trim_matches('\'').chars();
+            let (Some(c), None) = (chars.next(), chars.next()) else {
+                return None;
+            };
+            Key::Char(c)
+        }
+        Layout::Primitive(PrimitiveLayout::StrSlice(_)) | Layout::Std(StdLayout::String(_)) => {
+            Key::Str(value.as_bytes().to_vec())
+        }
+        _ => return None,
+    })
+}
+
+/// Reads a key of a type [`parse_key`] supports from the target
+fn read_key(
+    layout: &Layout<Die>,
+    address: u64,
+    data_resolver: &dyn crate::DataResolver,
+) -> Result<Key> {
+    let read_str = |data_ptr_offset: usize, length_offset: usize| -> Result<Key> {
+        let data = data_resolver.read_address(address + data_ptr_offset as u64)?;
+        let length = data_resolver.read_memory(address + length_offset as u64, 8)?;
+        let length = usize::from_le_bytes(length.try_into().unwrap());
+        if length == 0 {
+            return Ok(Key::Str(vec![]));
+        }
+        Ok(Key::Str(data_resolver.read_memory(data, length)?))
+    };
+
+    Ok(match layout {
+        Layout::Primitive(PrimitiveLayout::Int(int)) => {
+            let bytes = data_resolver.read_memory(address, int.size)?;
+            // sign extend to 128 bits
+            let negative = bytes.last().is_some_and(|b| b & 0x80 != 0);
+            let mut extended = [if negative { 0xff } else { 0 }; 16];
+            extended[..int.size].copy_from_slice(&bytes);
+            Key::Int(i128::from_le_bytes(extended))
+        }
+        Layout::Primitive(PrimitiveLayout::UnsignedInt(int)) => {
+            let bytes = data_resolver.read_memory(address, int.size)?;
+            let mut extended = [0; 16];
+            extended[..int.size].copy_from_slice(&bytes);
+            Key::UnsignedInt(u128::from_le_bytes(extended))
+        }
+        Layout::Primitive(PrimitiveLayout::Bool(())) => {
+            Key::Bool(data_resolver.read_memory(address, 1)?[0] != 0)
+        }
+        Layout::Primitive(PrimitiveLayout::Char(())) => {
+            let bytes = data_resolver.read_memory(address, 4)?;
+            let c = char::from_u32(u32::from_le_bytes(bytes.try_into().unwrap()))
+                .ok_or_else(|| anyhow::anyhow!("Invalid char value at address {address:#x}"))?;
+            Key::Char(c)
+        }
+        Layout::Primitive(PrimitiveLayout::StrSlice(s)) => {
+            read_str(s.data_ptr_offset, s.length_offset)?
+        }
+        Layout::Std(StdLayout::String(StringLayout(v))) => {
+            read_str(v.data_ptr_offset, v.length_offset)?
+        }
+        _ => anyhow::bail!("Keys of type {} can't be compared", layout.display_name()),
+    })
+}
+
+/// Control byte of an empty hashbrown bucket
+const EMPTY: u8 = 0xff;
+
+/// Returns the entries that may hold `key`, found by probing the hash table
+fn hash_map_candidates(
+    address: u64,
+    def: &MapLayout<Die>,
+    key: &Value,
+    data_resolver: &dyn crate::DataResolver,
+) -> Result<Option<Vec<(TypedPointer, TypedPointer)>>> {
+    let MapVariant::HashMap {
+        bucket_mask_offset,
+        ctrl_offset,
+        items_offset,
+        pair_size,
+        key_offset,
+        value_offset,
+        group_width,
+        random_state: Some(random_state),
+    } = &def.variant
+    else {
+        return Ok(None);
+    };
+    let Some(key_bytes) = hashed_key_bytes(def.key_type.layout.as_ref(), key) else {
+        return Ok(None);
+    };
+
+    let table = read_raw_table(
+        address,
+        *bucket_mask_offset,
+        *ctrl_offset,
+        *items_offset,
+        *pair_size,
+        *group_width,
+        data_resolver,
+    )?;
+    if table.items == 0 {
+        return Ok(Some(vec![]));
+    }
+
+    let hash = random_state_hash(address, random_state, &key_bytes, data_resolver)?;
+    let candidates = probe(hash, &table, *group_width, data_resolver)?
+        .into_iter()
+        .map(|index| {
+            let bucket = table.bucket(index, *pair_size);
+            (
+                TypedPointer {
+                    address: bucket + *key_offset as u64,
+                    type_def: def.key_type.clone(),
+                },
+                TypedPointer {
+                    address: bucket + *value_offset as u64,
+                    type_def: def.value_type.clone(),
+                },
+            )
+        })
+        .collect();
+
+    Ok(Some(candidates))
+}
+
+/// Returns the entries that may hold `key`, found by probing the table of
+/// indices into the entries
+fn index_map_candidates(
+    address: u64,
+    def: &MapLayout<Die>,
+    key: &Value,
+    data_resolver: &dyn crate::DataResolver,
+) -> Result<Option<Vec<(TypedPointer, TypedPointer)>>> {
+    let MapVariant::IndexMap {
+        entries_ptr_offset,
+        entries_len_offset,
+        bucket_size,
+        key_offset,
+        value_offset,
+        indices_bucket_mask_offset,
+        indices_ctrl_offset,
+        indices_items_offset,
+        group_width,
+        random_state: Some(random_state),
+        ..
+    } = &def.variant
+    else {
+        return Ok(None);
+    };
+    let Some(key_bytes) = hashed_key_bytes(def.key_type.layout.as_ref(), key) else {
+        return Ok(None);
+    };
+
+    let length = data_resolver.read_memory(address + *entries_len_offset as u64, 8)?;
+    let length = usize::from_le_bytes(length.try_into().unwrap());
+    if length == 0 {
+        return Ok(Some(vec![]));
+    }
+
+    // each slot of the table holds a `usize` index into the entries
+    let table = read_raw_table(
+        address,
+        *indices_bucket_mask_offset,
+        *indices_ctrl_offset,
+        *indices_items_offset,
+        8,
+        *group_width,
+        data_resolver,
+    )?;
+    let hash = random_state_hash(address, random_state, &key_bytes, data_resolver)?;
+    let entries_ptr = data_resolver.read_address(address + *entries_ptr_offset as u64)?;
+
+    let mut candidates = Vec::new();
+    for slot in probe(hash, &table, *group_width, data_resolver)? {
+        let index = data_resolver.read_memory(table.bucket(slot, 8), 8)?;
+        let index = usize::from_le_bytes(index.try_into().unwrap());
+        if index >= length {
+            continue;
+        }
+        let bucket = entries_ptr + (index * bucket_size) as u64;
+        candidates.push((
+            TypedPointer {
+                address: bucket + *key_offset as u64,
+                type_def: def.key_type.clone(),
+            },
+            TypedPointer {
+                address: bucket + *value_offset as u64,
+                type_def: def.value_type.clone(),
+            },
+        ));
+    }
+
+    Ok(Some(candidates))
+}
+
+/// Hashes `key_bytes` with the SipHash keys of the `RandomState` in the map at `address`
+fn random_state_hash(
+    address: u64,
+    random_state: &RandomStateLayout,
+    key_bytes: &[u8],
+    data_resolver: &dyn crate::DataResolver,
+) -> Result<u64> {
+    let k0 = data_resolver.read_memory(address + random_state.k0_offset as u64, 8)?;
+    let k1 = data_resolver.read_memory(address + random_state.k1_offset as u64, 8)?;
+    Ok(sip_hash_1_3(
+        u64::from_le_bytes(k0.try_into().unwrap()),
+        u64::from_le_bytes(k1.try_into().unwrap()),
+        key_bytes,
+    ))
+}
+
+/// Returns the indices of the buckets of a hashbrown table whose control
+/// byte matches `hash`
+fn probe(
+    hash: u64,
+    table: &RawTable,
+    group_width: usize,
+    data_resolver: &dyn crate::DataResolver,
+) -> Result<Vec<usize>> {
+    let bucket_mask = table.buckets - 1;
+    // hashbrown uses the top 7 bits of the hash as the control byte of a
+    // full bucket, and the low bits to pick where to start probing
+    let h2 = (hash >> 57) as u8 & 0x7f;
+    let mut pos = hash as usize & bucket_mask;
+    let mut stride = 0;
+
+    let mut matches = Vec::new();
+    // Triangular probing visits every group within this many steps, so only
+    // a corrupted table would need more
+    for _ in 0..=(bucket_mask / group_width + 1) {
+        // The control bytes are followed by a copy of the first group, so
+        // a whole group can be read from any position
+        let group = data_resolver.read_memory(table.ctrl_ptr + pos as u64, group_width)?;
+        for (bit, &ctrl) in group.iter().enumerate() {
+            if ctrl == h2 {
+                matches.push((pos + bit) & bucket_mask);
+            }
+        }
+        if group.contains(&EMPTY) {
+            break;
+        }
+        stride += group_width;
+        pos = (pos + stride) & bucket_mask;
+    }
+
+    Ok(matches)
+}
+
+/// The bytes std feeds to the hasher when hashing `key` as a value of type
+/// `layout`, or `None` if we don't know how the type is hashed
+fn hashed_key_bytes(layout: &Layout<Die>, key: &Value) -> Option<Vec<u8>> {
+    Some(match parse_key(layout, key)? {
+        // integers are hashed as their bytes in target (little endian) order
+        Key::Int(value) => value.to_le_bytes()[..layout.size()?].to_vec(),
+        Key::UnsignedInt(value) => value.to_le_bytes()[..layout.size()?].to_vec(),
+        Key::Bool(value) => vec![value as u8],
+        Key::Char(c) => (c as u32).to_le_bytes().to_vec(),
+        // strings are hashed as their bytes followed by a 0xff terminator,
+        // so that e.g. ("ab", "c") and ("a", "bc") hash differently
+        Key::Str(mut bytes) => {
+            bytes.push(0xff);
+            bytes
+        }
+    })
+}
+
+/// Returns the entry holding `key`, if any, found by searching down the tree
+fn btree_map_candidates(
+    address: u64,
+    def: &MapLayout<Die>,
+    key: &Value,
+    data_resolver: &dyn crate::DataResolver,
+) -> Result<Option<Vec<(TypedPointer, TypedPointer)>>> {
+    let Some(tree) = BTree::new(def, data_resolver) else {
+        return Ok(None);
+    };
+    let Some(key) = parse_key(def.key_type.layout.as_ref(), key) else {
+        return Ok(None);
+    };
+    let Some((mut node, height)) = tree.root(address)? else {
+        return Ok(Some(vec![]));
+    };
+
+    for height in (0..=height).rev() {
+        let (index, found) = tree.search(node, &key)?;
+        if found {
+            return Ok(Some(vec![tree.entry(node, index)]));
+        }
+        if height > 0 {
+            node = tree.edge(node, index)?;
+        }
+    }
+    Ok(Some(vec![]))
+}
+
+/// Returns the first (or last) entry of a BTreeMap, reading only the nodes
+/// along the edge of the tree
+pub(crate) fn btree_map_end(
+    address: u64,
+    def: &MapLayout<Die>,
+    last: bool,
+    data_resolver: &dyn crate::DataResolver,
+) -> Result<Option<(TypedPointer, TypedPointer)>> {
+    let tree = BTree::new(def, data_resolver).ok_or_else(|| {
+        anyhow::anyhow!("Cannot determine the entry size of {}", def.display_name())
+    })?;
+    let Some((mut node, height)) = tree.root(address)? else {
+        return Ok(None);
+    };
+
+    for _ in 0..height {
+        let edge = if last { tree.len(node)? } else { 0 };
+        node = tree.edge(node, edge)?;
+    }
+    let len = tree.len(node)?;
+    if len == 0 {
+        return Ok(None);
+    }
+    Ok(Some(tree.entry(node, if last { len - 1 } else { 0 })))
+}
+
+/// Returns the entries of a BTreeMap whose keys lie in `start..end`, in key
+/// order, along with how many there are.
+///
+/// Missing bounds are unbounded. Only the first `limit` entries are returned;
+/// the rest are counted without reading their keys.
+pub(crate) fn btree_map_range(
+    address: u64,
+    def: &MapLayout<Die>,
+    start: Option<&Value>,
+    end: Option<&Value>,
+    limit: usize,
+    data_resolver: &dyn crate::DataResolver,
+) -> Result<(Vec<(TypedPointer, TypedPointer)>, usize)> {
+    let tree = BTree::new(def, data_resolver).ok_or_else(|| {
+        anyhow::anyhow!("Cannot determine the entry size of {}", def.display_name())
+    })?;
+    let layout = def.key_type.layout.as_ref();
+    let bound = |value: Option<&Value>| {
+        value
+            .map(|value| {
+                parse_key(layout, value).ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Cannot use {value:?} as a range bound for keys of type {}",
+                        def.key_type.display_name()
+                    )
+                })
+            })
+            .transpose()
+    };
+    let (start, end) = (bound(start)?, bound(end)?);
+
+    let mut entries = Vec::new();
+    let mut count = 0;
+    if let (Some(start), Some(end)) = (&start, &end)
+        && start >= end
+    {
+        return Ok((entries, count));
+    }
+    if let Some((root, height)) = tree.root(address)? {
+        tree.collect_range(
+            root,
+            height,
+            start.as_ref(),
+            end.as_ref(),
+            limit,
+            &mut entries,
+            &mut count,
+        )?;
+    }
+    Ok((entries, count))
+}
+
+/// The nodes of a BTreeMap, along with what we need to read their entries
+struct BTree<'a> {
+    def: &'a MapLayout<Die>,
+    root_offset: usize,
+    root_layout: &'a BTreeRootLayout,
+    node_layout: &'a BTreeNodeLayout,
+    key_size: usize,
+    value_size: usize,
+    data_resolver: &'a dyn crate::DataResolver,
+}
+
+impl<'a> BTree<'a> {
+    /// Returns `None` if `def` isn't a BTreeMap, or its key or value size is unknown
+    fn new(def: &'a MapLayout<Die>, data_resolver: &'a dyn crate::DataResolver) -> Option<Self> {
+        let MapVariant::BTreeMap {
+            root_offset,
+            root_layout,
+            node_layout,
+            ..
+        } = &def.variant
+        else {
+            return None;
+        };
+        Some(Self {
+            def,
+            root_offset: *root_offset,
+            root_layout,
+            node_layout,
+            key_size: def.key_type.size()?,
+            value_size: def.value_type.size()?,
+            data_resolver,
+        })
+    }
+
+    /// The root node and height of the tree of the map at `address`
+    fn root(&self, address: u64) -> Result<Option<(u64, usize)>> {
+        crate::data::btree_root(
+            address + self.root_offset as u64,
+            self.root_layout,
+            self.data_resolver,
+        )
+    }
+
+    /// The number of entries in `node`
+    fn len(&self, node: u64) -> Result<usize> {
+        let len = self
+            .data_resolver
+            .read_memory(node + self.node_layout.len_offset as u64, 2)?;
+        Ok(u16::from_le_bytes(len.try_into().unwrap()) as usize)
+    }
+
+    /// The child node to the left of entry `index` of an internal `node`
+    fn edge(&self, node: u64, index: usize) -> Result<u64> {
+        self.data_resolver.read_address(
+            node + (self.node_layout.edges_offset + index * self.node_layout.edge_size) as u64,
+        )
+    }
+
+    /// The number of entries in the subtree at `node`, which only takes
+    /// reading the lengths of its nodes
+    fn subtree_len(&self, node: u64, height: usize) -> Result<usize> {
+        let len = self.len(node)?;
+        let mut total = len;
+        if height > 0 {
+            for index in 0..=len {
+                total += self.subtree_len(self.edge(node, index)?, height - 1)?;
+            }
+        }
+        Ok(total)
+    }
+
+    fn entry(&self, node: u64, index: usize) -> (TypedPointer, TypedPointer) {
+        (
+            TypedPointer {
+                address: node + (self.node_layout.keys_offset + index * self.key_size) as u64,
+                type_def: self.def.key_type.clone(),
+            },
+            TypedPointer {
+                address: node + (self.node_layout.vals_offset + index * self.value_size) as u64,
+                type_def: self.def.value_type.clone(),
+            },
+        )
+    }
+
+    /// Binary searches the keys of `node` for the first one that is not less
+    /// than `key`, returning its index and whether it is equal to `key`
+    fn search(&self, node: u64, key: &Key) -> Result<(usize, bool)> {
+        let (mut low, mut high) = (0, self.len(node)?);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let (mid_key, _) = self.entry(node, mid);
+            match read_key(
+                self.def.key_type.layout.as_ref(),
+                mid_key.address,
+                self.data_resolver,
+            )?
+            .cmp(key)
+            {
+                std::cmp::Ordering::Less => low = mid + 1,
+                std::cmp::Ordering::Equal => return Ok((mid, true)),
+                std::cmp::Ordering::Greater => high = mid,
+            }
+        }
+        Ok((low, false))
+    }
+
+    /// Collects the first `limit` entries of the subtree at `node` with keys
+    /// in `start..end`, and counts all of them
+    ///
+    /// Subtrees that lie entirely within the range are walked without
+    /// comparing any keys, and once we have `limit` entries they are only
+    /// counted from the lengths of their nodes.
+    #[allow(clippy::too_many_arguments)]
+    fn collect_range(
+        &self,
+        node: u64,
+        height: usize,
+        start: Option<&Key>,
+        end: Option<&Key>,
+        limit: usize,
+        entries: &mut Vec<(TypedPointer, TypedPointer)>,
+        count: &mut usize,
+    ) -> Result<()> {
+        let first = match start {
+            Some(start) => self.search(node, start)?.0,
+            None => 0,
+        };
+        let last = match end {
+            Some(end) => self.search(node, end)?.0,
+            None => self.len(node)?,
+        };
+
+        for index in first..=last {
+            if height > 0 {
+                let edge = self.edge(node, index)?;
+                // only the edges at either end can hold keys outside the range
+                let start = start.filter(|_| index == first);
+                let end = end.filter(|_| index == last);
+                if entries.len() == limit && start.is_none() && end.is_none() {
+                    *count += self.subtree_len(edge, height - 1)?;
+                } else {
+                    self.collect_range(edge, height - 1, start, end, limit, entries, count)?;
+                }
+            }
+            if index < last {
+                *count += 1;
+                if entries.len() < limit {
+                    entries.push(self.entry(node, index));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// SipHash-1-3, the hash function behind std's `DefaultHasher`
+fn sip_hash_1_3(k0: u64, k1: u64, message: &[u8]) -> u64 {
+    let mut v = [
+        k0 ^ 0x736f_6d65_7073_6575,
+        k1 ^ 0x646f_7261_6e64_6f6d,
+        k0 ^ 0x6c79_6765_6e65_7261,
+        k1 ^ 0x7465_6462_7974_6573,
+    ];
+
+    let mut chunks = message.chunks_exact(8);
+    for chunk in &mut chunks {
+        let m = u64::from_le_bytes(chunk.try_into().unwrap());
+        v[3] ^= m;
+        sip_round(&mut v);
+        v[0] ^= m;
+    }
+
+    // the final block holds the remaining bytes and the message length
+    let mut last = [0; 8];
+    last[..chunks.remainder().len()].copy_from_slice(chunks.remainder());
+    let m = u64::from_le_bytes(last) | ((message.len() as u64 & 0xff) << 56);
+    v[3] ^= m;
+    sip_round(&mut v);
+    v[0] ^= m;
+
+    v[2] ^= 0xff;
+    for _ in 0..3 {
+        sip_round(&mut v);
+    }
+    v[0] ^ v[1] ^ v[2] ^ v[3]
+}
+
+fn sip_round(v: &mut [u64; 4]) {
+    v[0] = v[0].wrapping_add(v[1]);
+    v[1] = v[1].rotate_left(13);
+    v[1] ^= v[0];
+    v[0] = v[0].rotate_left(32);
+    v[2] = v[2].wrapping_add(v[3]);
+    v[3] = v[3].rotate_left(16);
+    v[3] ^= v[2];
+    v[0] = v[0].wrapping_add(v[3]);
+    v[3] = v[3].rotate_left(21);
+    v[3] ^= v[0];
+    v[2] = v[2].wrapping_add(v[1]);
+    v[1] = v[1].rotate_left(17);
+    v[1] ^= v[2];
+    v[2] = v[2].rotate_left(32);
+}
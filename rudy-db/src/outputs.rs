@@ -2,7 +2,7 @@
 
 use std::{collections::BTreeMap, fmt};
 
-use rudy_dwarf::{function::SelfType, types::DieTypeDefinition};
+use rudy_dwarf::{file::SourceOrigin, function::SelfType, types::DieTypeDefinition};
 
 /// A resolved memory address from a source location.
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -18,6 +18,105 @@ impl fmt::Debug for ResolvedAddress {
     }
 }
 
+/// One of the addresses that a source location resolves to.
+///
+/// A single source line may have many breakpoint addresses: one for each
+/// monomorphization of a generic function, and one for each inlined copy.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ResolvedBreakpoint {
+    pub address: u64,
+    /// The function containing the address, including any generic arguments
+    pub function: String,
+    /// Whether this address is part of an inlined copy of the source location
+    pub inlined: bool,
+}
+
+impl fmt::Debug for ResolvedBreakpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResolvedBreakpoint")
+            .field("address", &format!("{:#x}", self.address))
+            .field("function", &self.function)
+            .field("inlined", &self.inlined)
+            .finish()
+    }
+}
+
+/// The source files of a single crate that are referenced by the debug info.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrateSourceFiles {
+    /// The crate (or package) name, if it could be determined
+    pub name: Option<String>,
+    /// Whether the crate is local, a dependency, or part of the standard library
+    pub origin: SourceOrigin,
+    /// Paths of the source files, sorted
+    pub files: Vec<String>,
+}
+
+/// A function defined in a source file, and the lines it spans.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionSpan {
+    pub name: String,
+    /// The line the function is declared on
+    pub start_line: u64,
+    /// The last line of the function that has code associated with it
+    pub end_line: u64,
+}
+
+/// The contents of a module, as found in the debug info.
+///
+/// Only items that made it into the binary are listed, e.g. generic
+/// functions that were never instantiated will be missing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleInfo {
+    /// The full path of the module, e.g. `my_crate::server`
+    pub path: String,
+    /// Names of the child modules
+    pub modules: Vec<String>,
+    pub types: Vec<ModuleItem>,
+    pub functions: Vec<ModuleItem>,
+    pub statics: Vec<String>,
+}
+
+/// A type or function declared in a module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleItem {
+    /// The name of the item, without generic arguments
+    pub name: String,
+    /// The full names of each generic instantiation,
+    /// empty if the item is not generic
+    pub instantiations: Vec<String>,
+}
+
+/// An implementation of a trait for a type, as found in the binary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraitImpl {
+    /// The implementing type without generic arguments, e.g. `alloc::vec::Vec`
+    pub type_name: String,
+    /// The implemented trait, e.g. `core::fmt::Debug`
+    pub trait_name: String,
+    /// The methods of the impl that have code in the binary, sorted by name
+    pub methods: Vec<TraitImplMethod>,
+}
+
+/// A method of a trait implementation.
+#[derive(Clone, PartialEq, Eq)]
+pub struct TraitImplMethod {
+    pub name: String,
+    /// The full symbol name, e.g. `<alloc::string::String as core::fmt::Debug>::fmt`
+    pub full_name: String,
+    pub address: u64,
+}
+
+impl fmt::Debug for TraitImplMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TraitImplMethod")
+            .field("name", &self.name)
+            .field("full_name", &self.full_name)
+            .field("address", &format!("{:#x}", self.address))
+            .finish()
+    }
+}
+
 /// Source location information resolved from a memory address.
 #[derive(Debug, PartialEq, Clone)]
 pub struct ResolvedLocation {
@@ -85,6 +184,50 @@ pub enum Value {
     Map {
         ty: String,
         entries: Vec<(Value, Value)>,
+        /// How many entries were not read because of a
+        /// [`ReadOptions`](crate::ReadOptions) limit, if any were left out
+        truncated: Option<usize>,
+    },
+    Set {
+        ty: String,
+        items: Vec<Value>,
+    },
+    /// A value of an enum, including `Option`, `Result` and C-style enums
+    Enum {
+        /// The enum type, e.g. `Option<i32>`
+        ty: String,
+        /// The name of the active variant, e.g. `Some`
+        variant: String,
+        /// The discriminant of the active variant, i.e. its declared value
+        /// or its index, rather than how it is encoded in memory
+        discriminant: i128,
+        /// The fields of the variant: a `Value::Tuple` for tuple variants,
+        /// a `Value::Struct` for struct variants, and `None` for unit variants
+        payload: Option<Box<Value>>,
+    },
+    /// Stands in for the items of a collection that were not read because
+    /// of a [`ReadOptions`](crate::ReadOptions) limit. It is the last item
+    /// of the collection, except in maps, which count the entries they left
+    /// out themselves.
+    Truncated {
+        /// How many items were left out
+        remaining: usize,
+    },
+    /// Stands in for a value that can't be valid, e.g. a `Vec` whose length
+    /// exceeds its capacity, as found in uninitialized or corrupted memory
+    Invalid {
+        /// The type the value was read as
+        ty: String,
+        /// What makes the value invalid
+        reason: String,
+    },
+    /// A value that was read, but looks wrong, e.g. a `String` that isn't
+    /// valid UTF-8
+    Suspect {
+        /// What makes the value suspect
+        reason: String,
+        /// The value as best it could be read
+        value: Box<Value>,
     },
     Pointer(TypedPointer),
 }
@@ -111,9 +254,40 @@ impl Value {
                 ty: type_map(ty),
                 fields: fields.clone(),
             },
-            Value::Map { entries, ty } => Value::Map {
+            Value::Map {
+                entries,
+                ty,
+                truncated,
+            } => Value::Map {
                 ty: type_map(ty),
                 entries: entries.clone(),
+                truncated: *truncated,
+            },
+            Value::Set { items, ty } => Value::Set {
+                ty: type_map(ty),
+                items: items.clone(),
+            },
+            Value::Enum {
+                ty,
+                variant,
+                discriminant,
+                payload,
+            } => Value::Enum {
+                ty: type_map(ty),
+                variant: variant.clone(),
+                discriminant: *discriminant,
+                payload: payload.clone(),
+            },
+            Value::Truncated { remaining } => Value::Truncated {
+                remaining: *remaining,
+            },
+            Value::Invalid { ty, reason } => Value::Invalid {
+                ty: type_map(ty),
+                reason: reason.clone(),
+            },
+            Value::Suspect { reason, value } => Value::Suspect {
+                reason: reason.clone(),
+                value: Box::new(value.map_type(type_map)),
             },
             Value::Pointer(ptr) => Value::Pointer(TypedPointer {
                 address: ptr.address,
@@ -122,6 +296,23 @@ impl Value {
         }
     }
 
+    /// The position of this kind of value when ordering values of different kinds
+    fn kind_order(&self) -> u8 {
+        match self {
+            Value::Scalar { .. } => 0,
+            Value::Array { .. } => 1,
+            Value::Struct { .. } => 2,
+            Value::Enum { .. } => 3,
+            Value::Tuple { .. } => 4,
+            Value::Map { .. } => 5,
+            Value::Set { .. } => 6,
+            Value::Truncated { .. } => 7,
+            Value::Pointer(_) => 8,
+            Value::Invalid { .. } => 9,
+            Value::Suspect { .. } => 10,
+        }
+    }
+
     /// Creates a new value with where the current type is prefixed with `prefix`.
     pub(crate) fn prefix_type<T: AsRef<str>>(&self, prefix: T) -> Self {
         let prefix = prefix.as_ref();
@@ -134,6 +325,42 @@ impl Value {
     }
 }
 
+/// A window onto the children of a collection or string, as returned by
+/// [`DebugInfo::children`](crate::DebugInfo::children)
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ChildPage {
+    /// How many children there are in total (bytes, for strings)
+    pub total: usize,
+    /// The children in the requested range
+    pub children: Children,
+}
+
+/// The children within a [`ChildPage`]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Children {
+    /// Elements of an array, slice, `Vec`, `VecDeque` or `LinkedList`,
+    /// or the items of a set
+    Items(Vec<TypedPointer>),
+    /// Key-value pairs of a map
+    Entries(Vec<(TypedPointer, TypedPointer)>),
+    /// A section of a string
+    Text(String),
+}
+
+/// The text shown in place of `remaining` items that were not read, e.g. `… 9,990 more`
+pub fn truncation_marker(remaining: usize) -> String {
+    // group the digits in threes
+    let digits = remaining.to_string();
+    let mut count = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            count.push(',');
+        }
+        count.push(digit);
+    }
+    format!("… {count} more")
+}
+
 impl PartialOrd for Value {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
         Some(self.cmp(other))
@@ -142,7 +369,6 @@ impl PartialOrd for Value {
 
 impl Ord for Value {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        use std::cmp::Ordering;
         match (self, other) {
             (
                 Value::Scalar {
@@ -188,26 +414,76 @@ impl Ord for Value {
                 Value::Map {
                     ty: ty1,
                     entries: entries1,
+                    truncated: truncated1,
                 },
                 Value::Map {
                     ty: ty2,
                     entries: entries2,
+                    truncated: truncated2,
                 },
-            ) => ty1.cmp(ty2).then_with(|| entries1.cmp(entries2)),
+            ) => ty1
+                .cmp(ty2)
+                .then_with(|| entries1.cmp(entries2))
+                .then_with(|| truncated1.cmp(truncated2)),
+            (
+                Value::Set {
+                    ty: ty1,
+                    items: items1,
+                },
+                Value::Set {
+                    ty: ty2,
+                    items: items2,
+                },
+            ) => ty1.cmp(ty2).then_with(|| items1.cmp(items2)),
+            (
+                Value::Enum {
+                    ty: ty1,
+                    variant: variant1,
+                    discriminant: discriminant1,
+                    payload: payload1,
+                },
+                Value::Enum {
+                    ty: ty2,
+                    variant: variant2,
+                    discriminant: discriminant2,
+                    payload: payload2,
+                },
+            ) => ty1
+                .cmp(ty2)
+                .then_with(|| discriminant1.cmp(discriminant2))
+                .then_with(|| variant1.cmp(variant2))
+                .then_with(|| payload1.cmp(payload2)),
+            (
+                Value::Truncated {
+                    remaining: remaining1,
+                },
+                Value::Truncated {
+                    remaining: remaining2,
+                },
+            ) => remaining1.cmp(remaining2),
+            (
+                Value::Invalid {
+                    ty: ty1,
+                    reason: reason1,
+                },
+                Value::Invalid {
+                    ty: ty2,
+                    reason: reason2,
+                },
+            ) => ty1.cmp(ty2).then_with(|| reason1.cmp(reason2)),
+            (
+                Value::Suspect {
+                    reason: reason1,
+                    value: value1,
+                },
+                Value::Suspect {
+                    reason: reason2,
+                    value: value2,
+                },
+            ) => value1.cmp(value2).then_with(|| reason1.cmp(reason2)),
             (Value::Pointer(ptr1), Value::Pointer(ptr2)) => ptr1.address.cmp(&ptr2.address),
             // Define ordering between different variants
-            (Value::Scalar { .. }, _) => Ordering::Less,
-            (Value::Array { .. }, Value::Scalar { .. }) => Ordering::Greater,
-            (Value::Array { .. }, _) => Ordering::Less,
-            (Value::Struct { .. }, Value::Scalar { .. }) => Ordering::Greater,
-            (Value::Struct { .. }, Value::Array { .. }) => Ordering::Greater,
-            (Value::Struct { .. }, _) => Ordering::Less,
-            (Value::Tuple { .. }, Value::Pointer(_)) => Ordering::Less,
-            (Value::Tuple { .. }, Value::Map { .. }) => Ordering::Less,
-            (Value::Tuple { .. }, _) => Ordering::Greater,
-            (Value::Map { .. }, Value::Pointer(_)) => Ordering::Less,
-            (Value::Map { .. }, _) => Ordering::Greater,
-            (Value::Pointer(_), _) => Ordering::Greater,
+            _ => self.kind_order().cmp(&other.kind_order()),
         }
     }
 }
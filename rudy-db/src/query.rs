@@ -1,6 +1,12 @@
 //! Query functions for looking up debug information
 
-use rudy_dwarf::{Binary, SymbolName};
+use std::collections::BTreeMap;
+
+use rudy_dwarf::{
+    Binary, SymbolName,
+    address::{FileLines, FunctionLines, LocationAddress},
+    modules::{ModuleItems, module_index},
+};
 
 use crate::{
     database::Db,
@@ -54,6 +60,104 @@ pub fn lookup_position(
     closest_match
 }
 
+/// Finds every address that a source location resolves to
+///
+/// Whereas [`lookup_position`] only returns the closest match, this returns
+/// one address per monomorphization and inlined copy of the closest matching line.
+#[salsa::tracked]
+pub fn lookup_positions(
+    db: &dyn Db,
+    binary: Binary,
+    query: rudy_dwarf::file::SourceLocation,
+) -> Vec<LocationAddress> {
+    let index = index::debug_index(db, binary);
+    let symbol_index = index.symbol_index(db);
+    let source_to_file = index.source_to_file(db);
+
+    let Some(debug_files) = source_to_file.get(&query.file) else {
+        return vec![];
+    };
+
+    let mut closest_matches = vec![];
+    let mut closest_line = u64::MAX;
+
+    for debug_file in debug_files {
+        let Some(function_index) = symbol_index.function_index(db, *debug_file) else {
+            continue;
+        };
+        let Some((addresses, distance)) =
+            function_index.location_to_addresses(db, *debug_file, &query)
+        else {
+            continue;
+        };
+        tracing::debug!(
+            "found {} matches at distance {distance} in {debug_file:?}",
+            addresses.len()
+        );
+        if distance < closest_line {
+            closest_matches = addresses;
+            closest_line = distance;
+        } else if distance == closest_line {
+            closest_matches.extend(addresses);
+        }
+    }
+
+    closest_matches.sort_by_key(|m| m.address);
+    closest_matches.dedup_by_key(|m| m.address);
+    closest_matches
+}
+
+/// Finds the lines of a source file that have code, and the functions declared in it
+///
+/// Results are merged across all debug files that reference the source file.
+#[salsa::tracked]
+pub fn lookup_file_lines(
+    db: &dyn Db,
+    binary: Binary,
+    file: rudy_dwarf::file::SourceFile,
+) -> FileLines {
+    let index = index::debug_index(db, binary);
+    let symbol_index = index.symbol_index(db);
+    let source_to_file = index.source_to_file(db);
+
+    let mut lines = FileLines::default();
+    let mut functions: BTreeMap<(u64, SymbolName), FunctionLines> = BTreeMap::new();
+
+    for debug_file in source_to_file.get(&file).into_iter().flatten() {
+        let Some(function_index) = symbol_index.function_index(db, *debug_file) else {
+            continue;
+        };
+        let file_lines = function_index.file_lines(db, *debug_file, &file);
+        lines.lines.extend(file_lines.lines);
+        for function in file_lines.functions {
+            functions
+                .entry((function.start_line, function.function.clone()))
+                .and_modify(|existing| existing.end_line = existing.end_line.max(function.end_line))
+                .or_insert(function);
+        }
+    }
+
+    lines.functions = functions.into_values().collect();
+    lines
+}
+
+/// Finds the items declared in a module, merged across all debug files
+///
+/// Returns `None` if no debug file contains the module.
+#[salsa::tracked]
+pub fn lookup_module(db: &dyn Db, binary: Binary, module_path: Vec<String>) -> Option<ModuleItems> {
+    let debug_files = index::debug_index(db, binary).debug_files(db);
+
+    let mut items: Option<ModuleItems> = None;
+    for debug_file in debug_files.values() {
+        let Some(module) = module_index(db, *debug_file).find_by_path(db, &module_path) else {
+            continue;
+        };
+        items.get_or_insert_default().merge(module.items(db));
+    }
+    items
+}
+
 #[tracing::instrument(skip_all, fields(binary=binary.name(db), address=address))]
 #[salsa::tracked]
 pub fn lookup_address<'db>(
@@ -0,0 +1,223 @@
+//! `serde` implementations for the output types, following the JSON schema
+//! documented at the crate root.
+//!
+//! These are written by hand rather than derived so that the schema doesn't
+//! change along with the Rust types, and so that type definitions (which hold
+//! references into the debug info) are serialized as their display names.
+
+use serde::{
+    Serialize, Serializer,
+    ser::{SerializeMap, SerializeStruct},
+};
+
+use crate::{
+    DiscoveredFunction, DiscoveredMethod, FunctionParameter, ModuleInfo, ModuleItem,
+    ResolvedFunction, TypedPointer, Value, Variable, VariableInfo,
+};
+
+/// Addresses are written as hex strings, since they often don't fit in the
+/// integers that JSON parsers support
+struct Address(u64);
+
+impl Serialize for Address {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&format_args!("{:#x}", self.0))
+    }
+}
+
+/// A key-value pair of a map
+struct Entry<'a>(&'a Value, &'a Value);
+
+impl Serialize for Entry<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let Entry(key, value) = self;
+        let mut entry = serializer.serialize_struct("Entry", 2)?;
+        entry.serialize_field("key", key)?;
+        entry.serialize_field("value", value)?;
+        entry.end()
+    }
+}
+
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        match self {
+            Value::Scalar { ty, value } => {
+                map.serialize_entry("kind", "scalar")?;
+                map.serialize_entry("type", ty)?;
+                map.serialize_entry("value", value)?;
+            }
+            Value::Array { ty, items } => {
+                map.serialize_entry("kind", "array")?;
+                map.serialize_entry("type", ty)?;
+                map.serialize_entry("items", items)?;
+            }
+            Value::Struct { ty, fields } => {
+                map.serialize_entry("kind", "struct")?;
+                map.serialize_entry("type", ty)?;
+                map.serialize_entry("fields", fields)?;
+            }
+            Value::Tuple { ty, entries } => {
+                map.serialize_entry("kind", "tuple")?;
+                map.serialize_entry("type", ty)?;
+                map.serialize_entry("entries", entries)?;
+            }
+            Value::Map {
+                ty,
+                entries,
+                truncated,
+            } => {
+                map.serialize_entry("kind", "map")?;
+                map.serialize_entry("type", ty)?;
+                let entries: Vec<_> = entries.iter().map(|(k, v)| Entry(k, v)).collect();
+                map.serialize_entry("entries", &entries)?;
+                map.serialize_entry("truncated", truncated)?;
+            }
+            Value::Set { ty, items } => {
+                map.serialize_entry("kind", "set")?;
+                map.serialize_entry("type", ty)?;
+                map.serialize_entry("items", items)?;
+            }
+            Value::Enum {
+                ty,
+                variant,
+                discriminant,
+                payload,
+            } => {
+                map.serialize_entry("kind", "enum")?;
+                map.serialize_entry("type", ty)?;
+                map.serialize_entry("variant", variant)?;
+                // as a string, like scalars, since it may not fit in a double
+                map.serialize_entry("discriminant", &discriminant.to_string())?;
+                map.serialize_entry("payload", payload)?;
+            }
+            Value::Truncated { remaining } => {
+                map.serialize_entry("kind", "truncated")?;
+                map.serialize_entry("remaining", remaining)?;
+            }
+            Value::Invalid { ty, reason } => {
+                map.serialize_entry("kind", "invalid")?;
+                map.serialize_entry("type", ty)?;
+                map.serialize_entry("reason", reason)?;
+            }
+            Value::Suspect { reason, value } => {
+                map.serialize_entry("kind", "suspect")?;
+                map.serialize_entry("reason", reason)?;
+                map.serialize_entry("value", value)?;
+            }
+            Value::Pointer(pointer) => {
+                map.serialize_entry("kind", "pointer")?;
+                map.serialize_entry("type", &pointer.type_def.display_name())?;
+                map.serialize_entry("address", &Address(pointer.address))?;
+            }
+        }
+        map.end()
+    }
+}
+
+impl Serialize for TypedPointer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut pointer = serializer.serialize_struct("TypedPointer", 2)?;
+        pointer.serialize_field("type", &self.type_def.display_name())?;
+        pointer.serialize_field("address", &Address(self.address))?;
+        pointer.end()
+    }
+}
+
+impl Serialize for VariableInfo {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut variable = serializer.serialize_struct("VariableInfo", 3)?;
+        variable.serialize_field("name", &self.name)?;
+        variable.serialize_field("type", &self.type_def.display_name())?;
+        variable.serialize_field("address", &self.address.map(Address))?;
+        variable.end()
+    }
+}
+
+impl Serialize for Variable {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut variable = serializer.serialize_struct("Variable", 3)?;
+        variable.serialize_field("name", &self.name)?;
+        variable.serialize_field("type", &self.ty.display_name())?;
+        variable.serialize_field("value", &self.value)?;
+        variable.end()
+    }
+}
+
+impl Serialize for ResolvedFunction {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut function = serializer.serialize_struct("ResolvedFunction", 4)?;
+        function.serialize_field("name", &self.name)?;
+        function.serialize_field("address", &Address(self.address))?;
+        function.serialize_field("size", &self.size)?;
+        function.serialize_field("params", &self.params)?;
+        function.end()
+    }
+}
+
+impl Serialize for FunctionParameter {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut parameter = serializer.serialize_struct("FunctionParameter", 2)?;
+        parameter.serialize_field("name", &self.name)?;
+        parameter.serialize_field("type", &self.type_def.display_name())?;
+        parameter.end()
+    }
+}
+
+impl Serialize for DiscoveredMethod {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut method = serializer.serialize_struct("DiscoveredMethod", 9)?;
+        method.serialize_field("name", &self.name)?;
+        method.serialize_field("full_name", &self.full_name)?;
+        method.serialize_field("signature", &self.signature)?;
+        method.serialize_field("address", &Address(self.address))?;
+        method.serialize_field("self_type", &self.self_type)?;
+        method.serialize_field("callable", &self.callable)?;
+        method.serialize_field("is_synthetic", &self.is_synthetic)?;
+        method.serialize_field(
+            "return_type",
+            &self.return_type.as_ref().map(|ty| ty.display_name()),
+        )?;
+        method.serialize_field("parameters", &self.parameters)?;
+        method.end()
+    }
+}
+
+impl Serialize for DiscoveredFunction {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut function = serializer.serialize_struct("DiscoveredFunction", 8)?;
+        function.serialize_field("name", &self.name)?;
+        function.serialize_field("full_name", &self.full_name)?;
+        function.serialize_field("signature", &self.signature)?;
+        function.serialize_field("address", &Address(self.address))?;
+        function.serialize_field("callable", &self.callable)?;
+        function.serialize_field("module_path", &self.module_path)?;
+        function.serialize_field(
+            "return_type",
+            &self.return_type.as_ref().map(|ty| ty.display_name()),
+        )?;
+        function.serialize_field("parameters", &self.parameters)?;
+        function.end()
+    }
+}
+
+impl Serialize for ModuleInfo {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut module = serializer.serialize_struct("ModuleInfo", 5)?;
+        module.serialize_field("path", &self.path)?;
+        module.serialize_field("modules", &self.modules)?;
+        module.serialize_field("types", &self.types)?;
+        module.serialize_field("functions", &self.functions)?;
+        module.serialize_field("statics", &self.statics)?;
+        module.end()
+    }
+}
+
+impl Serialize for ModuleItem {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut item = serializer.serialize_struct("ModuleItem", 2)?;
+        item.serialize_field("name", &self.name)?;
+        item.serialize_field("instantiations", &self.instantiations)?;
+        item.end()
+    }
+}
@@ -9,7 +9,14 @@
 //! - **Vec<T>**: `len()`, `capacity()`, `is_empty()`
 //! - **String**: `len()`, `is_empty()`
 //! - **Option<T>**: `is_some()`, `is_none()`
-//! - **Result<T,E>**: `is_ok()`, `is_err()` (partial - needs Result layout work)
+//! - **Result<T,E>**: `is_ok()`, `is_err()`
+//! - **HashSet<T>** / **BTreeSet<T>** / **IndexSet<T>**: `len()`, `is_empty()`, `contains()`
+//! - **BTreeMap<K,V>**: `first_key_value()`, `last_key_value()`, `range(start..end)`
+//! - **Rc<T>** / **Arc<T>** / **Weak<T>**: `strong_count()`, `weak_count()`
+//! - **Mutex<T>**: `is_locked()`, `is_poisoned()`
+//! - **RwLock<T>**: `is_locked()`, `is_poisoned()`, `reader_count()`
+//! - **RefCell<T>**: `borrow_state()`
+//! - **PathBuf** / **&Path**: `file_name()`, `extension()`, `parent()`, `is_absolute()`
 //! - **&[T]**: `len()`, `is_empty()`
 //! - **&str**: `len()`, `is_empty()`
 //! - **[T; N]**: `len()` (when array types are available in debug info)
@@ -18,15 +25,11 @@
 //!
 //! ### Standard Collections
 //! - **HashMap<K,V>** / **BTreeMap<K,V>**: `len()`, `is_empty()`, `contains_key()`
-//! - **HashSet<T>** / **BTreeSet<T>**: `len()`, `is_empty()`, `contains()`
 //! - **VecDeque<T>**: `len()`, `capacity()`, `is_empty()`
 //! - **LinkedList<T>**: `len()`, `is_empty()`
 //!
 //! ### Smart Pointers
 //! - **Box<T>**: `is_null()` (check for null pointer)
-//! - **Rc<T>** / **Arc<T>**: `strong_count()`, `weak_count()`
-//! - **RefCell<T>**: `try_borrow()` (check if currently borrowed)
-//! - **Mutex<T>** / **RwLock<T>**: `is_poisoned()`
 //!
 //! ### Iterators
 //! - **Range<T>**: `len()`, `is_empty()`, `contains()`
@@ -35,7 +38,7 @@
 //! ### Networking & I/O
 //! - **SocketAddr**: `ip()`, `port()`, `is_ipv4()`, `is_ipv6()`
 //! - **IpAddr**: `is_loopback()`, `is_multicast()`, `is_global()`
-//! - **Path** / **PathBuf**: `is_absolute()`, `is_relative()`, `exists()` (if we can stat)
+//! - **Path** / **PathBuf**: `is_relative()`, `exists()` (if we can stat)
 //!
 //! ### Time & Duration
 //! - **Duration**: `as_secs()`, `as_millis()`, `as_micros()`, `as_nanos()`, `is_zero()`
@@ -55,7 +58,6 @@
 //! ### Complex Computed Properties
 //! - **String**: `char_len()` - length in Unicode characters (vs bytes)
 //! - **Vec<T>**: `remaining_capacity()` - capacity minus length
-//! - **Path**: `file_name()`, `extension()`, `parent()` (string operations on path)
 //!
 //! ## Implementation Notes
 //!
@@ -70,7 +72,10 @@
 
 use anyhow::{Result, anyhow};
 use rudy_dwarf::{Die, types::DieTypeDefinition};
-use rudy_types::{Layout, Location, StdLayout};
+use rudy_types::{
+    Layout, Location, MapVariant, OsStringLayout, PathStyle, SmartPtrLayout, SmartPtrState,
+    SmartPtrVariant, StdLayout,
+};
 
 use crate::{DataResolver, Value};
 
@@ -144,9 +149,115 @@ pub fn get_synthetic_methods<L: Location>(type_layout: &Layout<L>) -> Vec<Synthe
                     takes_args: false,
                 },
             ],
-            StdLayout::Map(_) => vec![
-                // HashMap/BTreeMap methods require understanding internal structure
+            StdLayout::Map(map_layout) => match map_layout.variant {
+                MapVariant::BTreeMap { .. } => vec![
+                    SyntheticMethod {
+                        name: "first_key_value",
+                        signature: "fn first_key_value(&self) -> Option<(&K, &V)>",
+                        takes_args: false,
+                    },
+                    SyntheticMethod {
+                        name: "last_key_value",
+                        signature: "fn last_key_value(&self) -> Option<(&K, &V)>",
+                        takes_args: false,
+                    },
+                    SyntheticMethod {
+                        name: "range",
+                        signature: "fn range(&self, range: Range<K>) -> Range<'_, K, V>",
+                        takes_args: true,
+                    },
+                ],
+                // Other HashMap/BTreeMap methods require understanding internal structure
                 // which is complex and varies by implementation
+                _ => vec![],
+            },
+            StdLayout::Set(_) => vec![
+                SyntheticMethod {
+                    name: "len",
+                    signature: "fn len(&self) -> usize",
+                    takes_args: false,
+                },
+                SyntheticMethod {
+                    name: "is_empty",
+                    signature: "fn is_empty(&self) -> bool",
+                    takes_args: false,
+                },
+                SyntheticMethod {
+                    name: "contains",
+                    signature: "fn contains(&self, value: &T) -> bool",
+                    takes_args: true,
+                },
+            ],
+            StdLayout::SmartPtr(smart_ptr) => match smart_ptr.variant {
+                SmartPtrVariant::Rc | SmartPtrVariant::Arc | SmartPtrVariant::Weak => vec![
+                    SyntheticMethod {
+                        name: "strong_count",
+                        signature: "fn strong_count(&self) -> usize",
+                        takes_args: false,
+                    },
+                    SyntheticMethod {
+                        name: "weak_count",
+                        signature: "fn weak_count(&self) -> usize",
+                        takes_args: false,
+                    },
+                ],
+                SmartPtrVariant::Mutex => vec![
+                    SyntheticMethod {
+                        name: "is_locked",
+                        signature: "fn is_locked(&self) -> bool",
+                        takes_args: false,
+                    },
+                    SyntheticMethod {
+                        name: "is_poisoned",
+                        signature: "fn is_poisoned(&self) -> bool",
+                        takes_args: false,
+                    },
+                ],
+                SmartPtrVariant::RwLock => vec![
+                    SyntheticMethod {
+                        name: "is_locked",
+                        signature: "fn is_locked(&self) -> bool",
+                        takes_args: false,
+                    },
+                    SyntheticMethod {
+                        name: "is_poisoned",
+                        signature: "fn is_poisoned(&self) -> bool",
+                        takes_args: false,
+                    },
+                    SyntheticMethod {
+                        name: "reader_count",
+                        signature: "fn reader_count(&self) -> usize",
+                        takes_args: false,
+                    },
+                ],
+                SmartPtrVariant::RefCell => vec![SyntheticMethod {
+                    name: "borrow_state",
+                    signature: "fn borrow_state(&self) -> BorrowState",
+                    takes_args: false,
+                }],
+                _ => vec![],
+            },
+            StdLayout::OsString(os_string) if os_string.variant.is_path() => vec![
+                SyntheticMethod {
+                    name: "file_name",
+                    signature: "fn file_name(&self) -> Option<&OsStr>",
+                    takes_args: false,
+                },
+                SyntheticMethod {
+                    name: "extension",
+                    signature: "fn extension(&self) -> Option<&OsStr>",
+                    takes_args: false,
+                },
+                SyntheticMethod {
+                    name: "parent",
+                    signature: "fn parent(&self) -> Option<&Path>",
+                    takes_args: false,
+                },
+                SyntheticMethod {
+                    name: "is_absolute",
+                    signature: "fn is_absolute(&self) -> bool",
+                    takes_args: false,
+                },
             ],
             _ => vec![],
         },
@@ -178,6 +289,9 @@ pub fn get_synthetic_methods<L: Location>(type_layout: &Layout<L>) -> Vec<Synthe
 }
 
 /// Evaluate a synthetic method call
+///
+/// Methods that need to compare values read from the target (e.g. `HashSet::contains`)
+/// are only available through [`DebugInfo::evaluate_synthetic_method`](crate::DebugInfo::evaluate_synthetic_method).
 pub fn evaluate_synthetic_method(
     address: u64,
     def: &DieTypeDefinition,
@@ -202,6 +316,15 @@ pub fn evaluate_synthetic_method(
             StdLayout::Map(map_layout) => {
                 evaluate_map_method(address, map_layout, method, resolver)
             }
+            StdLayout::Set(set_layout) => {
+                evaluate_set_method(address, set_layout, method, resolver)
+            }
+            StdLayout::SmartPtr(smart_ptr_layout) => {
+                evaluate_smart_ptr_method(address, smart_ptr_layout, method, resolver)
+            }
+            StdLayout::OsString(os_string_layout) if os_string_layout.variant.is_path() => {
+                evaluate_path_method(address, os_string_layout, method, resolver)
+            }
             _ => Err(anyhow!(
                 "No synthetic method '{}' for type {}",
                 method,
@@ -330,64 +453,24 @@ fn evaluate_result_method(
     method: &str,
     resolver: &dyn DataResolver,
 ) -> Result<Value> {
-    // Read the discriminant to check Ok vs Err
-    let discriminant_bytes = resolver.read_memory(
-        address + result_layout.discriminant.offset as u64,
-        result_layout.discriminant.size(),
-    )?;
+    let is_ok = crate::data::result_is_ok(address, result_layout, resolver)?;
 
-    let discriminant_value = match discriminant_bytes.len() {
-        1 => discriminant_bytes[0] as u64,
-        2 => u16::from_le_bytes(discriminant_bytes.clone().try_into().unwrap()) as u64,
-        4 => u32::from_le_bytes(discriminant_bytes.clone().try_into().unwrap()) as u64,
-        8 => u64::from_le_bytes(discriminant_bytes.clone().try_into().unwrap()),
-        _ => return Err(anyhow!("Unexpected discriminant size")),
-    };
-
-    // Debug: print the discriminant value and bytes
-    tracing::debug!(
-        "Result discriminant bytes: {:?}, value: {:#x}, at offset {}",
-        discriminant_bytes,
-        discriminant_value,
-        result_layout.discriminant.offset
-    );
-
-    // Result uses a special encoding where the discriminant is part of the payload
-    // For Result<T, E>, when it's Ok, the discriminant area contains the Ok value
-    // When it's Err, it uses a special marker (often 0x8000000000000000 for 64-bit)
-    // This is an optimization to avoid wasting space
     match method {
-        "is_ok" => {
-            // Check if the high bit is NOT set (indicating Ok variant)
-            let is_ok = match discriminant_bytes.len() {
-                8 => (discriminant_value & 0x8000000000000000) == 0,
-                4 => (discriminant_value & 0x80000000) == 0,
-                _ => discriminant_value == 0,
-            };
-            Ok(Value::Scalar {
-                ty: "bool".to_string(),
-                value: is_ok.to_string(),
-            })
-        }
-        "is_err" => {
-            // Check if the high bit IS set (indicating Err variant)
-            let is_err = match discriminant_bytes.len() {
-                8 => (discriminant_value & 0x8000000000000000) != 0,
-                4 => (discriminant_value & 0x80000000) != 0,
-                _ => discriminant_value != 0,
-            };
-            Ok(Value::Scalar {
-                ty: "bool".to_string(),
-                value: is_err.to_string(),
-            })
-        }
+        "is_ok" => Ok(Value::Scalar {
+            ty: "bool".to_string(),
+            value: is_ok.to_string(),
+        }),
+        "is_err" => Ok(Value::Scalar {
+            ty: "bool".to_string(),
+            value: (!is_ok).to_string(),
+        }),
         _ => Err(anyhow!("Unknown synthetic method '{}' for Result", method)),
     }
 }
 
 fn evaluate_map_method(
     _address: u64,
-    _map_layout: &rudy_types::MapLayout<Die>,
+    map_layout: &rudy_types::MapLayout<Die>,
     method: &str,
     _resolver: &dyn DataResolver,
 ) -> Result<Value> {
@@ -397,10 +480,68 @@ fn evaluate_map_method(
         "len" | "is_empty" => Err(anyhow!(
             "HashMap/BTreeMap synthetic methods not yet implemented"
         )),
+        "first_key_value" | "last_key_value" | "range" => Err(anyhow!(
+            "{}::{method} needs to read the map entries, use DebugInfo::evaluate_synthetic_method",
+            map_layout.variant.name()
+        )),
         _ => Err(anyhow!("Unknown synthetic method '{}' for Map", method)),
     }
 }
 
+fn evaluate_set_method(
+    address: u64,
+    set_layout: &rudy_types::SetLayout<Die>,
+    method: &str,
+    resolver: &dyn DataResolver,
+) -> Result<Value> {
+    match method {
+        "len" => {
+            let len = set_len(address, set_layout, resolver)?;
+            Ok(Value::Scalar {
+                ty: "usize".to_string(),
+                value: len.to_string(),
+            })
+        }
+        "is_empty" => {
+            let len = set_len(address, set_layout, resolver)?;
+            Ok(Value::Scalar {
+                ty: "bool".to_string(),
+                value: (len == 0).to_string(),
+            })
+        }
+        "contains" => Err(anyhow!(
+            "{}::contains needs to read the set elements, use DebugInfo::evaluate_synthetic_method",
+            set_layout.map.variant.set_name()
+        )),
+        _ => Err(anyhow!(
+            "Unknown synthetic method '{}' for {}",
+            method,
+            set_layout.map.variant.set_name()
+        )),
+    }
+}
+
+/// Read the number of elements of a set from the length field of its inner map
+fn set_len(
+    address: u64,
+    set_layout: &rudy_types::SetLayout<Die>,
+    resolver: &dyn DataResolver,
+) -> Result<usize> {
+    let map_address = address + set_layout.map_offset as u64;
+    let len_offset = match &set_layout.map.variant {
+        MapVariant::HashMap { items_offset, .. } => *items_offset,
+        MapVariant::BTreeMap { length_offset, .. } => *length_offset,
+        MapVariant::IndexMap {
+            entries_len_offset, ..
+        } => *entries_len_offset,
+    };
+    let len_bytes = resolver.read_memory(
+        map_address + len_offset as u64,
+        std::mem::size_of::<usize>(),
+    )?;
+    usize_from_bytes(&len_bytes)
+}
+
 fn evaluate_slice_method(
     address: u64,
     _slice_layout: &rudy_types::SliceLayout<Die>,
@@ -475,6 +616,266 @@ fn evaluate_array_method(
     }
 }
 
+/// Mask of the reader count bits in a futex-based `RwLock` state word
+///
+/// A write lock is represented by all of these bits being set.
+const RWLOCK_READERS_MASK: u32 = (1 << 30) - 1;
+
+/// Path methods are string operations, so we run them on a lossy copy of the
+/// path, split the way the target would split it
+fn evaluate_path_method(
+    address: u64,
+    layout: &OsStringLayout,
+    method: &str,
+    resolver: &dyn DataResolver,
+) -> Result<Value> {
+    let bytes = crate::data::read_os_string_bytes(address, layout, resolver)?;
+    let path = String::from_utf8_lossy(&bytes);
+    let path = TargetPath::new(&path, layout.path_style);
+
+    // optional results are shown the way we'd read an `Option` from memory
+    let optional = |ty: &str, value: Option<&str>| {
+        let option_ty = format!("Option<{ty}>");
+        match value {
+            Some(value) => Value::Enum {
+                variant: "Some".to_string(),
+                discriminant: 1,
+                payload: Some(Box::new(Value::Tuple {
+                    ty: format!("{option_ty}::Some"),
+                    entries: vec![Value::Scalar {
+                        ty: ty.to_string(),
+                        value: format!("\"{value}\""),
+                    }],
+                })),
+                ty: option_ty,
+            },
+            None => Value::Enum {
+                ty: option_ty,
+                variant: "None".to_string(),
+                discriminant: 0,
+                payload: None,
+            },
+        }
+    };
+
+    match method {
+        "file_name" => Ok(optional("&OsStr", path.file_name())),
+        "extension" => Ok(optional("&OsStr", path.extension())),
+        "parent" => Ok(optional("&Path", path.parent())),
+        "is_absolute" => Ok(Value::Scalar {
+            ty: "bool".to_string(),
+            value: path.absolute.to_string(),
+        }),
+        _ => Err(anyhow!(
+            "Unknown synthetic method '{}' for {}",
+            method,
+            layout.variant.name()
+        )),
+    }
+}
+
+/// A path split into components the way `std::path` splits them on the
+/// target, which may not be the platform we're running on
+struct TargetPath<'a> {
+    path: &'a str,
+    /// The length of the prefix and root, e.g. `/` or `C:\`
+    root_len: usize,
+    /// The byte ranges of the components after the root
+    components: Vec<std::ops::Range<usize>>,
+    absolute: bool,
+}
+
+impl<'a> TargetPath<'a> {
+    fn new(path: &'a str, style: PathStyle) -> Self {
+        let is_separator = |c: char| c == '/' || (style == PathStyle::Windows && c == '\\');
+        let (root_len, absolute) = match style {
+            PathStyle::Unix => {
+                let root_len = path.len() - path.trim_start_matches('/').len();
+                (root_len, root_len > 0)
+            }
+            PathStyle::Windows => windows_root(path),
+        };
+
+        let mut components = vec![];
+        let mut start = root_len;
+        for part in path[root_len..].split(is_separator) {
+            let range = start..start + part.len();
+            // separators are all one byte long
+            start = range.end + 1;
+            // like std, we skip empty components and `.`, unless the `.`
+            // starts a relative path
+            if part.is_empty() || (part == "." && range.start > 0) {
+                continue;
+            }
+            components.push(range);
+        }
+
+        Self {
+            path,
+            root_len,
+            components,
+            absolute,
+        }
+    }
+
+    fn file_name(&self) -> Option<&'a str> {
+        let name = &self.path[self.components.last()?.clone()];
+        (name != "." && name != "..").then_some(name)
+    }
+
+    fn extension(&self) -> Option<&'a str> {
+        // names that start with their only `.`, like `.bashrc`, have no extension
+        let (stem, extension) = self.file_name()?.rsplit_once('.')?;
+        (!stem.is_empty()).then_some(extension)
+    }
+
+    fn parent(&self) -> Option<&'a str> {
+        let (_, rest) = self.components.split_last()?;
+        Some(match rest.last() {
+            Some(previous) => &self.path[..previous.end],
+            None => &self.path[..self.root_len],
+        })
+    }
+}
+
+/// The length of the prefix and root of a Windows path, and whether the path
+/// is absolute, i.e. starts with a UNC prefix (`\\server\share`) or a drive
+/// with a root (`C:\`)
+fn windows_root(path: &str) -> (usize, bool) {
+    let bytes = path.as_bytes();
+    let is_separator = |b: &u8| *b == b'/' || *b == b'\\';
+    let component_end = |start: usize| {
+        let start = start.min(bytes.len());
+        start
+            + bytes[start..]
+                .iter()
+                .position(is_separator)
+                .unwrap_or(bytes.len() - start)
+    };
+
+    let (prefix_len, unc) = if bytes.len() >= 2 && bytes[..2].iter().all(is_separator) {
+        // the server and share names are part of the prefix
+        (component_end(component_end(2) + 1), true)
+    } else if bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' {
+        (2, false)
+    } else {
+        (0, false)
+    };
+    let root_len = bytes[prefix_len..]
+        .iter()
+        .take_while(|b| is_separator(b))
+        .count();
+    let absolute = unc || (prefix_len > 0 && root_len > 0);
+    (prefix_len + root_len, absolute)
+}
+
+fn evaluate_smart_ptr_method(
+    address: u64,
+    smart_ptr_layout: &SmartPtrLayout<Die>,
+    method: &str,
+    resolver: &dyn DataResolver,
+) -> Result<Value> {
+    let variant = smart_ptr_layout.variant;
+    match (variant, method) {
+        (
+            SmartPtrVariant::Rc | SmartPtrVariant::Arc | SmartPtrVariant::Weak,
+            "strong_count" | "weak_count",
+        ) => {
+            let (strong, weak) = crate::data::read_ref_counts(address, smart_ptr_layout, resolver)?;
+            Ok(Value::Scalar {
+                ty: "usize".to_string(),
+                value: if method == "strong_count" {
+                    strong
+                } else {
+                    weak
+                }
+                .to_string(),
+            })
+        }
+        (SmartPtrVariant::Mutex | SmartPtrVariant::RwLock, "is_poisoned") => {
+            let SmartPtrState::Lock { poison_offset, .. } = smart_ptr_layout.state else {
+                return Err(anyhow!("No lock state available for {}", variant.name()));
+            };
+            let poisoned = resolver.read_memory(address + poison_offset as u64, 1)?[0] != 0;
+            Ok(Value::Scalar {
+                ty: "bool".to_string(),
+                value: poisoned.to_string(),
+            })
+        }
+        (SmartPtrVariant::Mutex, "is_locked") => {
+            // futex mutex states: 0 = unlocked, 1 = locked, 2 = locked with waiters
+            let state = read_lock_state(address, smart_ptr_layout, resolver)?;
+            Ok(Value::Scalar {
+                ty: "bool".to_string(),
+                value: (state != 0).to_string(),
+            })
+        }
+        (SmartPtrVariant::RwLock, "is_locked" | "reader_count") => {
+            let readers =
+                read_lock_state(address, smart_ptr_layout, resolver)? & RWLOCK_READERS_MASK;
+            Ok(if method == "is_locked" {
+                Value::Scalar {
+                    ty: "bool".to_string(),
+                    value: (readers != 0).to_string(),
+                }
+            } else {
+                Value::Scalar {
+                    ty: "usize".to_string(),
+                    // a write lock sets every reader bit
+                    value: if readers == RWLOCK_READERS_MASK {
+                        0
+                    } else {
+                        readers
+                    }
+                    .to_string(),
+                }
+            })
+        }
+        (SmartPtrVariant::RefCell, "borrow_state") => {
+            let SmartPtrState::BorrowFlag { offset } = smart_ptr_layout.state else {
+                return Err(anyhow!("No borrow flag available for RefCell"));
+            };
+            let flag_bytes = resolver.read_memory(address + offset as u64, size_of::<isize>())?;
+            let flag = usize_from_bytes(&flag_bytes)? as isize;
+            // positive values count shared borrows, negative ones mutable borrows
+            let state = match flag {
+                0 => "Unborrowed".to_string(),
+                n if n > 0 => format!("Borrowed({n})"),
+                _ => "MutablyBorrowed".to_string(),
+            };
+            Ok(Value::Scalar {
+                ty: "BorrowState".to_string(),
+                value: state,
+            })
+        }
+        _ => Err(anyhow!(
+            "Unknown synthetic method '{}' for {}",
+            method,
+            variant.name()
+        )),
+    }
+}
+
+/// Reads the futex state word of a `Mutex` or `RwLock`
+fn read_lock_state(
+    address: u64,
+    smart_ptr_layout: &SmartPtrLayout<Die>,
+    resolver: &dyn DataResolver,
+) -> Result<u32> {
+    let SmartPtrState::Lock {
+        state_offset: Some(state_offset),
+        ..
+    } = smart_ptr_layout.state
+    else {
+        return Err(anyhow!(
+            "Lock state of {} is not available on this platform",
+            smart_ptr_layout.variant.name()
+        ));
+    };
+    let state_bytes = resolver.read_memory(address + state_offset as u64, 4)?;
+    Ok(u32::from_le_bytes(state_bytes.try_into().unwrap()))
+}
+
 fn usize_from_bytes(bytes: &[u8]) -> Result<usize> {
     if bytes.len() == 8 {
         Ok(u64::from_le_bytes(bytes.try_into().unwrap()) as usize)
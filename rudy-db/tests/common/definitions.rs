@@ -52,7 +52,7 @@ pub enum ReprCEnum {
     Struct { x: f64, y: f64 },
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq, Hash)]
 #[repr(u8)]
 pub enum U8Enum {
     First,
@@ -61,3 +61,37 @@ pub enum U8Enum {
     // skip fourth to see what happens
     Fifth = 5,
 }
+
+#[repr(C)]
+pub union TestUnion {
+    pub int: u32,
+    pub float: f32,
+    pub bytes: [u8; 4],
+}
+
+/// A future that is pending the first time it is polled
+pub struct TestYield(pub bool);
+
+impl std::future::Future for TestYield {
+    type Output = ();
+
+    fn poll(
+        mut self: std::pin::Pin<&mut Self>,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<()> {
+        if self.0 {
+            std::task::Poll::Ready(())
+        } else {
+            self.0 = true;
+            std::task::Poll::Pending
+        }
+    }
+}
+
+pub async fn test_async_work(n: u32) -> u32 {
+    let name = String::from("hello");
+    TestYield(false).await;
+    let doubled = n * 2;
+    TestYield(false).await;
+    doubled + name.len() as u32
+}
@@ -93,6 +93,15 @@ impl DataResolver for SelfProcessResolver {
         Ok(buffer)
     }
 
+    fn write_memory(&self, address: u64, data: &[u8]) -> anyhow::Result<()> {
+        // Only tests that pass pointers to their own locals write memory
+        let ptr = address as *mut u8;
+        unsafe {
+            std::ptr::copy_nonoverlapping(data.as_ptr(), ptr, data.len());
+        }
+        Ok(())
+    }
+
     fn get_register(&self, _idx: usize) -> anyhow::Result<u64> {
         // For testing, we need to provide dummy register values.
         Ok(0)
@@ -5,13 +5,18 @@
 pub mod common;
 
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, LinkedList, VecDeque},
     sync::Arc,
 };
 
 use anyhow::Result;
 use common::*;
-use rudy_db::{DataResolver, DebugDb, DebugInfo, Value};
+use indexmap::{IndexMap, IndexSet};
+use rudy_db::{
+    CachingResolver, Children, DataResolver, DebugDb, DebugInfo, ReadOptions, TypedPointer, Value,
+    rudy_dwarf::file::SourceOrigin,
+    rudy_types::{CaptureMode, Layout},
+};
 
 /// Macro that approximates finding a variable in the current process
 /// and reading it from raw memory.
@@ -22,31 +27,33 @@ macro_rules! resolve_variable {
         let resolver = get_resolver(&$debug_info);
         let var_info_pointer = variable_pointer!($debug_info, $var);
 
-        let value = $debug_info
-            .read_pointer(&var_info_pointer, &resolver)
-            .expect("Failed to read string from memory");
-
-        let value = read_value_recursively(&$debug_info, value, &resolver)
-            .expect("Failed to read value recursively");
-
-        value
+        $debug_info
+            .read_value(&var_info_pointer, ReadOptions::unlimited(), &resolver)
+            .expect("Failed to read value")
     }};
 }
 
 macro_rules! variable_pointer {
     ($debug_info:ident, $var:ident) => {{
         let resolver = get_resolver(&$debug_info);
-        let address = $debug_info
-            .find_address_from_source_location(file!(), line!() as u64, None)
-            .expect("Failed to resolve current position")
-            .expect("should resolve current position")
-            .address;
-        tracing::debug!("Current address: {address:#x}");
-
-        let mut var_info = $debug_info
-            .get_variable_at_pc(address, stringify!($var), &resolver)
-            .expect("Failed to get variable at address")
-            .expect("test_string variable should be found");
+
+        // a line without code of its own resolves to the next line that has
+        // some, which may also have code in a closure where the variable isn't
+        // in scope, so we try every address until we find the variable
+        let mut found = None;
+        let breakpoints = $debug_info
+            .find_all_addresses_from_source_location(file!(), line!() as u64, None)
+            .expect("Failed to resolve current position");
+        for breakpoint in breakpoints {
+            tracing::debug!("Current address: {:#x}", breakpoint.address);
+            found = $debug_info
+                .get_variable_at_pc(breakpoint.address, stringify!($var), &resolver)
+                .expect("Failed to get variable at address");
+            if found.is_some() {
+                break;
+            }
+        }
+        let mut var_info = found.expect("test_string variable should be found");
 
         var_info.address = Some(&$var as *const _ as u64);
 
@@ -76,54 +83,6 @@ fn get_resolver(debug_info: &DebugInfo) -> SelfProcessResolver {
     SelfProcessResolver::new(shift)
 }
 
-fn read_value_recursively<'db>(
-    debug_info: &DebugInfo<'db>,
-    value: Value,
-    resolver: &dyn DataResolver,
-) -> Result<Value> {
-    match value {
-        Value::Pointer(typed_pointer) => {
-            let value = debug_info.read_pointer(&typed_pointer, resolver)?;
-            read_value_recursively(debug_info, value, resolver)
-        }
-        v @ Value::Scalar { .. } => Ok(v),
-        Value::Array { ty, items } => {
-            let items = items
-                .into_iter()
-                .map(|v| read_value_recursively(debug_info, v, resolver))
-                .collect::<Result<Vec<_>, _>>()?;
-            Ok(Value::Array { ty, items })
-        }
-        Value::Struct { ty, fields } => {
-            let fields = fields
-                .into_iter()
-                .map(|(k, v)| {
-                    read_value_recursively(debug_info, v, resolver).map(|v| (k.clone(), v))
-                })
-                .collect::<Result<BTreeMap<_, _>, _>>()?;
-            Ok(Value::Struct { ty, fields })
-        }
-        Value::Tuple { ty, entries } => {
-            let entries = entries
-                .into_iter()
-                .map(|v| read_value_recursively(debug_info, v, resolver))
-                .collect::<Result<Vec<_>, _>>()?;
-            Ok(Value::Tuple { ty, entries })
-        }
-        Value::Map { ty, entries } => {
-            let entries = entries
-                .into_iter()
-                .map(|(k, v)| {
-                    let key = read_value_recursively(debug_info, k, resolver)?;
-                    let value = read_value_recursively(debug_info, v, resolver)?;
-                    Ok((key, value))
-                })
-                .collect::<Result<Vec<_>>>()?;
-            Ok(Value::Map { ty, entries })
-        }
-    }
-}
-
 #[macro_export]
 macro_rules! setup_db {
     () => {{
@@ -229,108 +188,2171 @@ fn test_introspect_option() -> Result<()> {
     let value = resolve_variable!(debug_info, test_some);
     insta::assert_debug_snapshot!(value);
 
-    // Test Option::None
-    let test_none: Option<u32> = None;
-    let value = resolve_variable!(debug_info, test_none);
-    insta::assert_debug_snapshot!(value);
+    // Test Option::None
+    let test_none: Option<u32> = None;
+    let value = resolve_variable!(debug_info, test_none);
+    insta::assert_debug_snapshot!(value);
+
+    // Keep data alive
+    let _ = (test_some, test_none);
+    Ok(())
+}
+
+#[test]
+fn test_introspect_hashmap() -> Result<()> {
+    let (_guards, debug_info) = setup_db!();
+
+    // Create test data
+    let mut test_map = HashMap::new();
+    test_map.insert("one".to_string(), 1);
+    test_map.insert("two".to_string(), 2);
+    test_map.insert("three".to_string(), 3);
+
+    let value = resolve_variable!(debug_info, test_map);
+    let Value::Map {
+        ty, mut entries, ..
+    } = value
+    else {
+        panic!("Expected a Map value, got: {value:?}");
+    };
+    assert_eq!(ty, "HashMap<String, i32>");
+    assert_eq!(entries.len(), 3);
+    entries.sort();
+    insta::assert_debug_snapshot!(entries);
+
+    // Keep map alive
+    let _ = test_map;
+    Ok(())
+}
+
+#[test]
+fn test_introspect_btreemap() -> Result<()> {
+    let (_guards, debug_info) = setup_db!();
+
+    // Create test data
+    let mut test_map = BTreeMap::new();
+    test_map.insert("one".to_string(), 1);
+    test_map.insert("two".to_string(), 2);
+    test_map.insert("three".to_string(), 3);
+
+    let value = resolve_variable!(debug_info, test_map);
+    insta::assert_debug_snapshot!(value);
+
+    // Keep map alive
+    let _ = test_map;
+    Ok(())
+}
+
+#[test]
+fn test_introspect_sets() -> Result<()> {
+    let (_guards, debug_info) = setup_db!();
+    let resolver = get_resolver(&debug_info);
+
+    let mut test_hash_set = HashSet::new();
+    test_hash_set.insert("one".to_string());
+    test_hash_set.insert("two".to_string());
+    test_hash_set.insert("three".to_string());
+    let value = resolve_variable!(debug_info, test_hash_set);
+    let Value::Set { ty, mut items } = value else {
+        panic!("Expected a Set value, got: {value:?}");
+    };
+    assert_eq!(ty, "HashSet<String>");
+    items.sort();
+    let items = items
+        .iter()
+        .map(|item| match item {
+            Value::Scalar { value, .. } => value.clone(),
+            _ => panic!("Expected a scalar item, got: {item:?}"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(items, vec!["\"one\"", "\"three\"", "\"two\""]);
+
+    let test_btree_set: BTreeSet<u32> = (1..=20).map(|i| i * 3).collect();
+    let value = resolve_variable!(debug_info, test_btree_set);
+    let Value::Set { ty, items } = value else {
+        panic!("Expected a Set value, got: {value:?}");
+    };
+    assert_eq!(ty, "BTreeSet<u32>");
+    // BTreeSet iterates in order, even across multiple nodes
+    let items = items
+        .iter()
+        .map(|item| match item {
+            Value::Scalar { value, .. } => value.parse::<u32>().unwrap(),
+            _ => panic!("Expected a scalar item, got: {item:?}"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(items, test_btree_set.iter().copied().collect::<Vec<_>>());
+
+    let empty_set: HashSet<u32> = HashSet::new();
+
+    let scalar = |ty: &str, value: &str| Value::Scalar {
+        ty: ty.to_string(),
+        value: value.to_string(),
+    };
+
+    let hash_set_ptr = variable_pointer!(debug_info, test_hash_set);
+    let btree_set_ptr = variable_pointer!(debug_info, test_btree_set);
+    let empty_set_ptr = variable_pointer!(debug_info, empty_set);
+
+    for (ptr, len) in [
+        (&hash_set_ptr, "3"),
+        (&btree_set_ptr, "20"),
+        (&empty_set_ptr, "0"),
+    ] {
+        assert_eq!(
+            debug_info.evaluate_synthetic_method(ptr, "len", &[], &resolver)?,
+            scalar("usize", len)
+        );
+        assert_eq!(
+            debug_info.evaluate_synthetic_method(ptr, "is_empty", &[], &resolver)?,
+            scalar("bool", &(len == "0").to_string())
+        );
+    }
+
+    let contains = |ptr, needle: Value| {
+        debug_info
+            .evaluate_synthetic_method(ptr, "contains", &[needle], &resolver)
+            .unwrap()
+    };
+    assert_eq!(
+        contains(&hash_set_ptr, scalar("String", "two")),
+        scalar("bool", "true")
+    );
+    assert_eq!(
+        contains(&hash_set_ptr, scalar("String", "four")),
+        scalar("bool", "false")
+    );
+    assert_eq!(
+        contains(&btree_set_ptr, scalar("u32", "42")),
+        scalar("bool", "true")
+    );
+    assert_eq!(
+        contains(&btree_set_ptr, scalar("u32", "43")),
+        scalar("bool", "false")
+    );
+    assert_eq!(
+        contains(&empty_set_ptr, scalar("u32", "0")),
+        scalar("bool", "false")
+    );
+
+    // Keep sets alive
+    let _ = (test_hash_set, test_btree_set, empty_set);
+    Ok(())
+}
+
+#[test]
+fn test_introspect_results() -> Result<()> {
+    let (_guards, debug_info) = setup_db!();
+    let resolver = get_resolver(&debug_info);
+
+    let payload = |value: Value| -> (String, Value) {
+        let Value::Enum {
+            variant,
+            payload: Some(payload),
+            ..
+        } = value
+        else {
+            panic!("Expected an Enum value with a payload, got: {value:?}");
+        };
+        let Value::Tuple { mut entries, .. } = *payload else {
+            panic!("Expected a Tuple payload, got: {payload:?}");
+        };
+        assert_eq!(entries.len(), 1, "expected a single payload in {variant}");
+        (variant, entries.remove(0))
+    };
+
+    // tagged: the `String` capacity niche is used to encode `Ok`
+    let ok_result: Result<i32, String> = Ok(42);
+    let (variant, value) = payload(resolve_variable!(debug_info, ok_result));
+    assert_eq!(variant, "Ok");
+    assert_eq!(
+        value,
+        Value::Scalar {
+            ty: "i32".to_string(),
+            value: "42".to_string(),
+        }
+    );
+
+    let err_result: Result<i32, String> = Err("error".to_string());
+    let (variant, value) = payload(resolve_variable!(debug_info, err_result));
+    assert_eq!(variant, "Err");
+    assert_eq!(
+        value,
+        Value::Scalar {
+            ty: "String".to_string(),
+            value: "\"error\"".to_string(),
+        }
+    );
+
+    // niche-packed: a null box pointer encodes `Ok(())`
+    let unit_ok: Result<(), Box<u64>> = Ok(());
+    let unit_err: Result<(), Box<u64>> = Err(Box::new(7));
+    assert_eq!(
+        std::mem::size_of_val(&unit_ok),
+        std::mem::size_of::<usize>()
+    );
+    let (variant, _) = payload(resolve_variable!(debug_info, unit_ok));
+    assert_eq!(variant, "Ok");
+    let (variant, _) = payload(resolve_variable!(debug_info, unit_err));
+    assert_eq!(variant, "Err");
+
+    // small explicit tag
+    let small_err: Result<u8, u16> = Err(300);
+    let (variant, value) = payload(resolve_variable!(debug_info, small_err));
+    assert_eq!(variant, "Err");
+    assert_eq!(
+        value,
+        Value::Scalar {
+            ty: "u16".to_string(),
+            value: "300".to_string(),
+        }
+    );
+
+    // field access goes to the payload of the active variant
+    for (pointer, expected) in [
+        (
+            variable_pointer!(debug_info, ok_result),
+            Value::Scalar {
+                ty: "i32".to_string(),
+                value: "42".to_string(),
+            },
+        ),
+        (
+            variable_pointer!(debug_info, err_result),
+            Value::Scalar {
+                ty: "String".to_string(),
+                value: "\"error\"".to_string(),
+            },
+        ),
+        (
+            variable_pointer!(debug_info, small_err),
+            Value::Scalar {
+                ty: "u16".to_string(),
+                value: "300".to_string(),
+            },
+        ),
+    ] {
+        let field = debug_info.get_field(&pointer, "0", &resolver)?;
+        assert_eq!(debug_info.read_pointer(&field, &resolver)?, expected);
+    }
+
+    let boxed_ptr = variable_pointer!(debug_info, unit_err);
+    let field = debug_info.get_field(&boxed_ptr, "0", &resolver)?;
+    let boxed = debug_info.read_value(&field, ReadOptions::unlimited(), &resolver)?;
+    assert_eq!(
+        boxed,
+        Value::Scalar {
+            ty: "Box<u64>".to_string(),
+            value: "7".to_string(),
+        }
+    );
+
+    let _ = (ok_result, err_result, unit_ok, unit_err, small_err);
+
+    Ok(())
+}
+
+#[test]
+fn test_introspect_queues() -> Result<()> {
+    let (_guards, debug_info) = setup_db!();
+    let resolver = get_resolver(&debug_info);
+
+    let scalars = |value: Value| -> (String, Vec<String>) {
+        let Value::Array { ty, items } = value else {
+            panic!("Expected an Array value, got: {value:?}");
+        };
+        let items = items
+            .into_iter()
+            .map(|item| match item {
+                Value::Scalar { value, .. } => value,
+                _ => panic!("Expected a scalar item, got: {item:?}"),
+            })
+            .collect();
+        (ty, items)
+    };
+
+    // Push to both ends so that the ring buffer wraps around the allocation
+    let mut test_deque = VecDeque::with_capacity(8);
+    test_deque.extend([3, 4, 5]);
+    test_deque.push_front(2);
+    test_deque.push_front(1);
+    assert!(
+        !test_deque.as_slices().1.is_empty(),
+        "deque contents should be wrapped"
+    );
+    let value = resolve_variable!(debug_info, test_deque);
+    assert_eq!(
+        scalars(value),
+        (
+            "VecDeque<i32>".to_string(),
+            vec!["1", "2", "3", "4", "5"]
+                .into_iter()
+                .map(String::from)
+                .collect()
+        )
+    );
+
+    let deque_ptr = variable_pointer!(debug_info, test_deque);
+    for (index, expected) in test_deque.iter().enumerate() {
+        let element = debug_info.index_array_or_slice(&deque_ptr, index as u64, &resolver)?;
+        assert_eq!(
+            debug_info.read_pointer(&element, &resolver)?,
+            Value::Scalar {
+                ty: "i32".to_string(),
+                value: expected.to_string(),
+            }
+        );
+    }
+    assert!(
+        debug_info
+            .index_array_or_slice(&deque_ptr, 5, &resolver)
+            .is_err()
+    );
+
+    // zero-sized items have a capacity of `usize::MAX`, and pushing to the
+    // front moves the head to the end of that
+    let mut unit_deque: VecDeque<()> = VecDeque::new();
+    unit_deque.push_front(());
+    unit_deque.extend([(), ()]);
+    let Value::Array { items, .. } = resolve_variable!(debug_info, unit_deque) else {
+        panic!("expected the deque's items");
+    };
+    assert_eq!(items.len(), 3);
+
+    let test_list: LinkedList<String> = ["a", "b", "c"].into_iter().map(String::from).collect();
+    let value = resolve_variable!(debug_info, test_list);
+    assert_eq!(
+        scalars(value),
+        (
+            "LinkedList<String>".to_string(),
+            vec!["\"a\"", "\"b\"", "\"c\""]
+                .into_iter()
+                .map(String::from)
+                .collect()
+        )
+    );
+    let list_ptr = variable_pointer!(debug_info, test_list);
+    let element = debug_info.index_array_or_slice(&list_ptr, 2, &resolver)?;
+    assert_eq!(
+        debug_info.read_pointer(&element, &resolver)?,
+        Value::Scalar {
+            ty: "String".to_string(),
+            value: "\"c\"".to_string(),
+        }
+    );
+
+    let test_heap: BinaryHeap<u64> = [4, 9, 1, 7].into_iter().collect();
+    let value = resolve_variable!(debug_info, test_heap);
+    let (ty, items) = scalars(value);
+    assert_eq!(ty, "BinaryHeap<u64>");
+    // the heap is read in storage order, which puts the maximum first
+    assert_eq!(
+        items,
+        test_heap
+            .as_slice()
+            .iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(items[0], "9");
+
+    // Keep data alive
+    let _ = (test_deque, test_list, test_heap);
+    Ok(())
+}
+
+#[test]
+fn test_introspect_indexmap() -> Result<()> {
+    let (_guards, debug_info) = setup_db!();
+    let resolver = get_resolver(&debug_info);
+
+    let scalar = |ty: &str, value: &str| Value::Scalar {
+        ty: ty.to_string(),
+        value: value.to_string(),
+    };
+
+    // Insertion order is preserved, even after a removal shifts entries down
+    let mut test_index_map = IndexMap::new();
+    test_index_map.insert("zebra".to_string(), 26);
+    test_index_map.insert("removed".to_string(), 0);
+    test_index_map.insert("apple".to_string(), 1);
+    test_index_map.insert("mango".to_string(), 13);
+    test_index_map.shift_remove("removed");
+
+    let value = resolve_variable!(debug_info, test_index_map);
+    let Value::Map { ty, entries, .. } = value else {
+        panic!("Expected a Map value, got: {value:?}");
+    };
+    assert_eq!(ty, "IndexMap<String, i32>");
+    assert_eq!(
+        entries,
+        vec![
+            (scalar("String", "\"zebra\""), scalar("i32", "26")),
+            (scalar("String", "\"apple\""), scalar("i32", "1")),
+            (scalar("String", "\"mango\""), scalar("i32", "13")),
+        ]
+    );
+
+    let map_ptr = variable_pointer!(debug_info, test_index_map);
+    let value_ptr = debug_info.index_map(
+        map_ptr.address,
+        &map_ptr.type_def,
+        &scalar("String", "apple"),
+        &resolver,
+    )?;
+    assert_eq!(
+        debug_info.read_pointer(&value_ptr, &resolver)?,
+        scalar("i32", "1")
+    );
+    assert!(
+        debug_info
+            .index_map(
+                map_ptr.address,
+                &map_ptr.type_def,
+                &scalar("String", "removed"),
+                &resolver,
+            )
+            .is_err()
+    );
+
+    let test_index_set: IndexSet<u64> = [30, 10, 20].into_iter().collect();
+    let value = resolve_variable!(debug_info, test_index_set);
+    assert_eq!(
+        value,
+        Value::Set {
+            ty: "IndexSet<u64>".to_string(),
+            items: vec![
+                scalar("u64", "30"),
+                scalar("u64", "10"),
+                scalar("u64", "20"),
+            ],
+        }
+    );
+
+    let set_ptr = variable_pointer!(debug_info, test_index_set);
+    let element_ptr = debug_info.index_map(
+        set_ptr.address,
+        &set_ptr.type_def,
+        &scalar("u64", "10"),
+        &resolver,
+    )?;
+    assert_eq!(
+        debug_info.read_pointer(&element_ptr, &resolver)?,
+        scalar("u64", "10")
+    );
+    assert_eq!(
+        debug_info.evaluate_synthetic_method(&set_ptr, "len", &[], &resolver)?,
+        scalar("usize", "3")
+    );
+
+    // Keep data alive
+    let _ = (test_index_map, test_index_set);
+    Ok(())
+}
+
+#[test]
+fn test_introspect_complex_nested_types() {
+    let (_guards, debug_info) = setup_db!();
+
+    // Create complex nested data - NOTE: This contains Vec and HashMap which are not implemented yet
+    let mut metadata = BTreeMap::new();
+    metadata.insert("version".to_string(), "1.0".to_string());
+    metadata.insert("author".to_string(), "test".to_string());
+
+    let test_data = TestComplexData {
+        id: 12345,
+        values: vec![100, 200, 300],
+        metadata,
+        location: TestPoint {
+            x: 3.14001,
+            y: 2.71,
+        },
+    };
+
+    let value = resolve_variable!(debug_info, test_data);
+    insta::assert_debug_snapshot!(value);
+
+    // Keep data alive
+    let _ = test_data;
+}
+
+#[test]
+fn test_introspect_smart_pointers() -> Result<()> {
+    let (_guards, debug_info) = setup_db!();
+
+    // Create test data with smart pointers
+    let test_box: Box<String> = Box::new(String::from("Boxed string"));
+    let test_arc: Arc<Vec<i32>> = Arc::new(vec![1, 2, 3]);
+    let test_rc: std::rc::Rc<i32> = std::rc::Rc::new(42);
+    let test_mutex: std::sync::Mutex<i32> = std::sync::Mutex::new(42);
+    let test_cell: std::cell::RefCell<i32> = std::cell::RefCell::new(100);
+
+    let box_value = resolve_variable!(debug_info, test_box);
+    insta::assert_debug_snapshot!(box_value);
+    let arc_value = resolve_variable!(debug_info, test_arc);
+    insta::assert_debug_snapshot!(arc_value);
+    let rc_value = resolve_variable!(debug_info, test_rc);
+    insta::assert_debug_snapshot!(rc_value);
+    let mutex_value = resolve_variable!(debug_info, test_mutex);
+    insta::assert_debug_snapshot!(mutex_value);
+    let cell_value = resolve_variable!(debug_info, test_cell);
+    insta::assert_debug_snapshot!(cell_value);
+
+    // Keep data alive
+    let _ = (test_box, test_arc, test_rc, test_mutex, test_cell);
+    Ok(())
+}
+
+#[test]
+fn test_smart_pointer_state() -> Result<()> {
+    let (_guards, debug_info) = setup_db!();
+    let resolver = get_resolver(&debug_info);
+
+    let scalar = |ty: &str, value: &str| Value::Scalar {
+        ty: ty.to_string(),
+        value: value.to_string(),
+    };
+    let call = |pointer: &rudy_db::TypedPointer, method: &str| {
+        debug_info
+            .evaluate_synthetic_method(pointer, method, &[], &resolver)
+            .unwrap_or_else(|e| panic!("{method}() failed: {e:?}"))
+    };
+
+    // plain cells
+    let test_cell = std::cell::Cell::new(7u32);
+    let test_unsafe_cell = std::cell::UnsafeCell::new(9u8);
+    assert_eq!(
+        resolve_variable!(debug_info, test_cell),
+        scalar("Cell<u32>", "7")
+    );
+    assert_eq!(
+        resolve_variable!(debug_info, test_unsafe_cell),
+        scalar("UnsafeCell<u8>", "9")
+    );
+
+    // reference counts, and weak pointers that can or can't be upgraded
+    let test_rc = std::rc::Rc::new(42i32);
+    let rc_clone = test_rc.clone();
+    let rc_weak = std::rc::Rc::downgrade(&test_rc);
+    let dropped_weak = std::rc::Rc::downgrade(&std::rc::Rc::new(1i32));
+    let dangling_weak: std::rc::Weak<i32> = std::rc::Weak::new();
+    let test_arc = Arc::new(String::from("shared"));
+    let arc_weak = Arc::downgrade(&test_arc);
+    let arc_weak_clone = arc_weak.clone();
+
+    let rc_ptr = variable_pointer!(debug_info, test_rc);
+    assert_eq!(call(&rc_ptr, "strong_count"), scalar("usize", "2"));
+    assert_eq!(call(&rc_ptr, "weak_count"), scalar("usize", "1"));
+    let arc_ptr = variable_pointer!(debug_info, test_arc);
+    assert_eq!(call(&arc_ptr, "strong_count"), scalar("usize", "1"));
+    assert_eq!(call(&arc_ptr, "weak_count"), scalar("usize", "2"));
+    let arc_weak_ptr = variable_pointer!(debug_info, arc_weak);
+    assert_eq!(call(&arc_weak_ptr, "strong_count"), scalar("usize", "1"));
+    let dropped_weak_ptr = variable_pointer!(debug_info, dropped_weak);
+    assert_eq!(
+        call(&dropped_weak_ptr, "strong_count"),
+        scalar("usize", "0")
+    );
+    let dangling_weak_ptr = variable_pointer!(debug_info, dangling_weak);
+    assert_eq!(call(&dangling_weak_ptr, "weak_count"), scalar("usize", "0"));
+
+    assert_eq!(
+        resolve_variable!(debug_info, rc_weak),
+        scalar("Weak<i32>", "42")
+    );
+    assert_eq!(
+        resolve_variable!(debug_info, arc_weak),
+        scalar("Weak<String>", "\"shared\"")
+    );
+    assert_eq!(
+        resolve_variable!(debug_info, dropped_weak),
+        scalar("Weak<i32>", "(dropped)")
+    );
+    assert_eq!(
+        resolve_variable!(debug_info, dangling_weak),
+        scalar("Weak<i32>", "(dropped)")
+    );
+
+    // RefCell borrow flag
+    let unborrowed = std::cell::RefCell::new(1i32);
+    let shared = std::cell::RefCell::new(2i32);
+    let exclusive = std::cell::RefCell::new(3i32);
+    let shared_borrows = (shared.borrow(), shared.borrow());
+    let exclusive_borrow = exclusive.borrow_mut();
+    for (pointer, expected) in [
+        (variable_pointer!(debug_info, unborrowed), "Unborrowed"),
+        (variable_pointer!(debug_info, shared), "Borrowed(2)"),
+        (variable_pointer!(debug_info, exclusive), "MutablyBorrowed"),
+    ] {
+        assert_eq!(
+            call(&pointer, "borrow_state"),
+            scalar("BorrowState", expected)
+        );
+    }
+
+    // lock state
+    let unlocked = std::sync::Mutex::new(1u64);
+    let locked = std::sync::Mutex::new(2u64);
+    let poisoned = std::sync::Mutex::new(3u64);
+    let locked_guard = locked.lock().unwrap();
+    let _ = std::panic::catch_unwind(|| {
+        let _guard = poisoned.lock().unwrap();
+        panic!("poisoning the mutex");
+    });
+    let read_locked = std::sync::RwLock::new(vec![1u8]);
+    let write_locked = std::sync::RwLock::new(vec![2u8]);
+    let read_guards = (read_locked.read().unwrap(), read_locked.read().unwrap());
+    let write_guard = write_locked.write().unwrap();
+
+    assert_eq!(
+        resolve_variable!(debug_info, unlocked),
+        scalar("Mutex<u64>", "1")
+    );
+    let poisoned_ptr = variable_pointer!(debug_info, poisoned);
+    assert_eq!(call(&poisoned_ptr, "is_poisoned"), scalar("bool", "true"));
+    let unlocked_ptr = variable_pointer!(debug_info, unlocked);
+    assert_eq!(call(&unlocked_ptr, "is_poisoned"), scalar("bool", "false"));
+
+    if cfg!(target_os = "linux") {
+        // lock state words are only available for futex-based locks
+        let locked_ptr = variable_pointer!(debug_info, locked);
+        assert_eq!(call(&unlocked_ptr, "is_locked"), scalar("bool", "false"));
+        assert_eq!(call(&locked_ptr, "is_locked"), scalar("bool", "true"));
+        assert_eq!(call(&poisoned_ptr, "is_locked"), scalar("bool", "false"));
+
+        let read_locked_ptr = variable_pointer!(debug_info, read_locked);
+        let write_locked_ptr = variable_pointer!(debug_info, write_locked);
+        assert_eq!(call(&read_locked_ptr, "is_locked"), scalar("bool", "true"));
+        assert_eq!(call(&read_locked_ptr, "reader_count"), scalar("usize", "2"));
+        assert_eq!(call(&write_locked_ptr, "is_locked"), scalar("bool", "true"));
+        assert_eq!(
+            call(&write_locked_ptr, "reader_count"),
+            scalar("usize", "0")
+        );
+    }
+
+    // Keep data alive
+    let _ = (test_cell, test_unsafe_cell, rc_clone, arc_weak_clone);
+    drop((
+        shared_borrows,
+        exclusive_borrow,
+        locked_guard,
+        read_guards,
+        write_guard,
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_introspect_unsized_smart_pointers() -> Result<()> {
+    let (_guards, debug_info) = setup_db!();
+
+    let scalar = |ty: &str, value: &str| Value::Scalar {
+        ty: ty.to_string(),
+        value: value.to_string(),
+    };
+    let array = |ty: &str, items: &[(&str, &str)]| Value::Array {
+        ty: ty.to_string(),
+        items: items.iter().map(|(ty, value)| scalar(ty, value)).collect(),
+    };
+
+    let boxed_str: Box<str> = "boxed".into();
+    let boxed_slice: Box<[u8]> = vec![1u8, 2].into_boxed_slice();
+    let rc_str: std::rc::Rc<str> = "counted".into();
+    let arc_slice: Arc<[u32]> = vec![3u32, 4, 5].into();
+    let weak_str = std::rc::Rc::downgrade(&rc_str);
+    let boxed_dyn: Box<dyn std::fmt::Debug> = Box::new(7u64);
+
+    assert_eq!(
+        resolve_variable!(debug_info, boxed_str),
+        scalar("Box<str>", "\"boxed\"")
+    );
+    assert_eq!(
+        resolve_variable!(debug_info, boxed_slice),
+        array("Box<[u8]>", &[("u8", "1"), ("u8", "2")])
+    );
+    assert_eq!(
+        resolve_variable!(debug_info, rc_str),
+        scalar("Rc<str>", "\"counted\"")
+    );
+    assert_eq!(
+        resolve_variable!(debug_info, arc_slice),
+        array("Arc<[u32]>", &[("u32", "3"), ("u32", "4"), ("u32", "5")])
+    );
+    assert_eq!(
+        resolve_variable!(debug_info, weak_str),
+        scalar("Weak<str>", "\"counted\"")
+    );
+    let Value::Scalar { ty, value } = resolve_variable!(debug_info, boxed_dyn) else {
+        panic!("trait objects should be read as a scalar");
+    };
+    assert_eq!(ty, "Box<dyn core::fmt::Debug>");
+    let data_address = &*boxed_dyn as *const dyn std::fmt::Debug as *const () as u64;
+    assert!(
+        value.starts_with(&format!("{data_address:#x} (vtable: 0x")),
+        "unexpected trait object value: {value}"
+    );
+
+    // Cow shows whichever data it holds
+    let borrowed_cow: std::borrow::Cow<'_, str> = std::borrow::Cow::Borrowed("borrowed");
+    let owned_cow: std::borrow::Cow<'_, str> = std::borrow::Cow::Owned("owned".to_string());
+    let owned_bytes: std::borrow::Cow<'_, [u8]> = std::borrow::Cow::Owned(vec![9u8]);
+    assert_eq!(
+        resolve_variable!(debug_info, borrowed_cow),
+        scalar("Cow<str>", "\"borrowed\"")
+    );
+    assert_eq!(
+        resolve_variable!(debug_info, owned_cow),
+        scalar("Cow<str>", "\"owned\"")
+    );
+    assert_eq!(
+        resolve_variable!(debug_info, owned_bytes),
+        array("Cow<[u8]>", &[("u8", "9")])
+    );
+
+    // Keep data alive
+    let _ = (
+        boxed_str,
+        boxed_slice,
+        rc_str,
+        arc_slice,
+        weak_str,
+        boxed_dyn,
+    );
+    let _ = (borrowed_cow, owned_cow, owned_bytes);
+    Ok(())
+}
+
+#[test]
+fn test_introspect_os_strings() -> Result<()> {
+    let (_guards, debug_info) = setup_db!();
+    let resolver = get_resolver(&debug_info);
+
+    let scalar = |ty: &str, value: &str| Value::Scalar {
+        ty: ty.to_string(),
+        value: value.to_string(),
+    };
+    let call = |pointer: &rudy_db::TypedPointer, method: &str| {
+        debug_info
+            .evaluate_synthetic_method(pointer, method, &[], &resolver)
+            .unwrap_or_else(|e| panic!("{method}() failed: {e:?}"))
+    };
+    let some = |ty: &str, value: &str| Value::Enum {
+        ty: format!("Option<{ty}>"),
+        variant: "Some".to_string(),
+        discriminant: 1,
+        payload: Some(Box::new(Value::Tuple {
+            ty: format!("Option<{ty}>::Some"),
+            entries: vec![scalar(ty, value)],
+        })),
+    };
+
+    let path_buf = std::path::PathBuf::from("/tmp/rudy/notes.txt");
+    let path: &std::path::Path = std::path::Path::new("relative/dir");
+    let os_string = std::ffi::OsString::from("os string");
+    let os_str: &std::ffi::OsStr = std::ffi::OsStr::new("os str");
+    let c_string = std::ffi::CString::new("c string").unwrap();
+    let c_str: &std::ffi::CStr = c"c str";
+
+    assert_eq!(
+        resolve_variable!(debug_info, path_buf),
+        scalar("PathBuf", "\"/tmp/rudy/notes.txt\"")
+    );
+    assert_eq!(
+        resolve_variable!(debug_info, path),
+        scalar("&Path", "\"relative/dir\"")
+    );
+    assert_eq!(
+        resolve_variable!(debug_info, os_string),
+        scalar("OsString", "\"os string\"")
+    );
+    assert_eq!(
+        resolve_variable!(debug_info, os_str),
+        scalar("&OsStr", "\"os str\"")
+    );
+    assert_eq!(
+        resolve_variable!(debug_info, c_string),
+        scalar("CString", "\"c string\"")
+    );
+    assert_eq!(
+        resolve_variable!(debug_info, c_str),
+        scalar("&CStr", "\"c str\"")
+    );
+    // these needn't be UTF-8, so invalid bytes aren't suspicious
+    let latin1 = std::ffi::CString::new(b"caf\xe9".to_vec()).unwrap();
+    assert_eq!(
+        resolve_variable!(debug_info, latin1),
+        scalar("CString", "\"caf\u{fffd}\"")
+    );
+
+    let path_buf_ptr = variable_pointer!(debug_info, path_buf);
+    assert_eq!(
+        call(&path_buf_ptr, "file_name"),
+        some("&OsStr", "\"notes.txt\"")
+    );
+    assert_eq!(call(&path_buf_ptr, "extension"), some("&OsStr", "\"txt\""));
+    assert_eq!(
+        call(&path_buf_ptr, "parent"),
+        some("&Path", "\"/tmp/rudy\"")
+    );
+    assert_eq!(call(&path_buf_ptr, "is_absolute"), scalar("bool", "true"));
+
+    let path_ptr = variable_pointer!(debug_info, path);
+    assert_eq!(
+        call(&path_ptr, "extension"),
+        Value::Enum {
+            ty: "Option<&OsStr>".to_string(),
+            variant: "None".to_string(),
+            discriminant: 0,
+            payload: None,
+        }
+    );
+    assert_eq!(call(&path_ptr, "is_absolute"), scalar("bool", "false"));
+
+    // Keep data alive
+    let _ = (path_buf, path, os_string, os_str, c_string, c_str);
+    Ok(())
+}
+
+#[test]
+fn test_introspect_unions() -> Result<()> {
+    let (_guards, debug_info) = setup_db!();
+
+    let scalar = |ty: &str, value: &str| Value::Scalar {
+        ty: ty.to_string(),
+        value: value.to_string(),
+    };
+
+    // every field of a union is shown, since any of them could be active
+    let test_union = TestUnion { float: 1.0 };
+    assert_eq!(
+        resolve_variable!(debug_info, test_union),
+        Value::Struct {
+            ty: "TestUnion".to_string(),
+            fields: [
+                ("int".to_string(), scalar("u32", "1065353216")),
+                ("float".to_string(), scalar("f32", "1")),
+                (
+                    "bytes".to_string(),
+                    Value::Array {
+                        ty: "[u8; 4]".to_string(),
+                        items: [0, 0, 128, 63]
+                            .iter()
+                            .map(|b: &u8| scalar("u8", &b.to_string()))
+                            .collect(),
+                    }
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        }
+    );
+
+    let manually_dropped = std::mem::ManuallyDrop::new(42u64);
+    let maybe_uninit = std::mem::MaybeUninit::new(7u32);
+    let maybe_uninit_string = std::mem::MaybeUninit::new("hello".to_string());
+    assert_eq!(
+        resolve_variable!(debug_info, manually_dropped),
+        scalar("ManuallyDrop<u64>", "42")
+    );
+    let may_be_uninitialized = |value: Value| Value::Suspect {
+        reason: "may be uninitialized".to_string(),
+        value: Box::new(value),
+    };
+    assert_eq!(
+        resolve_variable!(debug_info, maybe_uninit),
+        may_be_uninitialized(scalar("u32", "7"))
+    );
+    assert_eq!(
+        resolve_variable!(debug_info, maybe_uninit_string),
+        may_be_uninitialized(scalar("String", "\"hello\""))
+    );
+
+    // Keep data alive
+    let _ = (&test_union, manually_dropped, maybe_uninit);
+    let _ = unsafe { maybe_uninit_string.assume_init() };
+    Ok(())
+}
+
+#[test]
+fn test_introspect_async_fn() -> Result<()> {
+    let (_guards, debug_info) = setup_db!();
+
+    let scalar = |ty: &str, value: &str| Value::Scalar {
+        ty: ty.to_string(),
+        value: value.to_string(),
+    };
+
+    // the await points of `test_async_work`
+    let await_lines: Vec<_> = include_str!("common/definitions.rs")
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| line.contains("TestYield(false).await"))
+        .map(|(i, _)| i + 1)
+        .collect();
+
+    fn poll<F: std::future::Future>(future: &mut F) -> std::task::Poll<F::Output> {
+        let mut cx = std::task::Context::from_waker(std::task::Waker::noop());
+        // the future stays in place until the end of the test
+        unsafe { std::pin::Pin::new_unchecked(future) }.poll(&mut cx)
+    }
+
+    let mut future = test_async_work(21);
+
+    let Value::Struct { ty, fields } = resolve_variable!(debug_info, future) else {
+        panic!("unresumed future should hold its arguments");
+    };
+    assert_eq!(ty, "{async_fn_env#0}::Unresumed");
+    assert_eq!(fields["n"], scalar("u32", "21"));
+
+    assert!(poll(&mut future).is_pending());
+    let Value::Struct { ty, fields } = resolve_variable!(debug_info, future) else {
+        panic!("suspended future should hold its locals");
+    };
+    assert!(
+        ty.starts_with("{async_fn_env#0}::Suspend0 at ")
+            && ty.ends_with(&format!("definitions.rs:{}", await_lines[0])),
+        "unexpected state: {ty}"
+    );
+    assert_eq!(fields["n"], scalar("u32", "21"));
+    assert_eq!(fields["name"], scalar("String", "\"hello\""));
+    assert!(fields.contains_key("__awaitee"));
+
+    assert!(poll(&mut future).is_pending());
+    let Value::Struct { ty, fields } = resolve_variable!(debug_info, future) else {
+        panic!("suspended future should hold its locals");
+    };
+    assert!(
+        ty.starts_with("{async_fn_env#0}::Suspend1 at ")
+            && ty.ends_with(&format!("definitions.rs:{}", await_lines[1])),
+        "unexpected state: {ty}"
+    );
+    assert_eq!(fields["doubled"], scalar("u32", "42"));
+
+    assert_eq!(poll(&mut future), std::task::Poll::Ready(47));
+    assert_eq!(
+        resolve_variable!(debug_info, future),
+        scalar("{async_fn_env#0}::Returned", "Returned")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_introspect_closures() -> Result<()> {
+    let (_guards, debug_info) = setup_db!();
+
+    let scalar = |ty: &str, value: &str| Value::Scalar {
+        ty: ty.to_string(),
+        value: value.to_string(),
+    };
+
+    // closures are named after where their body starts
+    let source = include_str!("dynamic_tests.rs");
+    let body_position = |body: &str| {
+        source
+            .lines()
+            .enumerate()
+            .find_map(|(i, line)| line.find(body).map(|column| (i + 1, column + 1)))
+            .unwrap()
+    };
+
+    let name = String::from("hello");
+    let mut count = 3u64;
+    let moved = String::from("moved");
+
+    let by_ref = |x: usize| x + name.len();
+    let mut by_mut = || count += 1;
+    let by_move = move || moved.len();
+    by_mut();
+
+    // field captures are named after the place, even when the
+    // variable's own name contains the `__` rustc joins fields with
+    let point = (1u64, 2u64);
+    #[allow(non_snake_case)]
+    let total__count = 10u64;
+    let by_place = || point.1 + total__count;
+
+    let Value::Struct { ty, fields } = resolve_variable!(debug_info, by_ref) else {
+        panic!("closure should be read as its captures");
+    };
+    let (line, column) = body_position("x + name.len()");
+    assert_eq!(
+        ty,
+        format!(
+            "dynamic_tests::test_introspect_closures::{{closure@dynamic_tests.rs:{line}:{column}}}"
+        )
+    );
+    // by-ref captures are marked with a `&`, by-move ones aren't
+    assert_eq!(fields["&name"], scalar("&String", "\"hello\""));
+
+    let Value::Struct { ty, fields } = resolve_variable!(debug_info, by_mut) else {
+        panic!("closure should be read as its captures");
+    };
+    let (line, column) = body_position("count += 1");
+    assert!(
+        ty.ends_with(&format!("{{closure@dynamic_tests.rs:{line}:{column}}}")),
+        "unexpected closure name: {ty}"
+    );
+    assert_eq!(fields["&count"], scalar("&u64", "4"));
+
+    let Value::Struct { fields, .. } = resolve_variable!(debug_info, by_move) else {
+        panic!("closure should be read as its captures");
+    };
+    assert_eq!(fields["moved"], scalar("String", "\"moved\""));
+
+    let Value::Struct { fields, .. } = resolve_variable!(debug_info, by_place) else {
+        panic!("closure should be read as its captures");
+    };
+    assert_eq!(fields["&point.1"], scalar("&u64", "2"));
+    assert_eq!(fields["&total__count"], scalar("&u64", "10"));
+
+    // the layout records how each variable was captured
+    let by_move_ptr = variable_pointer!(debug_info, by_move);
+    let by_ref_ptr = variable_pointer!(debug_info, by_ref);
+    for (pointer, expected) in [
+        (&by_move_ptr, CaptureMode::ByMove),
+        (&by_ref_ptr, CaptureMode::ByRef),
+    ] {
+        let Layout::Closure(closure) = pointer.type_def.layout.as_ref() else {
+            panic!("expected a closure layout");
+        };
+        assert_eq!(
+            closure.enclosing_function,
+            "dynamic_tests::test_introspect_closures"
+        );
+        assert_eq!(closure.captures.len(), 1);
+        assert_eq!(closure.captures[0].mode, expected);
+    }
+
+    assert_eq!(by_ref(1), 6);
+    assert_eq!(by_move(), 5);
+    assert_eq!(by_place(), 12);
+    Ok(())
+}
+
+#[test]
+fn test_introspect_tuples() -> Result<()> {
+    let (_guards, debug_info) = setup_db!();
+    let resolver = get_resolver(&debug_info);
+
+    let scalar = |ty: &str, value: &str| Value::Scalar {
+        ty: ty.to_string(),
+        value: value.to_string(),
+    };
+
+    let pair = (42u32, String::from("answer"));
+    let nested = ((1u8, -2i64), 'x');
+    let pair_ptr = variable_pointer!(debug_info, pair);
+    let nested_ptr = variable_pointer!(debug_info, nested);
+
+    assert_eq!(
+        resolve_variable!(debug_info, pair),
+        Value::Tuple {
+            ty: "(u32, String)".to_string(),
+            entries: vec![scalar("u32", "42"), scalar("String", "\"answer\"")],
+        }
+    );
+    assert_eq!(
+        resolve_variable!(debug_info, nested),
+        Value::Tuple {
+            ty: "((u8, i64), char)".to_string(),
+            entries: vec![
+                Value::Tuple {
+                    ty: "(u8, i64)".to_string(),
+                    entries: vec![scalar("u8", "1"), scalar("i64", "-2")],
+                },
+                scalar("char", "'x'"),
+            ],
+        }
+    );
+
+    // elements are accessed like fields, e.g. `pair.1` or `nested.0.1`
+    let second = debug_info.get_struct_field(pair_ptr.address, &pair_ptr.type_def, "1")?;
+    assert_eq!(
+        debug_info.read_value(&second, ReadOptions::unlimited(), &resolver)?,
+        scalar("String", "\"answer\"")
+    );
+    let inner = debug_info.get_struct_field(nested_ptr.address, &nested_ptr.type_def, "0")?;
+    let inner_second = debug_info.get_struct_field(inner.address, &inner.type_def, "1")?;
+    assert_eq!(
+        debug_info.read_pointer(&inner_second, &resolver)?,
+        scalar("i64", "-2")
+    );
+    assert!(
+        debug_info
+            .get_struct_field(pair_ptr.address, &pair_ptr.type_def, "2")
+            .is_err()
+    );
+
+    assert_eq!(pair.0, 42);
+    assert_eq!(nested.0.1, -2);
+    Ok(())
+}
+
+#[test]
+fn test_introspect_str_pointers() -> Result<()> {
+    let (_guards, debug_info) = setup_db!();
+    let resolver = get_resolver(&debug_info);
+
+    let mut owned = String::from("mutable");
+    let const_ptr: *const str = "raw const";
+    let mut_str: &mut str = owned.as_mut_str();
+    let mut_str_ptr = variable_pointer!(debug_info, mut_str);
+    let mut_ptr: *mut str = mut_str as *mut str;
+
+    for (pointer, expected) in [
+        (variable_pointer!(debug_info, const_ptr), "\"raw const\""),
+        (variable_pointer!(debug_info, mut_ptr), "\"mutable\""),
+        (mut_str_ptr, "\"mutable\""),
+    ] {
+        let Value::Scalar { value, .. } = debug_info.read_pointer(&pointer, &resolver)? else {
+            panic!(
+                "{} should be read as a string",
+                pointer.type_def.display_name()
+            );
+        };
+        assert_eq!(value, expected);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_read_value_limits() -> Result<()> {
+    let (_guards, debug_info) = setup_db!();
+    let resolver = get_resolver(&debug_info);
+
+    let big_vec: Vec<u64> = (0..10_000).collect();
+    let big_vec_ptr = variable_pointer!(debug_info, big_vec);
+    let long_string = "é".repeat(1000);
+    let long_string_ptr = variable_pointer!(debug_info, long_string);
+    let nested: Vec<Vec<u8>> = vec![vec![1, 2], vec![3]];
+    let nested_ptr = variable_pointer!(debug_info, nested);
+    let big_map: HashMap<u32, u32> = (0..1000).map(|i| (i, i * 2)).collect();
+    let big_map_ptr = variable_pointer!(debug_info, big_map);
+    let strings: Vec<String> = (0..20).map(|i| format!("string number {i}")).collect();
+    let strings_ptr = variable_pointer!(debug_info, strings);
+
+    let options = ReadOptions {
+        max_items: 10,
+        ..ReadOptions::default()
+    };
+
+    // only the first items are read, the rest are counted
+    let Value::Array { ty, items } = debug_info.read_value(&big_vec_ptr, options, &resolver)?
+    else {
+        panic!("expected an array");
+    };
+    assert_eq!(ty, "Vec<u64>");
+    assert_eq!(items.len(), 11);
+    assert_eq!(
+        items[9],
+        Value::Scalar {
+            ty: "u64".to_string(),
+            value: "9".to_string(),
+        }
+    );
+    assert_eq!(items[10], Value::Truncated { remaining: 9_990 });
+    assert_eq!(rudy_db::truncation_marker(9_990), "… 9,990 more");
+
+    // map entries are read bucket by bucket until we have enough
+    let Value::Map {
+        entries, truncated, ..
+    } = debug_info.read_value(&big_map_ptr, options, &resolver)?
+    else {
+        panic!("expected a map");
+    };
+    assert_eq!(entries.len(), 10);
+    assert_eq!(truncated, Some(990));
+    for (key, value) in &entries[..10] {
+        let (Value::Scalar { value: key, .. }, Value::Scalar { value, .. }) = (key, value) else {
+            panic!("expected scalar entries, got {key:?}: {value:?}");
+        };
+        assert_eq!(key.parse::<u32>()? * 2, value.parse::<u32>()?);
+    }
+
+    // strings are cut on a character boundary
+    let options = ReadOptions {
+        max_string_bytes: 5,
+        ..ReadOptions::default()
+    };
+    assert_eq!(
+        debug_info.read_value(&long_string_ptr, options, &resolver)?,
+        Value::Scalar {
+            ty: "String".to_string(),
+            value: "\"éé\"… 1,996 more bytes".to_string(),
+        }
+    );
+
+    // values nested too deep are left unread
+    let options = ReadOptions {
+        max_depth: 2,
+        ..ReadOptions::default()
+    };
+    let Value::Array { items, .. } = debug_info.read_value(&nested_ptr, options, &resolver)? else {
+        panic!("expected an array");
+    };
+    let Value::Array { ty, items } = &items[0] else {
+        panic!("expected the inner vec to be read, got {:?}", items[0]);
+    };
+    assert_eq!(ty, "Vec<u8>");
+    assert!(matches!(items[0], Value::Pointer(_)));
+    assert_eq!(
+        debug_info.read_value(&nested_ptr, ReadOptions::default(), &resolver)?,
+        Value::Array {
+            ty: "Vec<Vec<u8>>".to_string(),
+            items: vec![
+                Value::Array {
+                    ty: "Vec<u8>".to_string(),
+                    items: vec![
+                        Value::Scalar {
+                            ty: "u8".to_string(),
+                            value: "1".to_string(),
+                        },
+                        Value::Scalar {
+                            ty: "u8".to_string(),
+                            value: "2".to_string(),
+                        },
+                    ],
+                },
+                Value::Array {
+                    ty: "Vec<u8>".to_string(),
+                    items: vec![Value::Scalar {
+                        ty: "u8".to_string(),
+                        value: "3".to_string(),
+                    }],
+                },
+            ],
+        }
+    );
+
+    // once the memory budget is spent, the rest is left unread
+    let options = ReadOptions {
+        max_bytes_read: 200,
+        ..ReadOptions::default()
+    };
+    let Value::Array { items, .. } = debug_info.read_value(&strings_ptr, options, &resolver)?
+    else {
+        panic!("expected an array");
+    };
+    assert_eq!(items.len(), 20);
+    assert_eq!(
+        items[0],
+        Value::Scalar {
+            ty: "String".to_string(),
+            value: "\"string number 0\"".to_string(),
+        }
+    );
+    assert!(matches!(items[19], Value::Pointer(_)));
+
+    // values holding pointers, like the results of synthetic methods, are
+    // read the same way, with one budget for all of them
+    let pointers = Value::Tuple {
+        ty: "(&Vec<String>, &Vec<u64>)".to_string(),
+        entries: vec![
+            Value::Pointer(strings_ptr.clone()),
+            Value::Pointer(big_vec_ptr.clone()),
+        ],
+    };
+    let Value::Tuple { entries, .. } = debug_info.read_pointers(pointers, options, &resolver)?
+    else {
+        panic!("expected a tuple");
+    };
+    let Value::Array { items, .. } = &entries[0] else {
+        panic!("expected the strings to be read, got {:?}", entries[0]);
+    };
+    assert!(matches!(items[19], Value::Pointer(_)));
+    assert!(matches!(entries[1], Value::Pointer(_)));
+
+    assert_eq!(
+        big_vec.len() + big_map.len() + nested.len() + strings.len(),
+        11_022
+    );
+    assert_eq!(long_string.len(), 2000);
+    Ok(())
+}
+
+#[test]
+fn test_read_value_smart_pointer_str_limit() -> Result<()> {
+    let (_guards, debug_info) = setup_db!();
+    let resolver = get_resolver(&debug_info);
+
+    let boxed_str: Box<str> = "é".repeat(1000).into_boxed_str();
+    let boxed_str_ptr = variable_pointer!(debug_info, boxed_str);
+    let rc_str: std::rc::Rc<str> = "é".repeat(1000).into();
+    let rc_str_ptr = variable_pointer!(debug_info, rc_str);
+
+    // `str`s behind smart pointers are cut like `String`s
+    let options = ReadOptions {
+        max_string_bytes: 5,
+        ..ReadOptions::default()
+    };
+    for (pointer, ty) in [(&boxed_str_ptr, "Box<str>"), (&rc_str_ptr, "Rc<str>")] {
+        assert_eq!(
+            debug_info.read_value(pointer, options, &resolver)?,
+            Value::Scalar {
+                ty: ty.to_string(),
+                value: "\"éé\"… 1,996 more bytes".to_string(),
+            }
+        );
+    }
+
+    assert_eq!(boxed_str.len() + rc_str.len(), 4000);
+    Ok(())
+}
+
+/// Counts the reads, and bytes read, through another resolver
+struct CountingResolver<'a> {
+    inner: &'a dyn DataResolver,
+    bytes_read: std::cell::Cell<usize>,
+    reads: std::cell::Cell<usize>,
+    readability_checks: std::cell::Cell<usize>,
+}
+
+impl DataResolver for CountingResolver<'_> {
+    fn read_memory(&self, address: u64, size: usize) -> Result<Vec<u8>> {
+        self.bytes_read.set(self.bytes_read.get() + size);
+        self.reads.set(self.reads.get() + 1);
+        self.inner.read_memory(address, size)
+    }
+
+    fn write_memory(&self, address: u64, data: &[u8]) -> Result<()> {
+        self.inner.write_memory(address, data)
+    }
+
+    fn is_readable(&self, address: u64, size: usize) -> bool {
+        self.readability_checks
+            .set(self.readability_checks.get() + 1);
+        self.inner.is_readable(address, size)
+    }
+}
+
+#[test]
+fn test_children_pages() -> Result<()> {
+    let (_guards, debug_info) = setup_db!();
+    let resolver = get_resolver(&debug_info);
+
+    let big_vec: Vec<u64> = (0..10_000).collect();
+    let big_vec_ptr = variable_pointer!(debug_info, big_vec);
+    let mut deque: VecDeque<u32> = (0..8).collect();
+    deque.rotate_left(5);
+    let deque_ptr = variable_pointer!(debug_info, deque);
+    let list: LinkedList<u32> = (0..10).collect();
+    let list_ptr = variable_pointer!(debug_info, list);
+    let text = String::from("hello world");
+    let text_ptr = variable_pointer!(debug_info, text);
+    let text_ref: &String = &text;
+    let text_ref_ptr = variable_pointer!(debug_info, text_ref);
+    let boxed: Box<[u16]> = vec![7, 8, 9].into_boxed_slice();
+    let boxed_ptr = variable_pointer!(debug_info, boxed);
+    let hash_map: HashMap<u32, u32> = (0..1000).map(|i| (i, i * 2)).collect();
+    let hash_map_ptr = variable_pointer!(debug_info, hash_map);
+    let btree_map: BTreeMap<u64, u64> = (0..1000).map(|i| (i, i * 3)).collect();
+    let btree_map_ptr = variable_pointer!(debug_info, btree_map);
+
+    let read = |pointer: &TypedPointer| -> Result<String> {
+        match debug_info.read_pointer(pointer, &resolver)? {
+            Value::Scalar { value, .. } => Ok(value),
+            v => panic!("expected a scalar, got {v:?}"),
+        }
+    };
+    let items = |pointer: &TypedPointer, range| -> Result<(usize, Vec<String>)> {
+        let page = debug_info.children(pointer, range, &resolver)?;
+        let Children::Items(items) = page.children else {
+            panic!("expected items, got {:?}", page.children);
+        };
+        Ok((page.total, items.iter().map(read).collect::<Result<_>>()?))
+    };
+
+    assert_eq!(
+        items(&big_vec_ptr, 100..103)?,
+        (10_000, vec!["100".into(), "101".into(), "102".into()])
+    );
+    // ranges are clamped to the collection
+    assert_eq!(
+        items(&big_vec_ptr, 9_999..20_000)?,
+        (10_000, vec!["9999".into()])
+    );
+    assert_eq!(items(&big_vec_ptr, 20_000..20_001)?, (10_000, vec![]));
+    // in logical order, across the end of the ring buffer
+    assert_eq!(
+        items(&deque_ptr, 2..5)?,
+        (8, vec!["7".into(), "0".into(), "1".into()])
+    );
+    assert_eq!(items(&list_ptr, 8..12)?, (10, vec!["8".into(), "9".into()]));
+    assert_eq!(items(&boxed_ptr, 1..2)?, (3, vec!["8".into()]));
+
+    // strings are paged by bytes, including behind references
+    for pointer in [&text_ptr, &text_ref_ptr] {
+        let page = debug_info.children(pointer, 6..100, &resolver)?;
+        assert_eq!(page.total, 11);
+        assert_eq!(page.children, Children::Text("world".to_string()));
+    }
+
+    // paging through a map visits every entry once
+    let mut seen = BTreeMap::new();
+    for start in (0..1000).step_by(300) {
+        let page = debug_info.children(&hash_map_ptr, start..start + 300, &resolver)?;
+        assert_eq!(page.total, 1000);
+        let Children::Entries(entries) = page.children else {
+            panic!("expected map entries");
+        };
+        for (key, value) in entries {
+            seen.insert(read(&key)?.parse::<u32>()?, read(&value)?.parse::<u32>()?);
+        }
+    }
+    assert_eq!(seen.len(), 1000);
+    assert!(seen.iter().all(|(key, value)| key * 2 == *value));
+
+    // B-tree entries come in key order, and only the nodes up to the
+    // window are touched
+    let counting = CountingResolver {
+        inner: &resolver,
+        bytes_read: Default::default(),
+        reads: Default::default(),
+        readability_checks: Default::default(),
+    };
+    let page = debug_info.children(&btree_map_ptr, 500..502, &counting)?;
+    assert_eq!(page.total, 1000);
+    let Children::Entries(entries) = page.children else {
+        panic!("expected map entries");
+    };
+    let entries = entries
+        .iter()
+        .map(|(key, value)| Ok((read(key)?, read(value)?)))
+        .collect::<Result<Vec<_>>>()?;
+    assert_eq!(
+        entries,
+        vec![
+            ("500".to_string(), "1500".to_string()),
+            ("501".to_string(), "1503".to_string())
+        ]
+    );
+    let first_page_cost = counting.bytes_read.get();
+    counting.bytes_read.set(0);
+    debug_info.children(&btree_map_ptr, 0..2, &counting)?;
+    assert!(
+        counting.bytes_read.get() < first_page_cost,
+        "reading the first entries should touch fewer nodes"
+    );
+
+    assert!(
+        debug_info
+            .children(&variable_pointer!(debug_info, resolver), 0..1, &resolver)
+            .is_err()
+    );
+
+    assert_eq!(deque.len() + list.len() + boxed.len(), 21);
+    assert_eq!(big_vec.len() + hash_map.len() + btree_map.len(), 12_000);
+    Ok(())
+}
+
+#[test]
+fn test_hash_map_lookup() -> Result<()> {
+    let (_guards, debug_info) = setup_db!();
+    let resolver = get_resolver(&debug_info);
+
+    let numbers: HashMap<u64, u64> = (0..100_000).map(|i| (i, i * 2)).collect();
+    let numbers_ptr = variable_pointer!(debug_info, numbers);
+    let words: HashMap<String, u32> = (0..10_000).map(|i| (format!("key{i}"), i)).collect();
+    let words_ptr = variable_pointer!(debug_info, words);
+    let signed: HashMap<i16, u8> = (-100..100).map(|i| (i, (i + 100) as u8)).collect();
+    let signed_ptr = variable_pointer!(debug_info, signed);
+    let letters: HashMap<char, u32> = ('a'..='z').map(|c| (c, c as u32)).collect();
+    let letters_ptr = variable_pointer!(debug_info, letters);
+    let names: HashSet<&str> = ["alice", "bob", "carol"].into_iter().collect();
+    let names_ptr = variable_pointer!(debug_info, names);
+    let custom: HashMap<u32, u32, std::hash::BuildHasherDefault<std::hash::DefaultHasher>> =
+        (0..100).map(|i| (i, i + 1)).collect();
+    let custom_ptr = variable_pointer!(debug_info, custom);
+
+    let counting = CountingResolver {
+        inner: &resolver,
+        bytes_read: Default::default(),
+        reads: Default::default(),
+        readability_checks: Default::default(),
+    };
+    let lookup = |map: &TypedPointer, key: &str| -> Result<String> {
+        let key = Value::Scalar {
+            ty: "String".to_string(),
+            value: key.to_string(),
+        };
+        let value = debug_info.index_map(map.address, &map.type_def, &key, &counting)?;
+        match debug_info.read_pointer(&value, &resolver)? {
+            Value::Scalar { value, .. } => Ok(value),
+            v => panic!("expected a scalar, got {v:?}"),
+        }
+    };
+
+    assert_eq!(lookup(&numbers_ptr, "12345")?, "24690");
+    assert_eq!(lookup(&numbers_ptr, "99999")?, "199998");
+    assert!(
+        counting.bytes_read.get() < 1024,
+        "hashed lookups should only read a few buckets, read {} bytes",
+        counting.bytes_read.get()
+    );
+    counting.bytes_read.set(0);
+    assert!(lookup(&numbers_ptr, "100000").is_err());
+    assert!(counting.bytes_read.get() < 1024);
+
+    counting.bytes_read.set(0);
+    assert_eq!(lookup(&words_ptr, "key4242")?, "4242");
+    assert!(counting.bytes_read.get() < 1024);
+    assert!(lookup(&words_ptr, "key10000").is_err());
+    assert_eq!(lookup(&signed_ptr, "-7")?, "93");
+    assert_eq!(lookup(&letters_ptr, "'q'")?, "113");
+
+    let needle = |name: &str| Value::Scalar {
+        ty: "&str".to_string(),
+        value: format!("\"{name}\""),
+    };
+    for (name, expected) in [("bob", "true"), ("dave", "false")] {
+        let found = debug_info.evaluate_synthetic_method(
+            &names_ptr,
+            "contains",
+            &[needle(name)],
+            &counting,
+        )?;
+        assert_eq!(
+            found,
+            Value::Scalar {
+                ty: "bool".to_string(),
+                value: expected.to_string()
+            }
+        );
+    }
+
+    // maps with other hashers fall back to comparing every key
+    assert_eq!(lookup(&custom_ptr, "42")?, "43");
+
+    assert_eq!(
+        numbers.len() + words.len() + signed.len() + letters.len(),
+        110_226
+    );
+    assert_eq!(names.len() + custom.len(), 103);
+    Ok(())
+}
+
+#[test]
+fn test_index_map_lookup() -> Result<()> {
+    let (_guards, debug_info) = setup_db!();
+    let resolver = get_resolver(&debug_info);
+
+    let counting = CountingResolver {
+        inner: &resolver,
+        bytes_read: Default::default(),
+        reads: Default::default(),
+        readability_checks: Default::default(),
+    };
+    let lookup = |map: &TypedPointer, key: &str| -> Result<Value> {
+        let key = Value::Scalar {
+            ty: "String".to_string(),
+            value: key.to_string(),
+        };
+        let value = debug_info.index_map(map.address, &map.type_def, &key, &counting)?;
+        debug_info.read_pointer(&value, &resolver)
+    };
+    let scalar = |ty: &str, value: &str| Value::Scalar {
+        ty: ty.to_string(),
+        value: value.to_string(),
+    };
+
+    // built without closures, which could be found at the lines below
+    let indexed: IndexMap<u64, u64> = (0..100_000).zip((0..300_000).step_by(3)).collect();
+    let indexed_ptr = variable_pointer!(debug_info, indexed);
+    let mut tags: IndexSet<String> = IndexSet::new();
+    for i in 0..1_000 {
+        tags.insert(format!("tag{i}"));
+    }
+    let tags_ptr = variable_pointer!(debug_info, tags);
+    let custom: IndexMap<u32, u32, std::hash::BuildHasherDefault<std::hash::DefaultHasher>> =
+        (0..100).zip(1..101).collect();
+    let custom_ptr = variable_pointer!(debug_info, custom);
+
+    assert_eq!(lookup(&indexed_ptr, "54321")?, scalar("u64", "162963"));
+    assert!(
+        counting.bytes_read.get() < 1024,
+        "hashed lookups should only read a few buckets, read {} bytes",
+        counting.bytes_read.get()
+    );
+    counting.bytes_read.set(0);
+    assert!(lookup(&indexed_ptr, "100000").is_err());
+    assert!(counting.bytes_read.get() < 1024);
+
+    counting.bytes_read.set(0);
+    assert_eq!(lookup(&tags_ptr, "tag777")?, scalar("String", "\"tag777\""));
+    assert!(counting.bytes_read.get() < 1024);
+
+    // maps with other hashers fall back to comparing every key
+    assert_eq!(lookup(&custom_ptr, "42")?, scalar("u32", "43"));
+
+    assert_eq!(indexed.len() + tags.len() + custom.len(), 101_100);
+    Ok(())
+}
+
+#[test]
+fn test_btree_map_lookup() -> Result<()> {
+    let (_guards, debug_info) = setup_db!();
+    let resolver = get_resolver(&debug_info);
+
+    let index: BTreeMap<u64, u64> = (0..100_000).map(|i| (i * 10, i)).collect();
+    let index_ptr = variable_pointer!(debug_info, index);
+    let words: BTreeMap<String, u32> = (0..5_000).map(|i| (format!("key{i:05}"), i)).collect();
+    let words_ptr = variable_pointer!(debug_info, words);
+    let signed: BTreeSet<i32> = (-500..500).collect();
+    let signed_ptr = variable_pointer!(debug_info, signed);
+    let empty: BTreeMap<u64, u64> = BTreeMap::new();
+    let empty_ptr = variable_pointer!(debug_info, empty);
+
+    let counting = CountingResolver {
+        inner: &resolver,
+        bytes_read: Default::default(),
+        reads: Default::default(),
+        readability_checks: Default::default(),
+    };
+    let scalar = |ty: &str, value: &str| Value::Scalar {
+        ty: ty.to_string(),
+        value: value.to_string(),
+    };
+    let read = |value: &Value| -> Result<String> {
+        let Value::Pointer(pointer) = value else {
+            panic!("expected a pointer, got {value:?}");
+        };
+        match debug_info.read_pointer(pointer, &resolver)? {
+            Value::Scalar { value, .. } => Ok(value),
+            v => panic!("expected a scalar, got {v:?}"),
+        }
+    };
+    let lookup = |map: &TypedPointer, key: &str| -> Result<String> {
+        let value = debug_info.index_map(
+            map.address,
+            &map.type_def,
+            &scalar("String", key),
+            &counting,
+        )?;
+        read(&Value::Pointer(value))
+    };
+
+    assert_eq!(lookup(&index_ptr, "12340")?, "1234");
+    assert_eq!(lookup(&index_ptr, "999990")?, "99999");
+    assert!(lookup(&index_ptr, "12345").is_err());
+    assert!(
+        counting.bytes_read.get() < 2048,
+        "searching the tree should only read a few nodes, read {} bytes",
+        counting.bytes_read.get()
+    );
+    counting.bytes_read.set(0);
+    assert_eq!(lookup(&words_ptr, "key04242")?, "4242");
+    assert!(counting.bytes_read.get() < 2048);
+    assert!(lookup(&words_ptr, "key5000").is_err());
+
+    for (needle, expected) in [("-5", "true"), ("500", "false")] {
+        let found = debug_info.evaluate_synthetic_method(
+            &signed_ptr,
+            "contains",
+            &[scalar("i32", needle)],
+            &counting,
+        )?;
+        assert_eq!(found, scalar("bool", expected));
+    }
+
+    let end_entry = |map: &TypedPointer, method: &str| -> Result<Option<(String, String)>> {
+        let value = debug_info.evaluate_synthetic_method(map, method, &[], &counting)?;
+        let Value::Enum { payload, .. } = value else {
+            panic!("expected an Option, got {value:?}");
+        };
+        payload
+            .map(|payload| {
+                let Value::Tuple { entries, .. } = *payload else {
+                    panic!("expected a (key, value) tuple, got {payload:?}");
+                };
+                Ok((read(&entries[0])?, read(&entries[1])?))
+            })
+            .transpose()
+    };
+    counting.bytes_read.set(0);
+    assert_eq!(
+        end_entry(&index_ptr, "first_key_value")?,
+        Some(("0".to_string(), "0".to_string()))
+    );
+    assert_eq!(
+        end_entry(&index_ptr, "last_key_value")?,
+        Some(("999990".to_string(), "99999".to_string()))
+    );
+    assert!(counting.bytes_read.get() < 1024);
+    assert_eq!(end_entry(&empty_ptr, "first_key_value")?, None);
+
+    // the keys in the range, and how many were left out
+    let range = |map: &TypedPointer,
+                 start: Option<&str>,
+                 end: Option<&str>|
+     -> Result<(Vec<Value>, Option<usize>)> {
+        let mut fields = std::collections::BTreeMap::new();
+        if let Some(start) = start {
+            fields.insert("start".to_string(), scalar("u64", start));
+        }
+        if let Some(end) = end {
+            fields.insert("end".to_string(), scalar("u64", end));
+        }
+        let range = Value::Struct {
+            ty: "Range".to_string(),
+            fields,
+        };
+        match debug_info.evaluate_synthetic_method(map, "range", &[range], &counting)? {
+            Value::Map {
+                entries, truncated, ..
+            } => Ok((entries.into_iter().map(|(k, _)| k).collect(), truncated)),
+            v => panic!("expected a map, got {v:?}"),
+        }
+    };
+    let keys = |entries: Vec<Value>| entries.iter().map(read).collect::<Result<Vec<_>>>();
+
+    counting.bytes_read.set(0);
+    assert_eq!(
+        keys(range(&index_ptr, Some("100"), Some("150"))?.0)?,
+        ["100", "110", "120", "130", "140"]
+    );
+    assert!(counting.bytes_read.get() < 2048);
+    assert_eq!(
+        keys(range(&index_ptr, Some("999955"), None)?.0)?,
+        ["999960", "999970", "999980", "999990"]
+    );
+    assert_eq!(range(&index_ptr, Some("150"), Some("100"))?, (vec![], None));
+
+    let (all, truncated) = range(&index_ptr, None, None)?;
+    assert_eq!(truncated, Some(99_900));
+    assert_eq!(all.len(), 100);
+    assert_eq!(read(&all[99])?, "990");
+    let (bounded, truncated) = range(&index_ptr, Some("100"), Some("500000"))?;
+    assert_eq!(truncated, Some(49_890));
+    assert_eq!(read(&bounded[99])?, "1090");
+
+    // callers can ask for more or fewer entries
+    let few = ReadOptions {
+        max_items: 3,
+        ..Default::default()
+    };
+    let range_struct = Value::Struct {
+        ty: "Range".to_string(),
+        fields: [("start".to_string(), scalar("u64", "100"))].into(),
+    };
+    let Value::Map {
+        entries, truncated, ..
+    } = debug_info.evaluate_synthetic_method_with_options(
+        &index_ptr,
+        "range",
+        &[range_struct],
+        few,
+        &counting,
+    )?
+    else {
+        panic!("expected a map");
+    };
+    assert_eq!(entries.len(), 3);
+    assert_eq!(truncated, Some(99_987));
+
+    assert_eq!(
+        index.len() + words.len() + signed.len() + empty.len(),
+        106_000
+    );
+    Ok(())
+}
+
+#[test]
+fn test_write_values() -> Result<()> {
+    let (_guards, debug_info) = setup_db!();
+    let resolver = get_resolver(&debug_info);
+
+    let mut small: i8 = 1;
+    let small_ptr = variable_pointer!(debug_info, small);
+    let mut big: u64 = 0;
+    let big_ptr = variable_pointer!(debug_info, big);
+    let mut ratio: f64 = 0.0;
+    let ratio_ptr = variable_pointer!(debug_info, ratio);
+    let mut scale: f32 = 1.0;
+    let scale_ptr = variable_pointer!(debug_info, scale);
+    let mut flag = false;
+    let flag_ptr = variable_pointer!(debug_info, flag);
+    let mut letter = 'a';
+    let letter_ptr = variable_pointer!(debug_info, letter);
+    let mut level = U8Enum::First;
+    let level_ptr = variable_pointer!(debug_info, level);
+    let mut pair: (u8, i32) = (0, 0);
+    let pair_ptr = variable_pointer!(debug_info, pair);
+    let mut maybe: Option<u32> = None;
+    let maybe_ptr = variable_pointer!(debug_info, maybe);
+    let target = 7u32;
+    let mut maybe_ref: Option<&u32> = Some(&target);
+    let maybe_ref_ptr = variable_pointer!(debug_info, maybe_ref);
+    let mut non_zero = std::num::NonZeroU32::new(1);
+    let non_zero_ptr = variable_pointer!(debug_info, non_zero);
+    let mut maybe_flag = Some(true);
+    let maybe_flag_ptr = variable_pointer!(debug_info, maybe_flag);
+    let mut greeting = String::from("before");
+    let greeting_ptr = variable_pointer!(debug_info, greeting);
+    // the writes below happen behind the compiler's back, so make sure it
+    // treats these as mutated by code it can't see
+    std::hint::black_box((
+        &mut small,
+        &mut big,
+        &mut ratio,
+        &mut scale,
+        &mut flag,
+        &mut letter,
+        &mut level,
+        &mut pair,
+        &mut maybe,
+        &mut maybe_ref,
+        &mut non_zero,
+        &mut maybe_flag,
+        &mut greeting,
+    ));
+
+    let write = |pointer: &TypedPointer, literal: &str| {
+        debug_info.write_literal(pointer, literal, &resolver)
+    };
+
+    write(&small_ptr, "-128")?;
+    assert_eq!(unsafe { std::ptr::read_volatile(&small) }, -128);
+    write(&small_ptr, "0x7f")?;
+    assert_eq!(unsafe { std::ptr::read_volatile(&small) }, 127);
+    let error = write(&small_ptr, "128").unwrap_err().to_string();
+    assert_eq!(error, "128 is out of range for i8 (-128..=127)");
+    assert_eq!(unsafe { std::ptr::read_volatile(&small) }, 127);
+    assert!(write(&small_ptr, "seven").is_err());
+
+    write(&big_ptr, "18_446_744_073_709_551_615")?;
+    assert_eq!(unsafe { std::ptr::read_volatile(&big) }, u64::MAX);
+    let error = write(&big_ptr, "-1").unwrap_err().to_string();
+    assert_eq!(
+        error,
+        "-1 is out of range for u64 (0..=18446744073709551615)"
+    );
+
+    write(&ratio_ptr, "2.5")?;
+    assert_eq!(unsafe { std::ptr::read_volatile(&ratio) }, 2.5);
+    let error = write(&scale_ptr, "1e40").unwrap_err().to_string();
+    assert_eq!(error, "1e40 is out of range for f32");
+    assert_eq!(unsafe { std::ptr::read_volatile(&scale) }, 1.0);
+    write(&scale_ptr, "-inf")?;
+    assert_eq!(
+        unsafe { std::ptr::read_volatile(&scale) },
+        f32::NEG_INFINITY
+    );
+    write(&flag_ptr, "true")?;
+    assert!(unsafe { std::ptr::read_volatile(&flag) });
+    write(&letter_ptr, "'z'")?;
+    assert_eq!(unsafe { std::ptr::read_volatile(&letter) }, 'z');
+
+    write(&level_ptr, "Fifth")?;
+    assert_eq!(unsafe { std::ptr::read_volatile(&level) }, U8Enum::Fifth);
+    write(&level_ptr, "U8Enum::Second")?;
+    assert_eq!(unsafe { std::ptr::read_volatile(&level) }, U8Enum::Second);
+    let error = write(&level_ptr, "Fourth").unwrap_err().to_string();
+    assert!(error.contains("First, Second, Third, Fifth"), "{error}");
+    assert!(write(&level_ptr, "Other::Third").is_err());
+    assert_eq!(unsafe { std::ptr::read_volatile(&level) }, U8Enum::Second);
+
+    write(&pair_ptr, "(200, -5)")?;
+    assert_eq!(unsafe { std::ptr::read_volatile(&pair) }, (200, -5));
+    // nothing is written if any element fails to encode
+    assert!(write(&pair_ptr, "(1, 1.5)").is_err());
+    assert_eq!(unsafe { std::ptr::read_volatile(&pair) }, (200, -5));
+
+    write(&maybe_ptr, "Some(42)")?;
+    assert_eq!(unsafe { std::ptr::read_volatile(&maybe) }, Some(42));
+    write(&maybe_ptr, "None")?;
+    assert_eq!(unsafe { std::ptr::read_volatile(&maybe) }, None);
+
+    write(&maybe_ref_ptr, "None")?;
+    assert_eq!(unsafe { std::ptr::read_volatile(&maybe_ref) }, None);
+    assert!(write(&maybe_ref_ptr, "Some(0)").is_err());
+    write(
+        &maybe_ref_ptr,
+        &format!("Some({:#x})", &target as *const u32 as u64),
+    )?;
+    assert_eq!(unsafe { std::ptr::read_volatile(&maybe_ref) }, Some(&7));
+
+    write(&non_zero_ptr, "Some(9)")?;
+    assert_eq!(
+        unsafe { std::ptr::read_volatile(&non_zero) }.map(|n| n.get()),
+        Some(9)
+    );
+    assert!(write(&non_zero_ptr, "Some(0)").is_err());
+    write(&non_zero_ptr, "None")?;
+    assert_eq!(unsafe { std::ptr::read_volatile(&non_zero) }, None);
+
+    // `None` is an invalid bool, whose value the debug info doesn't give
+    let error = write(&maybe_flag_ptr, "None").unwrap_err().to_string();
+    assert!(error.contains("can only be written"), "{error}");
+    assert_eq!(maybe_flag, Some(true));
+
+    let error = write(&greeting_ptr, "\"after\"").unwrap_err().to_string();
+    assert!(error.contains("allocate memory"), "{error}");
+    assert_eq!(greeting, "before");
 
-    // Keep data alive
-    let _ = (test_some, test_none);
     Ok(())
 }
 
 #[test]
-fn test_introspect_hashmap() -> Result<()> {
+fn test_caching_resolver() -> Result<()> {
     let (_guards, debug_info) = setup_db!();
+    let resolver = get_resolver(&debug_info);
 
-    // Create test data
-    let mut test_map = HashMap::new();
-    test_map.insert("one".to_string(), 1);
-    test_map.insert("two".to_string(), 2);
-    test_map.insert("three".to_string(), 3);
+    let names: Vec<String> = (0..20).map(|i| format!("name{i}")).collect();
+    let names_ptr = variable_pointer!(debug_info, names);
+    let mut counter: u64 = 1;
+    let counter_ptr = variable_pointer!(debug_info, counter);
+    // written behind the compiler's back below
+    std::hint::black_box(&mut counter);
 
-    let value = resolve_variable!(debug_info, test_map);
-    let Value::Map { ty, mut entries } = value else {
-        panic!("Expected a Map value, got: {value:?}");
+    let read_names = |resolver: &dyn DataResolver| -> Result<Value> {
+        debug_info.read_value(&names_ptr, ReadOptions::unlimited(), resolver)
     };
-    assert_eq!(ty, "HashMap<String, i32>");
-    assert_eq!(entries.len(), 3);
-    entries.sort();
-    insta::assert_debug_snapshot!(entries);
 
-    // Keep map alive
-    let _ = test_map;
+    let uncached = CountingResolver {
+        inner: &resolver,
+        bytes_read: Default::default(),
+        reads: Default::default(),
+        readability_checks: Default::default(),
+    };
+    let expected = read_names(&uncached)?;
+
+    let caching = CachingResolver::new(CountingResolver {
+        inner: &resolver,
+        bytes_read: Default::default(),
+        reads: Default::default(),
+        readability_checks: Default::default(),
+    });
+    assert_eq!(read_names(&caching)?, expected);
+    // each page is only fetched once, however many reads it serves
+    let reads = caching.inner().reads.get();
+    assert_eq!(reads, caching.cache().cached_pages());
+    assert!(
+        reads < uncached.reads.get(),
+        "expected fewer reads through the cache: {reads} vs {}",
+        uncached.reads.get()
+    );
+
+    // reading again is served entirely from the cache
+    let checks = caching.inner().readability_checks.get();
+    assert_eq!(read_names(&caching)?, expected);
+    assert_eq!(caching.inner().reads.get(), reads);
+    // including whether the buffers are readable
+    assert_eq!(caching.inner().readability_checks.get(), checks);
+
+    // readability is remembered per page, without reading the pages
+    let checking = CachingResolver::new(CountingResolver {
+        inner: &resolver,
+        bytes_read: Default::default(),
+        reads: Default::default(),
+        readability_checks: Default::default(),
+    });
+    let address = &counter as *const u64 as u64;
+    assert!(checking.is_readable(address, 8));
+    assert!(checking.is_readable(address, 4));
+    assert_eq!(checking.inner().readability_checks.get(), 1);
+    assert_eq!(checking.cache().cached_pages(), 0);
+
+    // writes go through, and update the cached pages
+    let read_counter = || -> Result<String> {
+        match debug_info.read_pointer(&counter_ptr, &caching)? {
+            Value::Scalar { value, .. } => Ok(value),
+            v => panic!("expected a scalar, got {v:?}"),
+        }
+    };
+    assert_eq!(read_counter()?, "1");
+    debug_info.write_literal(&counter_ptr, "42", &caching)?;
+    assert_eq!(unsafe { std::ptr::read_volatile(&counter) }, 42);
+    assert_eq!(read_counter()?, "42");
+
+    // changes made while "running" are only seen once the cache is invalidated
+    unsafe { std::ptr::write_volatile(&mut counter, 7) };
+    assert_eq!(read_counter()?, "42");
+    caching.invalidate();
+    assert_eq!(caching.cache().cached_pages(), 0);
+    assert_eq!(read_counter()?, "7");
+
     Ok(())
 }
 
-#[test]
-fn test_introspect_btreemap() -> Result<()> {
-    let (_guards, debug_info) = setup_db!();
+/// Forwards to another resolver, but claims no memory is mapped
+struct UnmappedResolver<'a>(&'a dyn DataResolver);
 
-    // Create test data
-    let mut test_map = BTreeMap::new();
-    test_map.insert("one".to_string(), 1);
-    test_map.insert("two".to_string(), 2);
-    test_map.insert("three".to_string(), 3);
+impl DataResolver for UnmappedResolver<'_> {
+    fn read_memory(&self, address: u64, size: usize) -> Result<Vec<u8>> {
+        self.0.read_memory(address, size)
+    }
 
-    let value = resolve_variable!(debug_info, test_map);
-    insta::assert_debug_snapshot!(value);
+    fn get_register(&self, idx: usize) -> Result<u64> {
+        self.0.get_register(idx)
+    }
 
-    // Keep map alive
-    let _ = test_map;
-    Ok(())
+    fn get_stack_pointer(&self) -> Result<u64> {
+        self.0.get_stack_pointer()
+    }
+
+    fn is_readable(&self, _address: u64, _size: usize) -> bool {
+        false
+    }
 }
 
 #[test]
-fn test_introspect_complex_nested_types() {
+fn test_corrupt_values() -> Result<()> {
     let (_guards, debug_info) = setup_db!();
+    let resolver = get_resolver(&debug_info);
 
-    // Create complex nested data - NOTE: This contains Vec and HashMap which are not implemented yet
-    let mut metadata = BTreeMap::new();
-    metadata.insert("version".to_string(), "1.0".to_string());
-    metadata.insert("author".to_string(), "test".to_string());
-
-    let test_data = TestComplexData {
-        id: 12345,
-        values: vec![100, 200, 300],
-        metadata,
-        location: TestPoint {
-            x: 3.14001,
-            y: 2.71,
-        },
+    // reinterprets `bytes` as a value of the type `like` points to
+    let at = |bytes: *const u8, like: &TypedPointer| TypedPointer {
+        address: bytes as u64,
+        type_def: like.type_def.clone(),
+    };
+    let reason = |value: Value| match value {
+        Value::Invalid { reason, .. } => reason,
+        v => panic!("expected an invalid value, got {v:?}"),
     };
 
-    let value = resolve_variable!(debug_info, test_data);
+    let mut numbers: Vec<u64> = Vec::with_capacity(5);
+    numbers.extend([1, 2, 3]);
+    let numbers_ptr = variable_pointer!(debug_info, numbers);
+    let flag = true;
+    let flag_ptr = variable_pointer!(debug_info, flag);
+    let letter = 'x';
+    let letter_ptr = variable_pointer!(debug_info, letter);
+    let text = String::from("hello");
+    let text_ptr = variable_pointer!(debug_info, text);
+    let pair: (u64, Vec<u64>) = (7, {
+        let mut numbers = Vec::with_capacity(5);
+        numbers.extend([1, 2, 3]);
+        numbers
+    });
+    let pair_ptr = variable_pointer!(debug_info, pair);
+    // a fixed hasher keeps the table's header the only words in the map
+    type FixedState = std::hash::BuildHasherDefault<std::hash::DefaultHasher>;
+    let mut table: HashMap<u64, u64, FixedState> =
+        HashMap::with_capacity_and_hasher(8, FixedState::default());
+    table.extend([(1, 10), (2, 20), (3, 30)]);
+    let table_ptr = variable_pointer!(debug_info, table);
+
+    // a vec whose length exceeds its capacity, found by their values
+    let mut corrupt_numbers: [usize; 3] = unsafe { std::mem::transmute_copy(&numbers) };
+    let length = corrupt_numbers.iter().position(|&w| w == 3).unwrap();
+    corrupt_numbers[length] = 1 << 40;
+    let value = debug_info.read_pointer(
+        &at(corrupt_numbers.as_ptr().cast(), &numbers_ptr),
+        &resolver,
+    )?;
+    assert_eq!(reason(value), "length 1099511627776 exceeds capacity 5");
+
+    let bad_bool: u8 = 7;
+    let value = debug_info.read_pointer(&at(&bad_bool, &flag_ptr), &resolver)?;
+    assert_eq!(reason(value), "0x07 is not a valid bool");
+
+    let surrogate: u32 = 0xD800;
+    let value = debug_info.read_pointer(
+        &at((&surrogate as *const u32).cast(), &letter_ptr),
+        &resolver,
+    )?;
+    assert_eq!(reason(value), "0xd800 is not a valid char");
+
+    // a `String` is a `Vec<u8>` that's known to hold UTF-8
+    let not_utf8: Vec<u8> = vec![b'h', 0xff, b'i'];
+    let value = debug_info.read_pointer(
+        &at((&not_utf8 as *const Vec<u8>).cast(), &text_ptr),
+        &resolver,
+    )?;
     insta::assert_debug_snapshot!(value);
 
-    // Keep data alive
-    let _ = test_data;
+    // corruption inside a value only invalidates that part of it
+    let mut corrupt_pair: [usize; 4] = unsafe { std::mem::transmute_copy(&pair) };
+    let length = corrupt_pair.iter().position(|&w| w == 3).unwrap();
+    corrupt_pair[length] = 1 << 40;
+    let corrupt_pair_ptr = at(corrupt_pair.as_ptr().cast(), &pair_ptr);
+    let value = debug_info.read_value(&corrupt_pair_ptr, ReadOptions::default(), &resolver)?;
+    insta::assert_debug_snapshot!(value);
+
+    // hash tables with an impossible header: 16 buckets hold 3 items, and
+    // the control bytes pointer is the only other large word
+    let words: [usize; 4] = unsafe { std::mem::transmute_copy(&table) };
+    let mask = words.iter().position(|&w| w == 15).unwrap();
+    let items = words.iter().position(|&w| w == 3).unwrap();
+    let ctrl = words.iter().position(|&w| w > 15).unwrap();
+    let read_table = |index: usize, word: usize| {
+        let mut corrupt = words;
+        corrupt[index] = word;
+        let value = debug_info.read_pointer(&at(corrupt.as_ptr().cast(), &table_ptr), &resolver);
+        reason(value.unwrap())
+    };
+    assert_eq!(
+        read_table(mask, usize::MAX),
+        "bucket mask 0xffffffffffffffff is not one less than a power of two"
+    );
+    assert_eq!(
+        read_table(items, 1 << 40),
+        "1099511627776 items don't fit in 16 buckets"
+    );
+    assert_eq!(read_table(ctrl, 0), "null control bytes with 3 items");
+    assert_eq!(
+        read_table(ctrl, 16),
+        "16 buckets of 16 bytes don't fit before 0x10"
+    );
+    let misaligned = read_table(ctrl, words[ctrl] + 1);
+    assert!(misaligned.contains("are not aligned"), "{misaligned}");
+
+    // buffers the resolver says aren't mapped aren't read at all
+    let value = debug_info.read_pointer(&numbers_ptr, &UnmappedResolver(&resolver))?;
+    let reason = reason(value);
+    assert!(reason.ends_with("are not readable"), "{reason}");
+
+    std::hint::black_box((&numbers, &flag, &letter, &text, &pair, &table));
+    Ok(())
 }
 
 #[test]
-fn test_introspect_smart_pointers() -> Result<()> {
+fn test_serialize_values() -> Result<()> {
     let (_guards, debug_info) = setup_db!();
+    let resolver = get_resolver(&debug_info);
 
-    // Create test data with smart pointers
-    let test_box: Box<String> = Box::new(String::from("Boxed string"));
-    let test_arc: Arc<Vec<i32>> = Arc::new(vec![1, 2, 3]);
-    let test_rc: std::rc::Rc<i32> = std::rc::Rc::new(42);
-    let test_mutex: std::sync::Mutex<i32> = std::sync::Mutex::new(42);
-    let test_cell: std::cell::RefCell<i32> = std::cell::RefCell::new(100);
+    let inventory: BTreeMap<u8, Option<Vec<u8>>> = (1..=4)
+        .map(|i| (i, (i % 2 == 1).then(|| (0..i * 2).collect())))
+        .collect();
+    let inventory_ptr = variable_pointer!(debug_info, inventory);
+    let score: u64 = 7;
+    let score_ptr = variable_pointer!(debug_info, score);
+    let session = TestSession::new(7, "serialized".to_string());
+    let _len = session.description_length(); // Call to ensure method is compiled in
+    let session_ptr = variable_pointer!(debug_info, session);
+
+    let options = ReadOptions {
+        max_items: 3,
+        ..ReadOptions::default()
+    };
+    let value = debug_info.read_value(&inventory_ptr, options, &resolver)?;
+    insta::assert_snapshot!(serde_json::to_string_pretty(&value)?);
 
-    let box_value = resolve_variable!(debug_info, test_box);
-    insta::assert_debug_snapshot!(box_value);
-    let arc_value = resolve_variable!(debug_info, test_arc);
-    insta::assert_debug_snapshot!(arc_value);
-    let rc_value = resolve_variable!(debug_info, test_rc);
-    insta::assert_debug_snapshot!(rc_value);
-    let mutex_value = resolve_variable!(debug_info, test_mutex);
-    insta::assert_debug_snapshot!(mutex_value);
-    let cell_value = resolve_variable!(debug_info, test_cell);
-    insta::assert_debug_snapshot!(cell_value);
+    let address = format!("{:#x}", score_ptr.address);
+    assert_eq!(
+        serde_json::to_value(&score_ptr)?,
+        serde_json::json!({ "type": "u64", "address": address })
+    );
+    assert_eq!(
+        serde_json::to_value(Value::Pointer(score_ptr.clone()))?,
+        serde_json::json!({ "kind": "pointer", "type": "u64", "address": address })
+    );
 
-    // Keep data alive
-    let _ = (test_box, test_arc, test_rc, test_mutex, test_cell);
+    let methods = debug_info.discover_methods_for_pointer(&session_ptr)?;
+    let method = methods
+        .iter()
+        .find(|m| m.name == "description_length")
+        .expect("Should find description_length() method");
+    let method = serde_json::to_value(method)?;
+    assert_eq!(method["self_type"], "Borrowed");
+    assert_eq!(method["return_type"], "u32");
+    assert_eq!(method["parameters"], serde_json::json!([]));
+    assert!(method["address"].as_str().unwrap().starts_with("0x"));
+
+    std::hint::black_box((&inventory, &score, &session));
     Ok(())
 }
 
@@ -422,6 +2444,71 @@ fn test_introspect_enums() {
     );
 }
 
+#[test]
+fn test_enum_map_keys() -> Result<()> {
+    let (_guards, debug_info) = setup_db!();
+    let resolver = get_resolver(&debug_info);
+
+    let enum_map = HashMap::from([(U8Enum::First, 1u32), (U8Enum::Fifth, 5)]);
+    let map_ptr = variable_pointer!(debug_info, enum_map);
+
+    let Value::Map { entries, .. } = resolve_variable!(debug_info, enum_map) else {
+        panic!("expected a map");
+    };
+    assert!(entries.iter().any(|(key, _)| key
+        == &Value::Enum {
+            ty: "U8Enum".to_string(),
+            variant: "Fifth".to_string(),
+            discriminant: 5,
+            payload: None,
+        }));
+
+    // unit variants can be looked up by name, qualified or not
+    let key = |name: &str| Value::Scalar {
+        ty: "String".to_string(),
+        value: name.to_string(),
+    };
+    for (name, expected) in [
+        ("Fifth", "5"),
+        ("U8Enum::First", "1"),
+        ("common::definitions::U8Enum::First", "1"),
+    ] {
+        let value_ptr =
+            debug_info.index_map(map_ptr.address, &map_ptr.type_def, &key(name), &resolver)?;
+        assert_eq!(
+            debug_info.read_pointer(&value_ptr, &resolver)?,
+            Value::Scalar {
+                ty: "u32".to_string(),
+                value: expected.to_string(),
+            }
+        );
+    }
+    assert!(
+        debug_info
+            .index_map(
+                map_ptr.address,
+                &map_ptr.type_def,
+                &key("Second"),
+                &resolver
+            )
+            .is_err()
+    );
+    // a path must be that of the key's enum
+    assert!(
+        debug_info
+            .index_map(
+                map_ptr.address,
+                &map_ptr.type_def,
+                &key("Other::Fifth"),
+                &resolver
+            )
+            .is_err()
+    );
+
+    let _ = enum_map;
+    Ok(())
+}
+
 #[test]
 fn test_real_method_execution() -> Result<()> {
     let (_guards, debug_info) = setup_db!();
@@ -679,11 +2766,34 @@ fn test_synthetic_methods() -> Result<()> {
         }
     );
 
-    // TODO: Test Result synthetic methods
-    // Skipping Result tests for now since Result layout reading isn't fully implemented
+    // Test Result synthetic methods
     let ok_result: Result<i32, String> = Ok(42);
     let err_result: Result<i32, String> = Err("error".to_string());
 
+    let ok_ptr = variable_pointer!(debug_info, ok_result);
+    let err_ptr = variable_pointer!(debug_info, err_result);
+    let result_type = ok_ptr.type_def;
+    let ok_ptr = ok_ptr.address;
+    let err_ptr = err_ptr.address;
+
+    for (address, method, expected) in [
+        (ok_ptr, "is_ok", "true"),
+        (ok_ptr, "is_err", "false"),
+        (err_ptr, "is_ok", "false"),
+        (err_ptr, "is_err", "true"),
+    ] {
+        let value =
+            rudy_db::evaluate_synthetic_method(address, &result_type, method, &[], &resolver)?;
+        assert_eq!(
+            value,
+            Value::Scalar {
+                ty: "bool".to_string(),
+                value: expected.to_string()
+            },
+            "unexpected result for {method}() at {address:#x}"
+        );
+    }
+
     // Test slice synthetic methods
     let slice: &[i32] = &test_vec[..];
     let slice_ptr = variable_pointer!(debug_info, slice);
@@ -719,3 +2829,400 @@ fn test_synthetic_methods() -> Result<()> {
 
     Ok(())
 }
+
+/// Generic function used to check we find a breakpoint address per monomorphization.
+///
+/// Returns the line number of the `let line = ...` statement
+#[inline(never)]
+fn generic_breakpoint_target<T: Default>() -> u64 {
+    let value = T::default();
+    let line = line!() as u64;
+    std::hint::black_box(value);
+    line
+}
+
+#[test]
+fn test_resolve_all_breakpoint_addresses() -> Result<()> {
+    let (_guards, debug_info) = setup_db!();
+
+    let line = generic_breakpoint_target::<u32>();
+    let _ = generic_breakpoint_target::<String>();
+
+    let breakpoints = debug_info.find_all_addresses_from_source_location(file!(), line, None)?;
+    tracing::info!("{breakpoints:#?}");
+
+    let mut functions = breakpoints
+        .iter()
+        .map(|bp| bp.function.as_str())
+        .collect::<Vec<_>>();
+    functions.sort();
+    assert_eq!(
+        functions,
+        vec![
+            "dynamic_tests::generic_breakpoint_target<alloc::string::String>",
+            "dynamic_tests::generic_breakpoint_target<u32>",
+        ]
+    );
+    assert!(breakpoints.iter().all(|bp| !bp.inlined));
+
+    // the single-address lookup should pick one of these
+    let address = debug_info
+        .find_address_from_source_location(file!(), line, None)?
+        .expect("should resolve line")
+        .address;
+    assert!(breakpoints.iter().any(|bp| bp.address == address));
+
+    Ok(())
+}
+
+/// Function that is always inlined, used to check we find a breakpoint
+/// address for each inlined copy.
+///
+/// Returns the line number of the `let line = ...` statement
+#[inline(always)]
+fn inlined_breakpoint_target(value: u64) -> u64 {
+    let line = line!() as u64;
+    std::hint::black_box(value);
+    line
+}
+
+#[inline(never)]
+fn inlined_breakpoint_caller() -> u64 {
+    inlined_breakpoint_target(1) + inlined_breakpoint_target(2)
+}
+
+#[test]
+fn test_resolve_inlined_breakpoint_addresses() -> Result<()> {
+    let (_guards, debug_info) = setup_db!();
+
+    let line = inlined_breakpoint_target(0);
+    std::hint::black_box(inlined_breakpoint_caller());
+
+    let breakpoints = debug_info.find_all_addresses_from_source_location(file!(), line, None)?;
+    tracing::info!("{breakpoints:#?}");
+
+    // one copy for the call above, and two in `inlined_breakpoint_caller`
+    let mut functions = breakpoints
+        .iter()
+        .map(|bp| bp.function.as_str())
+        .collect::<Vec<_>>();
+    functions.sort();
+    assert_eq!(
+        functions,
+        vec![
+            "dynamic_tests::inlined_breakpoint_caller",
+            "dynamic_tests::inlined_breakpoint_caller",
+            "dynamic_tests::test_resolve_inlined_breakpoint_addresses",
+        ]
+    );
+    assert!(breakpoints.iter().all(|bp| bp.inlined));
+    // the addresses of the copies are all distinct
+    let mut addresses = breakpoints.iter().map(|bp| bp.address).collect::<Vec<_>>();
+    addresses.sort();
+    addresses.dedup();
+    assert_eq!(addresses.len(), breakpoints.len());
+
+    Ok(())
+}
+
+#[test]
+fn test_source_file_matching() -> Result<()> {
+    let (_guards, debug_info) = setup_db!();
+
+    let line = line!() as u64;
+
+    // unique suffix of the path
+    let address =
+        debug_info.find_address_from_source_location("tests/dynamic_tests.rs", line, None)?;
+    assert!(address.is_some(), "should resolve by path suffix");
+
+    // fuzzy match on the crate directory and file name
+    assert_eq!(
+        debug_info.find_address_from_source_location("rudy-db/dynamic_tests.rs", line, None)?,
+        address
+    );
+
+    // many crates have a `lib.rs`
+    let err = debug_info
+        .find_address_from_source_location("lib.rs", 1, None)
+        .expect_err("should be ambiguous");
+    assert!(
+        err.to_string().contains("ambiguous source file `lib.rs`"),
+        "unexpected error: {err}"
+    );
+
+    // std sources are remapped to the configured `rust-src` component
+    if let Some(rust_src) = rudy_db::RustSrc::detect() {
+        let string_rs = rust_src.dir.join("library/alloc/src/string.rs");
+        let string_rs = string_rs.to_str().unwrap();
+        let db = DebugDb::new().with_rust_src(rust_src);
+        let exe_path = std::env::current_exe().expect("Failed to get current exe path");
+        let debug_info = DebugInfo::new(&db, exe_path.to_str().unwrap())?;
+        assert!(
+            debug_info
+                .find_address_from_source_location(string_rs, 1, None)?
+                .is_some(),
+            "should resolve std source at {string_rs}"
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_source_file_inventory() -> Result<()> {
+    let (_guards, debug_info) = setup_db!();
+
+    let line = generic_breakpoint_target::<u32>();
+    let _ = generic_breakpoint_target::<String>();
+
+    let crates = debug_info.source_files();
+    let local = crates
+        .iter()
+        .find(|c| c.origin == SourceOrigin::Local && c.name.as_deref() == Some("dynamic_tests"))
+        .expect("should find the local crate");
+    assert!(
+        local
+            .files
+            .iter()
+            .any(|f| f.ends_with("tests/dynamic_tests.rs")),
+        "missing test file in {local:#?}"
+    );
+    let library = crates
+        .iter()
+        .find(|c| c.origin == SourceOrigin::Local && c.name.as_deref() == Some("rudy_db"))
+        .expect("should find the library under test");
+    assert!(
+        library
+            .files
+            .iter()
+            .any(|f| f.ends_with("src/debug_info.rs")),
+        "missing library file in {library:#?}"
+    );
+    assert!(
+        crates
+            .iter()
+            .any(|c| c.origin == SourceOrigin::Std && c.name.as_deref() == Some("alloc")),
+        "should find std crates"
+    );
+
+    // both monomorphizations are listed as a single function
+    let functions = debug_info.functions_in_file(file!())?;
+    let generic_fns = functions
+        .iter()
+        .filter(|f| f.name == "dynamic_tests::generic_breakpoint_target")
+        .collect::<Vec<_>>();
+    let [generic_fn] = generic_fns.as_slice() else {
+        panic!("expected a single `generic_breakpoint_target`, got: {generic_fns:#?}");
+    };
+    assert_eq!(generic_fn.start_line, line - 2);
+    assert_eq!(generic_fn.end_line, line + 3);
+    assert!(
+        functions
+            .iter()
+            .any(|f| f.name == "dynamic_tests::test_source_file_inventory")
+    );
+
+    let lines = debug_info.breakable_lines(file!())?;
+    assert!(lines.contains(&line), "line {line} should be breakable");
+    // the attribute above the function has no code
+    assert!(!lines.contains(&(line - 3)));
+
+    Ok(())
+}
+
+#[test]
+fn test_module_browsing() -> Result<()> {
+    let (_guards, debug_info) = setup_db!();
+
+    let _ = generic_breakpoint_target::<u32>();
+    let _ = generic_breakpoint_target::<String>();
+
+    let root = debug_info.module("").expect("should list crates");
+    assert!(root.modules.iter().any(|m| m == "dynamic_tests"));
+    assert!(root.modules.iter().any(|m| m == "alloc"));
+
+    let module = debug_info
+        .module("dynamic_tests")
+        .expect("should find the test crate");
+    assert_eq!(module.path, "dynamic_tests");
+    assert!(module.modules.iter().any(|m| m == "common"), "{module:#?}");
+    assert!(module.types.iter().any(|t| t.name == "TestSession"));
+    assert!(module.statics.iter().any(|s| s == "BASE_ADDRESS"));
+
+    let generic_fn = module
+        .functions
+        .iter()
+        .find(|f| f.name == "generic_breakpoint_target")
+        .expect("should find generic function");
+    assert_eq!(
+        generic_fn.instantiations,
+        vec![
+            "generic_breakpoint_target<alloc::string::String>",
+            "generic_breakpoint_target<u32>",
+        ]
+    );
+    let test_fn = module
+        .functions
+        .iter()
+        .find(|f| f.name == "test_module_browsing")
+        .expect("should find test function");
+    assert!(test_fn.instantiations.is_empty());
+
+    let vec_module = debug_info
+        .module("alloc::vec")
+        .expect("should find std module");
+    let vec_type = vec_module
+        .types
+        .iter()
+        .find(|t| t.name == "Vec")
+        .expect("should find Vec");
+    assert!(
+        vec_type
+            .instantiations
+            .iter()
+            .any(|i| i == "Vec<u8, alloc::alloc::Global>"),
+        "{vec_type:#?}"
+    );
+
+    assert!(debug_info.module("dynamic_tests::not_a_module").is_none());
+
+    Ok(())
+}
+
+trait Handler {
+    fn handle(&self, input: u32) -> u32;
+    fn name(&self) -> &'static str;
+}
+
+#[derive(Debug)]
+struct EchoHandler;
+
+struct DoubleHandler;
+
+impl Handler for EchoHandler {
+    #[inline(never)]
+    fn handle(&self, input: u32) -> u32 {
+        input
+    }
+
+    #[inline(never)]
+    fn name(&self) -> &'static str {
+        "echo"
+    }
+}
+
+impl Handler for DoubleHandler {
+    #[inline(never)]
+    fn handle(&self, input: u32) -> u32 {
+        input * 2
+    }
+
+    #[inline(never)]
+    fn name(&self) -> &'static str {
+        "double"
+    }
+}
+
+#[test]
+fn test_trait_impl_index() -> Result<()> {
+    let (_guards, debug_info) = setup_db!();
+
+    let handlers: Vec<Box<dyn Handler>> = vec![Box::new(EchoHandler), Box::new(DoubleHandler)];
+    for handler in &handlers {
+        std::hint::black_box((handler.name(), handler.handle(1)));
+    }
+    std::hint::black_box(format!("{EchoHandler:?}"));
+
+    let implementors = debug_info.implementors_of("Handler");
+    let summary = implementors
+        .iter()
+        .map(|imp| {
+            let methods = imp
+                .methods
+                .iter()
+                .map(|m| m.name.as_str())
+                .collect::<Vec<_>>();
+            let methods = methods.join(", ");
+            format!("impl {} for {}: {methods}", imp.trait_name, imp.type_name)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        summary,
+        vec![
+            "impl dynamic_tests::Handler for dynamic_tests::DoubleHandler: handle, name",
+            "impl dynamic_tests::Handler for dynamic_tests::EchoHandler: handle, name",
+        ]
+    );
+    assert!(
+        implementors
+            .iter()
+            .flat_map(|imp| &imp.methods)
+            .all(|m| m.address != 0)
+    );
+
+    let traits = debug_info
+        .traits_implemented_by("dynamic_tests::EchoHandler")
+        .into_iter()
+        .map(|imp| imp.trait_name)
+        .collect::<Vec<_>>();
+    assert_eq!(traits, vec!["core::fmt::Debug", "dynamic_tests::Handler"]);
+
+    // partial paths, with or without generic arguments
+    assert!(
+        debug_info
+            .traits_implemented_by("vec::Vec")
+            .iter()
+            .any(|imp| imp.trait_name == "core::ops::drop::Drop")
+    );
+    assert!(debug_info.implementors_of("andler").is_empty());
+
+    // impls found in the debug info and in symbols are listed once, with
+    // the methods from the symbols
+    let vec_impls = debug_info.traits_implemented_by("alloc::vec::Vec<u8>");
+    let mut pairs = BTreeSet::new();
+    for imp in &vec_impls {
+        assert!(
+            pairs.insert((imp.type_name.as_str(), imp.trait_name.as_str())),
+            "impl {} for {} is listed twice",
+            imp.trait_name,
+            imp.type_name
+        );
+    }
+    let debug_impl = vec_impls
+        .iter()
+        .find(|imp| imp.type_name == "alloc::vec::Vec" && imp.trait_name == "core::fmt::Debug")
+        .expect("Vec should implement Debug");
+    assert!(debug_impl.methods.iter().any(|m| m.name == "fmt"));
+
+    Ok(())
+}
+
+trait Tagged {}
+
+struct TaggedItem;
+
+impl Tagged for TaggedItem {}
+
+#[test]
+fn test_trait_impl_index_marker_trait() -> Result<()> {
+    let (_guards, debug_info) = setup_db!();
+
+    // a marker trait has no methods, so the impl is only found from its vtable
+    let tagged: &dyn Tagged = &TaggedItem;
+    std::hint::black_box(tagged);
+
+    let implementors = debug_info.implementors_of("dynamic_tests::Tagged");
+    assert_eq!(implementors.len(), 1);
+    assert_eq!(implementors[0].type_name, "dynamic_tests::TaggedItem");
+    assert!(implementors[0].methods.is_empty());
+
+    assert!(
+        debug_info
+            .traits_implemented_by("TaggedItem")
+            .iter()
+            .any(|imp| imp.trait_name == "dynamic_tests::Tagged")
+    );
+
+    Ok(())
+}
@@ -85,6 +85,9 @@ class RudyConnection:
 
         self.request_id += 1
         msg = {"type": "Command", "cmd": cmd, "args": args}
+        stop_id = _get_stop_id(debugger)
+        if stop_id is not None:
+            msg["stop_id"] = stop_id
 
         self._send_message(msg)
 
@@ -146,6 +149,8 @@ class RudyConnection:
                 return self._handle_allocate_memory(event_msg, target)
             elif event == "WriteMemory":
                 return self._handle_write_memory(event_msg, target)
+            elif event == "IsReadable":
+                return self._handle_is_readable(event_msg, process)
             else:
                 return {
                     "type": "EventResponse",
@@ -847,6 +852,41 @@ class RudyConnection:
                 "message": f"Memory write error: {e}",
             }
 
+    def _handle_is_readable(self, event_msg: dict, process) -> dict:
+        """Handle IsReadable event"""
+        address = event_msg.get("address", 0)
+        size = event_msg.get("size", 0)
+
+        try:
+            end = address + size
+            readable = True
+            # the range may span several memory regions
+            while address < end:
+                region = lldb.SBMemoryRegionInfo()
+                error = process.GetMemoryRegionInfo(address, region)
+                if not error.Success():
+                    return {
+                        "type": "EventResponse",
+                        "event": "Error",
+                        "message": f"Memory region query failed: {error.GetCString()}",
+                    }
+                if not region.IsReadable() or region.GetRegionEnd() <= address:
+                    readable = False
+                    break
+                address = region.GetRegionEnd()
+
+            return {
+                "type": "EventResponse",
+                "event": "Readable",
+                "readable": readable,
+            }
+        except Exception as e:
+            return {
+                "type": "EventResponse",
+                "event": "Error",
+                "message": f"Memory region query error: {e}",
+            }
+
     def __del__(self):
         """Close the connection"""
         try:
@@ -1003,6 +1043,21 @@ def _get_connection(debugger) -> Optional[RudyConnection]:
         return None
 
 
+def _get_stop_id(debugger):
+    """Identify the current stop, so the server knows when cached memory is stale
+
+    Includes stops for expression evaluation, since those run code that can
+    change memory.
+    """
+    try:
+        process = debugger.GetSelectedTarget().GetProcess()
+        if process and process.IsValid():
+            return process.GetStopID(True)
+    except Exception as e:
+        debug_print(f"Failed to get stop id: {e}")
+    return None
+
+
 def rudy_command(debugger, command, result, internal_dict):
     """Main Rudy command handler"""
     args = command.strip().split()
@@ -1012,7 +1067,11 @@ def rudy_command(debugger, command, result, internal_dict):
         print("  eval <expression>     - Evaluate a Rust expression")
         print("  methods <expression>  - List all methods for a type or expression")
         print("  functions [pattern]   - List all functions or search by pattern")
+        print("  ls [module::path]     - List the contents of a module")
         print("  print <expression>    - Pretty print a Rust expression")
+        print("  set <expr> = <value>  - Assign a value to a variable or field")
+        print("                          (None can't be written to niche Options like Option<bool>,")
+        print("                           Option<char> or Option<Ordering>)")
         print("  status                - Show Rudy server status")
         return
 
@@ -1113,6 +1172,25 @@ def rudy_command(debugger, command, result, internal_dict):
                                 print(f"  {name}: {signature}{callable_str}")
                 else:
                     print("No functions found")
+
+            # Special formatting for ls command
+            elif subcommand == "ls" and isinstance(result_data, dict):
+                path = result_data.get("path", "")
+                print(f"{path or '(crates)'}:")
+                for module in result_data.get("modules", []):
+                    print(f"  mod {module}")
+                for kind, key in (("type", "types"), ("fn", "functions")):
+                    for item in result_data.get(key, []):
+                        name = item.get("name", "")
+                        instantiations = item.get("instantiations", [])
+                        if instantiations:
+                            print(f"  {kind} {name} ({len(instantiations)} instantiations)")
+                            for instantiation in instantiations:
+                                print(f"      {instantiation}")
+                        else:
+                            print(f"  {kind} {name}")
+                for static in result_data.get("statics", []):
+                    print(f"  static {static}")
             elif isinstance(result_data, dict):
                 for key, value in result_data.items():
                     print(f"{key}: {value}")
@@ -1134,7 +1212,9 @@ def __lldb_init_module(debugger, internal_dict):
     print("Available commands:")
     print("  rd eval <expression>    - Evaluate Rust expressions")
     print("  rd functions [pattern]  - List all functions or search by pattern")
+    print("  rd ls [module::path]    - List the contents of a module")
     print("  rd print <expression>   - Pretty print Rust values")
+    print("  rd set <expr> = <value> - Assign to variables and fields")
     print("  rd status               - Check server status")
     print("")
     print(f"Server: {RUDY_HOST}:{RUDY_PORT}")
@@ -8,8 +8,8 @@ use std::{cell::RefCell, collections::BTreeMap};
 use anyhow::{Context, Result, anyhow};
 use itertools::Itertools;
 use rudy_db::{
-    DataResolver, DebugInfo, TypedPointer, Value, evaluate_synthetic_method, get_synthetic_methods,
-    rudy_dwarf::types::DieTypeDefinition,
+    CachingResolver, DataResolver, DebugInfo, ReadOptions, TypedPointer, Value,
+    get_synthetic_methods, rudy_dwarf::types::DieTypeDefinition,
 };
 use rudy_parser::Expression;
 use rudy_types::{Layout, Location, StdLayout};
@@ -116,20 +116,34 @@ impl<'conn> DataResolver for RemoteDataAccess<'conn> {
             _ => Err(anyhow!("Unexpected response type for WriteMemory")),
         }
     }
+
+    fn is_readable(&self, address: u64, size: usize) -> bool {
+        let event = EventRequest::IsReadable { address, size };
+        match self.conn.borrow_mut().send_event_request(event) {
+            Ok(EventResponseData::Readable { readable }) => readable,
+            // if we can't tell, let the read itself fail
+            response => {
+                tracing::debug!("failed to check {address:#x}+{size} is readable: {response:?}");
+                true
+            }
+        }
+    }
 }
 
 pub struct EvalContext<'a> {
     /// Debug information for the current binary
     debug_info: DebugInfo<'a>,
-    conn: RemoteDataAccess<'a>,
+    /// Reads through the connection's memory cache
+    conn: CachingResolver<RemoteDataAccess<'a>>,
     pc: Option<u64>,
 }
 
 impl<'a> EvalContext<'a> {
     pub fn new(debug_info: DebugInfo<'a>, conn: &'a mut ClientConnection) -> Self {
+        let cache = conn.memory_cache().clone();
         Self {
             debug_info,
-            conn: RemoteDataAccess::new(conn),
+            conn: CachingResolver::with_cache(RemoteDataAccess::new(conn), cache),
             pc: None, // Program counter will be set when evaluating expressions
         }
     }
@@ -140,11 +154,8 @@ impl<'a> EvalContext<'a> {
             Ok(pc)
         } else {
             // Fetch the current program counter from the client connection
-            let EventResponseData::FrameInfo { pc, .. } = self
-                .conn
-                .conn
-                .borrow_mut()
-                .send_event_request(EventRequest::GetFrameInfo)?
+            let EventResponseData::FrameInfo { pc, .. } =
+                self.send_event_request(EventRequest::GetFrameInfo)?
             else {
                 return Err(anyhow!("Unexpected response type for GetFrameInfo"));
             };
@@ -153,13 +164,36 @@ impl<'a> EvalContext<'a> {
         }
     }
 
+    /// Send an event to the client
+    ///
+    /// Events that run code in the target drop the memory cache, since that
+    /// code may have changed any memory.
+    fn send_event_request(&self, event: EventRequest) -> Result<EventResponseData> {
+        let runs_code = matches!(
+            event,
+            EventRequest::ExecuteMethod { .. }
+                | EventRequest::ExecuteFunction { .. }
+                | EventRequest::EvaluateLLDBExpression { .. }
+        );
+        let response = self
+            .conn
+            .inner()
+            .conn
+            .borrow_mut()
+            .send_event_request(event);
+        if runs_code {
+            self.conn.invalidate();
+        }
+        response
+    }
+
     /// Check if a name is a variable in the current LLDB context
     pub fn is_variable(&mut self, name: &str) -> bool {
         let event = EventRequest::GetVariableType {
             name: name.to_string(),
         };
 
-        if let Ok(response) = self.conn.conn.borrow_mut().send_event_request(event) {
+        if let Ok(response) = self.send_event_request(event) {
             matches!(
                 response,
                 EventResponseData::VariableTypeResult { type_name: Some(_) }
@@ -171,74 +205,11 @@ impl<'a> EvalContext<'a> {
 
     /// Convert a TypedPointer to a final EvalResult by reading and formatting the value
     fn pointer_to_result(&mut self, pointer: &TypedPointer) -> Result<EvalResult> {
-        let mut value = self.debug_info.read_pointer(pointer, &self.conn)?;
-
-        value = self.read_pointer_recursive(&value, 8)?;
+        let value = self
+            .debug_info
+            .read_value(pointer, DISPLAY_READ_OPTIONS, &self.conn)?;
 
-        Ok(EvalResult {
-            value: format_value(&value),
-            type_name: pointer.type_def.display_name(),
-        })
-    }
-
-    fn read_pointer_recursive(&mut self, value: &Value, max_depth: usize) -> Result<Value> {
-        if max_depth == 0 {
-            return Ok(value.clone()); // Stop recursion at max depth
-        }
-        match value {
-            Value::Pointer(typed_pointer) => {
-                let value = self.debug_info.read_pointer(typed_pointer, &self.conn)?;
-                self.read_pointer_recursive(&value, max_depth - 1)
-            }
-            v @ Value::Scalar { .. } => Ok(v.clone()),
-            Value::Array { ty, items } => {
-                let items = items
-                    .iter()
-                    .map(|v| self.read_pointer_recursive(v, max_depth - 1))
-                    .collect::<Result<Vec<_>, _>>()?;
-                Ok(Value::Array {
-                    ty: ty.clone(),
-                    items,
-                })
-            }
-            Value::Struct { ty, fields } => {
-                let fields = fields
-                    .iter()
-                    .map(|(k, v)| {
-                        self.read_pointer_recursive(v, max_depth - 1)
-                            .map(|v| (k.clone(), v))
-                    })
-                    .collect::<Result<BTreeMap<_, _>, _>>()?;
-                Ok(Value::Struct {
-                    ty: ty.clone(),
-                    fields,
-                })
-            }
-            Value::Tuple { ty, entries } => {
-                let entries = entries
-                    .iter()
-                    .map(|v| self.read_pointer_recursive(v, max_depth - 1))
-                    .collect::<Result<Vec<_>, _>>()?;
-                Ok(Value::Tuple {
-                    ty: ty.clone(),
-                    entries,
-                })
-            }
-            Value::Map { ty, entries } => {
-                let entries = entries
-                    .iter()
-                    .map(|(k, v)| {
-                        let key = self.read_pointer_recursive(k, max_depth - 1)?;
-                        let value = self.read_pointer_recursive(v, max_depth - 1)?;
-                        Ok((key, value))
-                    })
-                    .collect::<Result<Vec<_>>>()?;
-                Ok(Value::Map {
-                    ty: ty.clone(),
-                    entries,
-                })
-            }
-        }
+        Ok(EvalResult::new(pointer.type_def.display_name(), value))
     }
 
     /// Evaluates an expression, potentially generating events for the client
@@ -256,14 +227,10 @@ impl<'a> EvalContext<'a> {
             )),
             Expression::FieldAccess { base, field } => self.evaluate_field_access(base, field),
             Expression::Index { base, index } => self.evaluate_index(base, index),
-            Expression::NumberLiteral(value) => Ok(EvalResult {
-                value: value.to_string(),
-                type_name: "u64".to_string(),
-            }),
-            Expression::StringLiteral(value) => Ok(EvalResult {
-                value: format!("\"{value}\""),
-                type_name: "String".to_string(),
-            }),
+            Expression::NumberLiteral(value) => Ok(EvalResult::scalar("u64", value.to_string())),
+            Expression::StringLiteral(value) => {
+                Ok(EvalResult::scalar("String", format!("\"{value}\"")))
+            }
             Expression::Parenthesized(inner) => self.evaluate(inner),
             Expression::Deref(_) => Err(anyhow!(
                 "Pointer dereferencing not supported - values are automatically dereferenced"
@@ -277,9 +244,23 @@ impl<'a> EvalContext<'a> {
             Expression::FunctionCall { function, args } => {
                 self.evaluate_function_call(function, args)
             }
+            Expression::Range { .. } => Err(anyhow!(
+                "Ranges ('{expr}') can only be passed to methods, e.g. `map.range(1..10)`"
+            )),
         }
     }
 
+    /// Assigns a literal to the place `target` evaluates to, returning its new value
+    pub fn assign(&mut self, target: &Expression, literal: &str) -> Result<EvalResult> {
+        let pointer = self
+            .evaluate_to_ref(target)
+            .with_context(|| format!("Cannot assign to {target}"))?;
+        self.debug_info
+            .write_literal(&pointer, literal, &self.conn)
+            .with_context(|| format!("Failed to assign {} to {target}", literal.trim()))?;
+        self.pointer_to_result(&pointer)
+    }
+
     /// Evaluates an expression to a TypedPointer (for intermediate computation)
     pub fn evaluate_to_ref(&mut self, expr: &Expression) -> Result<TypedPointer> {
         match expr {
@@ -408,7 +389,7 @@ impl<'a> EvalContext<'a> {
             return_type_size,
         };
 
-        let response = self.conn.conn.borrow_mut().send_event_request(event)?;
+        let response = self.send_event_request(event)?;
 
         match response {
             EventResponseData::MethodResult { result } => match result {
@@ -500,7 +481,7 @@ impl<'a> EvalContext<'a> {
             return_type_size,
         };
 
-        let response = self.conn.conn.borrow_mut().send_event_request(event)?;
+        let response = self.send_event_request(event)?;
 
         match response {
             EventResponseData::FunctionResult { result } => match result {
@@ -566,8 +547,7 @@ impl<'a> EvalContext<'a> {
         // First evaluate the base expression to a TypedPointer
         let base_ref = self.evaluate_to_ref(base)?;
 
-        self.debug_info
-            .get_struct_field(base_ref.address, &base_ref.type_def, field)
+        self.debug_info.get_field(&base_ref, field, &self.conn)
     }
 
     fn evaluate_index_to_ref(
@@ -648,7 +628,7 @@ impl<'a> EvalContext<'a> {
     fn supports_string_indexing(&self, type_def: &Layout<impl Location>) -> bool {
         match type_def {
             Layout::Std(std_def) => {
-                matches!(std_def, StdLayout::Map(_))
+                matches!(std_def, StdLayout::Map(_) | StdLayout::Set(_))
             }
             _ => false,
         }
@@ -748,33 +728,70 @@ impl<'a> EvalContext<'a> {
                 return Err(anyhow!("Method {}() takes no arguments", method));
             }
 
-            // Convert arguments to Values (currently we don't support args for synthetic methods)
-            let arg_values = Vec::new();
+            // Convert arguments to Values
+            let arg_values = args
+                .iter()
+                .map(|arg| self.evaluate_synthetic_argument(arg))
+                .collect::<Result<Vec<_>>>()?;
 
             // Evaluate the synthetic method
-            let result_value = evaluate_synthetic_method(
-                base_ref.address,
-                base_type,
+            let result_value = self.debug_info.evaluate_synthetic_method_with_options(
+                &base_ref,
                 method,
                 &arg_values,
+                DISPLAY_READ_OPTIONS,
                 &self.conn,
             )?;
+            // e.g. the entries found by `BTreeMap::range`
+            let result_value =
+                self.debug_info
+                    .read_pointers(result_value, DISPLAY_READ_OPTIONS, &self.conn)?;
 
             // Convert Value to EvalResult
-            Ok(EvalResult {
-                value: format_value(&result_value),
-                type_name: match &result_value {
-                    Value::Scalar { ty, .. } => ty.clone(),
-                    Value::Pointer(ptr) => ptr.type_def.display_name(),
-                    _ => "unknown".to_string(),
-                },
-            })
+            let type_name = match &result_value {
+                Value::Scalar { ty, .. }
+                | Value::Map { ty, .. }
+                | Value::Enum { ty, .. }
+                | Value::Invalid { ty, .. } => ty.clone(),
+                Value::Pointer(ptr) => ptr.type_def.display_name(),
+                _ => "unknown".to_string(),
+            };
+            Ok(EvalResult::new(type_name, result_value))
         } else {
             // Try to execute the real method
             self.execute_real_method(base_ref, method, args)
         }
     }
 
+    /// Evaluates an argument to a synthetic method, turning ranges into the
+    /// struct a `Range` would be read as
+    fn evaluate_synthetic_argument(&mut self, arg: &Expression) -> Result<Value> {
+        let Expression::Range { start, end } = arg else {
+            let result = self.evaluate(arg)?;
+            return Ok(Value::Scalar {
+                ty: result.type_name,
+                value: result.value,
+            });
+        };
+
+        let ty = match (start, end) {
+            (Some(_), Some(_)) => "Range",
+            (Some(_), None) => "RangeFrom",
+            (None, Some(_)) => "RangeTo",
+            (None, None) => "RangeFull",
+        };
+        let mut fields = BTreeMap::new();
+        for (name, bound) in [("start", start), ("end", end)] {
+            if let Some(bound) = bound {
+                fields.insert(name.to_string(), self.evaluate_synthetic_argument(bound)?);
+            }
+        }
+        Ok(Value::Struct {
+            ty: ty.to_string(),
+            fields,
+        })
+    }
+
     /// Execute a real method by calling it via LLDB
     fn execute_real_method(
         &mut self,
@@ -843,9 +860,9 @@ impl<'a> EvalContext<'a> {
                         String::new()
                     };
                     return Err(anyhow!(
-                        "Failed to convert argument {} ({}){}: {}",
+                        "Failed to convert argument {} ({:?}){}: {}",
                         i + 1,
-                        format!("{:?}", arg_expr),
+                        arg_expr,
                         param_info,
                         e
                     ));
@@ -867,14 +884,13 @@ impl<'a> EvalContext<'a> {
             return_type_size,
         };
 
-        let response = self.conn.conn.borrow_mut().send_event_request(event)?;
+        let response = self.send_event_request(event)?;
 
         match response {
             EventResponseData::MethodResult { result } => match result {
-                MethodCallResult::SimpleValue { value, return_type } => Ok(EvalResult {
-                    value: value.to_string(),
-                    type_name: return_type,
-                }),
+                MethodCallResult::SimpleValue { value, return_type } => {
+                    Ok(EvalResult::scalar(return_type, value.to_string()))
+                }
                 MethodCallResult::ComplexPointer {
                     address,
                     size: _,
@@ -891,10 +907,10 @@ impl<'a> EvalContext<'a> {
                         self.pointer_to_result(&typed_pointer)
                     } else {
                         // Fallback: no type definition available
-                        Ok(EvalResult {
-                            value: format!("<complex value at {address:#x}>"),
-                            type_name: "unknown".to_string(),
-                        })
+                        Ok(EvalResult::scalar(
+                            "unknown",
+                            format!("<complex value at {address:#x}>"),
+                        ))
                     }
                 }
             },
@@ -1004,14 +1020,13 @@ impl<'a> EvalContext<'a> {
             return_type_size,
         };
 
-        let response = self.conn.conn.borrow_mut().send_event_request(event)?;
+        let response = self.send_event_request(event)?;
 
         match response {
             EventResponseData::FunctionResult { result } => match result {
-                MethodCallResult::SimpleValue { value, return_type } => Ok(EvalResult {
-                    value: value.to_string(),
-                    type_name: return_type,
-                }),
+                MethodCallResult::SimpleValue { value, return_type } => {
+                    Ok(EvalResult::scalar(return_type, value.to_string()))
+                }
                 MethodCallResult::ComplexPointer {
                     address,
                     size: _,
@@ -1028,10 +1043,10 @@ impl<'a> EvalContext<'a> {
                         self.pointer_to_result(&typed_pointer)
                     } else {
                         // Fallback: no type definition available
-                        Ok(EvalResult {
-                            value: format!("<complex value at {address:#x}>"),
-                            type_name: "unknown".to_string(),
-                        })
+                        Ok(EvalResult::scalar(
+                            "unknown",
+                            format!("<complex value at {address:#x}>"),
+                        ))
                     }
                 }
             },
@@ -1160,23 +1175,24 @@ pub struct EvalResult {
     /// The type of the value
     #[serde(rename = "type")]
     pub type_name: String,
+    /// The value itself
+    pub data: Value,
 }
 
-/// Information about a discovered function (for serialization to client)
-#[derive(Debug, serde::Serialize, Clone)]
-pub struct FunctionInfo {
-    /// The function name (e.g., "main")
-    pub name: String,
-    /// The full function name with module path (e.g., "simple_test::main")
-    pub full_name: String,
-    /// The function signature (e.g., "fn main()")
-    pub signature: String,
-    /// The function address in memory
-    pub address: u64,
-    /// Whether this function can be called
-    pub callable: bool,
-    /// The module path components (e.g., ["simple_test"])
-    pub module_path: Vec<String>,
+impl EvalResult {
+    fn new(type_name: String, data: Value) -> Self {
+        Self {
+            value: format_value(&data),
+            type_name,
+            data,
+        }
+    }
+
+    /// A result that was never read from memory, such as a literal
+    fn scalar(ty: impl Into<String>, value: String) -> Self {
+        let ty = ty.into();
+        Self::new(ty.clone(), Value::Scalar { ty, value })
+    }
 }
 
 fn indent(s: &str, level: usize) -> String {
@@ -1187,17 +1203,22 @@ fn indent(s: &str, level: usize) -> String {
         .join("\n")
 }
 
+/// How much of a value to read for display, keeping large
+/// collections from being read over the RPC channel
+const DISPLAY_READ_OPTIONS: ReadOptions = ReadOptions {
+    max_depth: 8,
+    max_items: 10,
+    max_string_bytes: 1024,
+    max_bytes_read: 1 << 20,
+};
+
 /// Format a Value for display
 fn format_value(value: &Value) -> String {
     match value {
         Value::Scalar { ty: _, value } => value.clone(),
         Value::Array { ty: _, items } => {
-            if items.len() <= 10 {
-                let items_str: Vec<String> = items.iter().map(format_value).collect();
-                format!("[\n{}\n]", indent(&items_str.join(",\n"), 1))
-            } else {
-                format!("[{} items]", items.len())
-            }
+            let items_str: Vec<String> = items.iter().map(format_value).collect();
+            format!("[\n{}\n]", indent(&items_str.join(",\n"), 1))
         }
         Value::Struct { ty, fields } => {
             let fields_str: Vec<String> = fields
@@ -1206,23 +1227,48 @@ fn format_value(value: &Value) -> String {
                 .collect();
             format!("{ty} {{\n{}\n}}", indent(&fields_str.join(",\n"), 1))
         }
-        Value::Map { entries, .. } => {
-            if entries.len() <= 10 {
-                let fields_str: Vec<String> = entries
-                    .iter()
-                    .map(|(k, v)| format!("{}: {}", format_value(k), format_value(v)))
-                    .collect();
-                format!("{{\n{}\n}}", indent(&fields_str.join(",\n"), 1))
-            } else {
-                format!("{{ {} entries }}", entries.len())
+        Value::Map {
+            entries, truncated, ..
+        } => {
+            let mut fields_str: Vec<String> = entries
+                .iter()
+                .map(|(k, v)| format!("{}: {}", format_value(k), format_value(v)))
+                .collect();
+            if let Some(remaining) = truncated {
+                fields_str.push(rudy_db::truncation_marker(*remaining));
             }
+            format!("{{\n{}\n}}", indent(&fields_str.join(",\n"), 1))
+        }
+        Value::Set { items, .. } => {
+            let items_str: Vec<String> = items.iter().map(format_value).collect();
+            format!("{{\n{}\n}}", indent(&items_str.join(",\n"), 1))
         }
         Value::Tuple { ty, entries } => {
             let entries_str: Vec<String> = entries.iter().map(format_value).collect();
             format!("{ty} (\n{}\n)", indent(&entries_str.join(",\n"), 1))
         }
+        Value::Enum {
+            variant, payload, ..
+        } => match payload.as_deref() {
+            None => variant.clone(),
+            Some(Value::Tuple { entries, .. }) => {
+                let entries_str: Vec<String> = entries.iter().map(format_value).collect();
+                format!("{variant}(\n{}\n)", indent(&entries_str.join(",\n"), 1))
+            }
+            Some(Value::Struct { fields, .. }) => {
+                let fields_str: Vec<String> = fields
+                    .iter()
+                    .map(|(k, v)| format!("{}: {}", k, format_value(v)))
+                    .collect();
+                format!("{variant} {{\n{}\n}}", indent(&fields_str.join(",\n"), 1))
+            }
+            Some(payload) => format!("{variant}({})", format_value(payload)),
+        },
+        Value::Truncated { remaining } => rudy_db::truncation_marker(*remaining),
         Value::Pointer(ptr) => {
             format!("<{} @ {:#x}>", ptr.type_def.display_name(), ptr.address)
         }
+        Value::Invalid { reason, .. } => format!("<invalid: {reason}>"),
+        Value::Suspect { reason, value } => format!("{} <suspect: {reason}>", format_value(value)),
     }
 }
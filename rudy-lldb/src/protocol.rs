@@ -48,7 +48,15 @@ pub enum ClientMessage {
     /// Initialize a new session with the binary path
     Init { binary_path: String },
     /// Execute a command
-    Command { cmd: String, args: Vec<String> },
+    Command {
+        cmd: String,
+        args: Vec<String>,
+        /// Identifies the stop the target is at, changing whenever it
+        /// resumes, so that memory cached at an earlier stop is discarded.
+        /// Without it, nothing is cached between commands.
+        #[serde(default)]
+        stop_id: Option<u64>,
+    },
     /// Response to a server event request
     EventResponse {
         #[serde(flatten)]
@@ -124,6 +132,8 @@ pub enum EventRequest {
     AllocateMemory { size: usize },
     /// Write data to memory in the target process
     WriteMemory { address: u64, data: Vec<u8> },
+    /// Check whether a range of memory is mapped and readable
+    IsReadable { address: u64, size: usize },
 }
 
 impl fmt::Debug for EventRequest {
@@ -183,6 +193,11 @@ impl fmt::Debug for EventRequest {
                 .field("address", &format!("{address:#x}"))
                 .field("data_len", &data.len())
                 .finish(),
+            Self::IsReadable { address, size } => f
+                .debug_struct("IsReadable")
+                .field("address", &format!("{address:#x}"))
+                .field("size", size)
+                .finish(),
         }
     }
 }
@@ -217,6 +232,8 @@ pub enum EventResponseData {
     MemoryAllocated { address: u64 },
     /// Memory write confirmation
     MemoryWritten,
+    /// Whether a range of memory is readable
+    Readable { readable: bool },
     /// Generic error response
     Error { message: String },
 }
@@ -265,6 +282,10 @@ impl fmt::Debug for EventResponseData {
                 .field("address", &format!("{address:#x}"))
                 .finish(),
             Self::MemoryWritten => f.debug_struct("MemoryWritten").finish(),
+            Self::Readable { readable } => f
+                .debug_struct("Readable")
+                .field("readable", readable)
+                .finish(),
             Self::Error { message } => f.debug_struct("Error").field("message", message).finish(),
         }
     }
@@ -279,11 +300,20 @@ mod tests {
         let msg = ClientMessage::Command {
             cmd: "eval".to_string(),
             args: vec!["foo.bar".to_string()],
+            stop_id: Some(3),
         };
 
         let json = serde_json::to_string(&msg).unwrap();
         assert!(json.contains(r#""type":"Command""#));
         assert!(json.contains(r#""cmd":"eval""#));
+        assert!(json.contains(r#""stop_id":3"#));
+    }
+
+    #[test]
+    fn test_client_command_without_stop_id() {
+        let msg: ClientMessage =
+            serde_json::from_str(r#"{"type":"Command","cmd":"shutdown","args":[]}"#).unwrap();
+        assert!(matches!(msg, ClientMessage::Command { stop_id: None, .. }));
     }
 
     #[test]
@@ -6,7 +6,7 @@ use std::{
 };
 
 use anyhow::{Context, Result, anyhow};
-use rudy_db::{DebugDb, DebugInfo};
+use rudy_db::{DebugDb, DebugInfo, DiscoveredFunction, DiscoveredMethod, MemoryCache, RustSrc};
 use rudy_parser::parse_expression;
 use tracing::{debug, error, info, trace, warn};
 
@@ -22,7 +22,10 @@ pub fn run_server(host: &str, port: u16) -> Result<()> {
     debug!("Listening on {addr}");
 
     // create a new debug database instance
-    let db = DebugDb::new();
+    let mut db = DebugDb::new();
+    if let Some(rust_src) = RustSrc::detect() {
+        db = db.with_rust_src(rust_src);
+    }
 
     let mut session_id = 0;
 
@@ -48,6 +51,9 @@ pub struct ClientConnection {
     reader: BufReader<std::net::TcpStream>,
     writer: std::net::TcpStream,
     line_buffer: String,
+    /// Target memory read while it has been stopped at `stop_id`
+    memory_cache: MemoryCache,
+    stop_id: Option<u64>,
 }
 
 impl ClientConnection {
@@ -58,9 +64,16 @@ impl ClientConnection {
             reader,
             writer: stream,
             line_buffer: String::new(),
+            memory_cache: MemoryCache::default(),
+            stop_id: None,
         }
     }
 
+    /// Memory read from the target at its current stop
+    pub fn memory_cache(&self) -> &MemoryCache {
+        &self.memory_cache
+    }
+
     pub fn read_next_message(&mut self) -> Result<Option<ClientMessage>> {
         self.line_buffer.clear();
         let bytes_read = self.reader.read_line(&mut self.line_buffer)?;
@@ -154,7 +167,14 @@ impl ClientConnection {
 
             // Handle the message
             let response = match msg {
-                ClientMessage::Command { cmd, args } => {
+                ClientMessage::Command { cmd, args, stop_id } => {
+                    // memory read at an earlier stop may have changed since
+                    // (edits made from LLDB at the same stop aren't seen, see
+                    // `MemoryCache::invalidate`)
+                    if stop_id.is_none() || stop_id != self.stop_id {
+                        self.memory_cache.invalidate();
+                    }
+                    self.stop_id = stop_id;
                     self.handle_command(&cmd, &args, &debug_info)
                 }
                 ClientMessage::Init { .. } => {
@@ -242,6 +262,32 @@ impl ClientConnection {
                 }
             }
 
+            "set" => {
+                // Join all arguments back together since LLDB splits on spaces
+                let input = args.join(" ");
+                let Some((target, literal)) = split_assignment(&input) else {
+                    return Ok(ServerMessage::Error {
+                        error: "Usage: set <expression> = <value>".to_string(),
+                        backtrace: None,
+                    });
+                };
+
+                let target = match parse_expression(target.trim()) {
+                    Ok(expr) => expr,
+                    Err(e) => {
+                        return Ok(e.into());
+                    }
+                };
+
+                let mut eval_context = EvalContext::new(debug_info.clone(), self);
+                match eval_context.assign(&target, literal) {
+                    Ok(value) => Ok(ServerMessage::Complete {
+                        result: serde_json::to_value(&value)?,
+                    }),
+                    Err(e) => Ok(e.into()),
+                }
+            }
+
             "methods" => {
                 if args.is_empty() {
                     return Ok(ServerMessage::Error {
@@ -273,6 +319,20 @@ impl ClientConnection {
                 }
             }
 
+            "ls" => {
+                // Join all arguments back together since LLDB splits on spaces
+                let path = args.join("");
+                match debug_info.module(&path) {
+                    Some(module) => Ok(ServerMessage::Complete {
+                        result: serde_json::to_value(&module)?,
+                    }),
+                    None => Ok(ServerMessage::Error {
+                        error: format!("No module found matching '{path}'"),
+                        backtrace: None,
+                    }),
+                }
+            }
+
             "shutdown" => {
                 info!("Received shutdown command (session: {})", self.session_id);
                 // Return success response before shutting down
@@ -310,23 +370,7 @@ impl ClientConnection {
                         // Not a variable, try to resolve as a type
                         if let Ok(Some(type_def)) = debug_info.lookup_type_by_name(var_name) {
                             tracing::debug!("Resolved '{}' as a type", var_name);
-                            let discovered_methods =
-                                debug_info.discover_methods_for_type(&type_def)?;
-
-                            // Convert DiscoveredMethod to MethodInfo
-                            let methods: Vec<MethodInfo> = discovered_methods
-                                .into_iter()
-                                .map(|dm| MethodInfo {
-                                    name: dm.name,
-                                    signature: dm.signature,
-                                    description: Some(format!(
-                                        "Method at address {:#x}",
-                                        dm.address
-                                    )),
-                                    callable: dm.callable,
-                                    is_synthetic: dm.is_synthetic,
-                                })
-                                .collect();
+                            let methods = debug_info.discover_methods_for_type(&type_def)?;
 
                             return Ok(MethodDiscoveryResult {
                                 type_name: var_name.clone(),
@@ -345,19 +389,7 @@ impl ClientConnection {
                     let type_name = expr.to_string();
                     if let Ok(Some(type_def)) = debug_info.lookup_type_by_name(&type_name) {
                         tracing::debug!("Resolved '{}' as a type", type_name);
-                        let discovered_methods = debug_info.discover_methods_for_type(&type_def)?;
-
-                        // Convert DiscoveredMethod to MethodInfo
-                        let methods: Vec<MethodInfo> = discovered_methods
-                            .into_iter()
-                            .map(|dm| MethodInfo {
-                                name: dm.name,
-                                signature: dm.signature,
-                                description: Some(format!("Method at address {:#x}", dm.address)),
-                                callable: dm.callable,
-                                is_synthetic: dm.is_synthetic,
-                            })
-                            .collect();
+                        let methods = debug_info.discover_methods_for_type(&type_def)?;
 
                         return Ok(MethodDiscoveryResult { type_name, methods });
                     } else {
@@ -372,21 +404,9 @@ impl ClientConnection {
             // For all other cases (composite expressions or variables), evaluate to get TypedPointer
             match eval_context.evaluate_to_ref(&expr) {
                 Ok(pointer) => {
-                    let discovered_methods = debug_info.discover_methods_for_pointer(&pointer)?;
+                    let methods = debug_info.discover_methods_for_pointer(&pointer)?;
                     let type_name = pointer.type_def.display_name();
 
-                    // Convert DiscoveredMethod to MethodInfo
-                    let methods: Vec<MethodInfo> = discovered_methods
-                        .into_iter()
-                        .map(|dm| MethodInfo {
-                            name: dm.name,
-                            signature: dm.signature,
-                            description: Some(format!("Method at address {:#x}", dm.address)),
-                            callable: dm.callable,
-                            is_synthetic: dm.is_synthetic,
-                        })
-                        .collect();
-
                     Ok(MethodDiscoveryResult { type_name, methods })
                 }
                 Err(e) => {
@@ -397,19 +417,7 @@ impl ClientConnection {
         } else {
             // If it doesn't parse as an expression, try as a direct type name
             if let Ok(Some(type_def)) = debug_info.lookup_type_by_name(input) {
-                let discovered_methods = debug_info.discover_methods_for_type(&type_def)?;
-
-                // Convert DiscoveredMethod to MethodInfo
-                let methods: Vec<MethodInfo> = discovered_methods
-                    .into_iter()
-                    .map(|dm| MethodInfo {
-                        name: dm.name,
-                        signature: dm.signature,
-                        description: Some(format!("Method at address {:#x}", dm.address)),
-                        callable: dm.callable,
-                        is_synthetic: dm.is_synthetic,
-                    })
-                    .collect();
+                let methods = debug_info.discover_methods_for_type(&type_def)?;
 
                 Ok(MethodDiscoveryResult {
                     type_name: input.to_string(),
@@ -429,59 +437,76 @@ impl ClientConnection {
         &mut self,
         args: &[String],
         debug_info: &DebugInfo,
-    ) -> Result<Vec<crate::evaluator::FunctionInfo>> {
+    ) -> Result<Vec<DiscoveredFunction>> {
         if args.is_empty() {
             // List all functions
-            let all_functions = debug_info.discover_all_functions()?;
-
-            // Convert to FunctionInfo for serialization
-            let function_list: Vec<crate::evaluator::FunctionInfo> = all_functions
-                .into_values()
-                .map(|discovered_func| crate::evaluator::FunctionInfo {
-                    name: discovered_func.name,
-                    full_name: discovered_func.full_name,
-                    signature: discovered_func.signature,
-                    address: discovered_func.address,
-                    callable: discovered_func.callable,
-                    module_path: discovered_func.module_path,
-                })
-                .collect();
-
-            Ok(function_list)
+            Ok(debug_info.discover_all_functions()?.into_values().collect())
         } else {
             // Search for functions matching the pattern
-            let pattern = &args[0];
-            let discovered_functions = debug_info.discover_functions(pattern)?;
-
-            // Convert to FunctionInfo for serialization
-            let function_list: Vec<crate::evaluator::FunctionInfo> = discovered_functions
-                .into_iter()
-                .map(|discovered_func| crate::evaluator::FunctionInfo {
-                    name: discovered_func.name,
-                    full_name: discovered_func.full_name,
-                    signature: discovered_func.signature,
-                    address: discovered_func.address,
-                    callable: discovered_func.callable,
-                    module_path: discovered_func.module_path,
-                })
-                .collect();
+            debug_info.discover_functions(&args[0])
+        }
+    }
+}
 
-            Ok(function_list)
+/// Splits `target = value` on the first `=` that isn't nested in brackets or
+/// quotes (as in `map["a=b"] = 1`) or part of an operator like `==`
+fn split_assignment(input: &str) -> Option<(&str, &str)> {
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, c) in input.char_indices() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '"' | '\'' => quote = Some(c),
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            '=' if depth == 0 => {
+                let before = input[..i].chars().next_back();
+                let after = input[i + 1..].chars().next();
+                let operator = matches!(before, Some('=' | '!' | '<' | '>'))
+                    || matches!(after, Some('=' | '>'));
+                if !operator {
+                    return Some((&input[..i], &input[i + 1..]));
+                }
+            }
+            _ => {}
         }
     }
+    None
 }
 
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct MethodDiscoveryResult {
     pub type_name: String,
-    pub methods: Vec<MethodInfo>,
+    pub methods: Vec<DiscoveredMethod>,
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
-pub struct MethodInfo {
-    pub name: String,
-    pub signature: String,
-    pub description: Option<String>,
-    pub callable: bool,     // Whether we can actually call this method
-    pub is_synthetic: bool, // Whether this is a synthetic method (computed, not from debug info)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_assignment() {
+        assert_eq!(split_assignment("x = 1"), Some(("x ", " 1")));
+        assert_eq!(
+            split_assignment("map[\"a=b\"] = 1"),
+            Some(("map[\"a=b\"] ", " 1"))
+        );
+        assert_eq!(
+            split_assignment("pair = ('=', 2)"),
+            Some(("pair ", " ('=', 2)"))
+        );
+        assert_eq!(split_assignment("x == 1"), None);
+        assert_eq!(split_assignment("x >= 1"), None);
+        assert_eq!(split_assignment("x"), None);
+    }
 }